@@ -12,6 +12,13 @@ pub struct Config {
     pub gcs_bucket_name: String,
     pub gcs_project_id: String,
     pub google_application_credentials: Option<String>,
+    // Correo saliente; sin servidor SMTP los correos se escriben en `mail_outbox_dir`
+    pub smtp_host: Option<String>,
+    pub smtp_port: u16,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    pub mail_from: String,
+    pub mail_outbox_dir: String,
 }
 
 impl Config {
@@ -32,6 +39,16 @@ impl Config {
             gcs_bucket_name: env::var("GCS_BUCKET_NAME")?,
             gcs_project_id: env::var("GCS_PROJECT_ID")?,
             google_application_credentials: env::var("GOOGLE_APPLICATION_CREDENTIALS").ok(),
+            smtp_host: env::var("SMTP_HOST").ok(),
+            smtp_port: env::var("SMTP_PORT")
+                .ok()
+                .and_then(|port| port.parse().ok())
+                .unwrap_or(587),
+            smtp_username: env::var("SMTP_USERNAME").ok(),
+            smtp_password: env::var("SMTP_PASSWORD").ok(),
+            mail_from: env::var("MAIL_FROM")
+                .unwrap_or_else(|_| "Jira Clone <no-reply@localhost>".to_string()),
+            mail_outbox_dir: env::var("MAIL_OUTBOX_DIR").unwrap_or_else(|_| "outbox".to_string()),
         })
     }
 
@@ -55,9 +72,26 @@ impl Config {
                 .get("SERVER_ADDRESS")
                 .unwrap_or_else(|| "127.0.0.1:8000".to_string()),
             cors_origins,
-            gcs_bucket_name: secrets.get("GCS_BUCKET_NAME").ok_or(env::VarError::NotPresent)?,
-            gcs_project_id: secrets.get("GCS_PROJECT_ID").ok_or(env::VarError::NotPresent)?,
+            gcs_bucket_name: secrets
+                .get("GCS_BUCKET_NAME")
+                .ok_or(env::VarError::NotPresent)?,
+            gcs_project_id: secrets
+                .get("GCS_PROJECT_ID")
+                .ok_or(env::VarError::NotPresent)?,
             google_application_credentials: secrets.get("GOOGLE_APPLICATION_CREDENTIALS"),
+            smtp_host: secrets.get("SMTP_HOST"),
+            smtp_port: secrets
+                .get("SMTP_PORT")
+                .and_then(|port| port.parse().ok())
+                .unwrap_or(587),
+            smtp_username: secrets.get("SMTP_USERNAME"),
+            smtp_password: secrets.get("SMTP_PASSWORD"),
+            mail_from: secrets
+                .get("MAIL_FROM")
+                .unwrap_or_else(|| "Jira Clone <no-reply@localhost>".to_string()),
+            mail_outbox_dir: secrets
+                .get("MAIL_OUTBOX_DIR")
+                .unwrap_or_else(|| "outbox".to_string()),
         })
     }
 }
@@ -1,11 +1,17 @@
 // src/db.rs
 use crate::errors::AppError;
-use mongodb::{Client, Database, options::ClientOptions}; // Asegúrate de tener definido AppError
+use mongodb::{
+    Client, Database, IndexModel,
+    bson::{Document, doc},
+    options::{ClientOptions, IndexOptions},
+}; // Asegúrate de tener definido AppError
 
 #[derive(Clone)] // Para poder clonar y pasar el estado a los handlers de Axum
 pub struct DatabaseState {
     pub client: Client,
     pub db: Database,
+    // Las transacciones solo existen en un replica set o a través de mongos
+    pub supports_transactions: bool,
 }
 
 impl DatabaseState {
@@ -36,11 +42,259 @@ impl DatabaseState {
 
         tracing::info!("Conexión a MongoDB establecida exitosamente.");
 
+        let hello = client
+            .database("admin")
+            .run_command(doc! {"hello": 1})
+            .await
+            .map_err(|e| AppError::DatabaseConnectionError(e.to_string()))?;
+        let supports_transactions = hello.contains_key("setName")
+            || hello.get_str("msg").is_ok_and(|msg| msg == "isdbgrid");
+
         let db = client.database(db_name);
-        Ok(Self { client, db })
+        Ok(Self {
+            client,
+            db,
+            supports_transactions,
+        })
     }
 
     pub fn get_db(&self) -> &Database {
         &self.db
     }
+
+    // Índices compuestos para los listados más frecuentes; crear un índice existente no hace nada
+    pub async fn ensure_indexes(&self) -> Result<(), AppError> {
+        let task_indexes = [
+            doc! {"project_id": 1, "rank": 1, "_id": 1},
+            doc! {"project_id": 1, "created_at": 1, "_id": 1},
+            doc! {"project_id": 1, "updated_at": 1, "_id": 1},
+            doc! {"project_id": 1, "status": 1, "rank": 1},
+            doc! {"project_id": 1, "assignee_id": 1, "rank": 1},
+            doc! {"project_id": 1, "reporter_id": 1, "rank": 1},
+        ]
+        .into_iter()
+        .map(|keys| IndexModel::builder().keys(keys).build())
+        // Índice de texto para la búsqueda; el título pesa más que la descripción
+        .chain([IndexModel::builder()
+            .keys(doc! {"title": "text", "description": "text"})
+            .options(
+                IndexOptions::builder()
+                    .name("tasks_text".to_string())
+                    .weights(doc! {"title": 3, "description": 1})
+                    .default_language("spanish".to_string())
+                    .build(),
+            )
+            .build()]);
+
+        // Las claves de tarea son únicas; las tareas antiguas no tienen clave
+        let key_indexes = [
+            IndexModel::builder()
+                .keys(doc! {"key": 1})
+                .options(
+                    IndexOptions::builder()
+                        .unique(true)
+                        .partial_filter_expression(doc! {"key": {"$type": "string"}})
+                        .build(),
+                )
+                .build(),
+            IndexModel::builder().keys(doc! {"key_aliases": 1}).build(),
+        ];
+
+        self.db
+            .collection::<Document>("tasks")
+            .create_indexes(task_indexes.chain(key_indexes))
+            .await
+            .map_err(|e| {
+                tracing::error!("Error al crear los índices de tareas: {}", e);
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        self.db
+            .collection::<Document>("comments")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! {"content": "text"})
+                    .options(
+                        IndexOptions::builder()
+                            .name("comments_text".to_string())
+                            .default_language("spanish".to_string())
+                            .build(),
+                    )
+                    .build(),
+            )
+            .await
+            .map_err(|e| {
+                tracing::error!("Error al crear los índices de comentarios: {}", e);
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        // Respuestas de cada comentario
+        self.db
+            .collection::<Document>("comments")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! {"parent_id": 1, "created_at": 1})
+                    .options(
+                        IndexOptions::builder()
+                            .partial_filter_expression(doc! {"parent_id": {"$exists": true}})
+                            .build(),
+                    )
+                    .build(),
+            )
+            .await
+            .map_err(|e| {
+                tracing::error!("Error al crear los índices de comentarios: {}", e);
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        // Hilos de una tarea paginados por fecha y cambios para la sincronización incremental
+        let comment_indexes = [
+            doc! {"task_id": 1, "created_at": 1, "_id": 1},
+            doc! {"task_id": 1, "changed_at": 1},
+        ]
+        .into_iter()
+        .map(|keys| IndexModel::builder().keys(keys).build());
+
+        self.db
+            .collection::<Document>("comments")
+            .create_indexes(comment_indexes)
+            .await
+            .map_err(|e| {
+                tracing::error!("Error al crear los índices de comentarios: {}", e);
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        // Un único rango de fechas por tarea. Si ya hay rangos repetidos el índice no se puede
+        // crear: se avisa (el informe de migración los lista) y se sigue arrancando
+        if let Err(e) = self
+            .db
+            .collection::<Document>("task_date_ranges")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! {"task_id": 1})
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await
+        {
+            tracing::error!(
+                "No se pudo crear el índice único de rangos de fechas (¿hay rangos repetidos?): {}",
+                e
+            );
+        }
+
+        // Una dependencia por pareja de tareas, y búsqueda por proyecto y por sucesora
+        self.db
+            .collection::<Document>("task_dependencies")
+            .create_indexes([
+                IndexModel::builder()
+                    .keys(doc! {"predecessor_id": 1, "successor_id": 1})
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+                IndexModel::builder().keys(doc! {"successor_id": 1}).build(),
+                IndexModel::builder().keys(doc! {"project_id": 1}).build(),
+            ])
+            .await
+            .map_err(|e| {
+                tracing::error!("Error al crear los índices de dependencias: {}", e);
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        // Un único calendario laboral por proyecto
+        self.db
+            .collection::<Document>("project_calendars")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! {"project_id": 1})
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await
+            .map_err(|e| {
+                tracing::error!("Error al crear los índices de calendarios: {}", e);
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        // Una clave de campo personalizado es única dentro de su proyecto
+        self.db
+            .collection::<Document>("custom_fields")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! {"project_id": 1, "key": 1})
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await
+            .map_err(|e| {
+                tracing::error!("Error al crear los índices de campos personalizados: {}", e);
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        // Historial por tarea en orden cronológico y actividad del proyecto paginada
+        let history_indexes = [
+            doc! {"task_id": 1, "created_at": 1},
+            doc! {"project_id": 1, "created_at": -1, "_id": -1},
+        ]
+        .into_iter()
+        .map(|keys| IndexModel::builder().keys(keys).build());
+
+        self.db
+            .collection::<Document>("task_history")
+            .create_indexes(history_indexes)
+            .await
+            .map_err(|e| {
+                tracing::error!("Error al crear los índices del historial: {}", e);
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        // Bandeja de cada usuario, de la más reciente a la más antigua
+        self.db
+            .collection::<Document>("notifications")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! {"user_id": 1, "created_at": -1, "_id": -1})
+                    .build(),
+            )
+            .await
+            .map_err(|e| {
+                tracing::error!("Error al crear los índices de notificaciones: {}", e);
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        // Avisos pendientes de enviar por correo
+        self.db
+            .collection::<Document>("notifications")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! {"email": 1, "created_at": 1})
+                    .options(
+                        IndexOptions::builder()
+                            .partial_filter_expression(doc! {"email": {"$exists": true}})
+                            .build(),
+                    )
+                    .build(),
+            )
+            .await
+            .map_err(|e| {
+                tracing::error!("Error al crear los índices de notificaciones: {}", e);
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        // Un único documento de preferencias por usuario
+        self.db
+            .collection::<Document>("notification_preferences")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! {"user_id": 1})
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await
+            .map_err(|e| {
+                tracing::error!("Error al crear los índices de preferencias: {}", e);
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(())
+    }
 }
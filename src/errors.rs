@@ -36,6 +36,13 @@ pub enum AppError {
     #[error("No autorizado: {0}")]
     Unauthorized(String),
 
+    #[error("Conflicto: {0}")]
+    Conflict(String),
+
+    // Lleva el documento actual para que el cliente pueda fusionar sus cambios
+    #[error("La versión del recurso no coincide")]
+    PreconditionFailed(serde_json::Value),
+
     #[error("Error interno del servidor")]
     InternalServerError,
 
@@ -43,10 +50,24 @@ pub enum AppError {
     JwtError(#[from] jsonwebtoken::errors::Error),
 }
 
+impl AppError {
+    // 412 con el documento tal como está ahora en la base de datos
+    pub fn precondition_failed<T: serde::Serialize>(current: &T) -> Self {
+        AppError::PreconditionFailed(serde_json::to_value(current).unwrap_or_default())
+    }
+}
+
 // Cómo Axum convierte AppError en una respuesta HTTP
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let (status, error_message) = match self {
+            AppError::PreconditionFailed(current) => {
+                let body = Json(json!({
+                    "error": "El recurso fue modificado por otro usuario",
+                    "current": current,
+                }));
+                return (StatusCode::PRECONDITION_FAILED, body).into_response();
+            }
             AppError::DatabaseError(msg) | AppError::DatabaseConnectionError(msg) => {
                 tracing::error!("Error de base de datos: {}", msg);
                 (
@@ -79,6 +100,7 @@ impl IntoResponse for AppError {
             AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
             AppError::AuthError(msg) => (StatusCode::UNAUTHORIZED, msg), // O BAD_REQUEST dependiendo del contexto
             AppError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg),
+            AppError::Conflict(msg) => (StatusCode::CONFLICT, msg),
             AppError::InternalServerError => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "Error interno del servidor".to_string(),
@@ -0,0 +1,47 @@
+use axum::{
+    Json,
+    extract::{Extension, Path, State},
+};
+use mongodb::bson::oid::ObjectId;
+use std::sync::Arc;
+
+use crate::{
+    errors::AppError,
+    middleware::auth_middleware::AuthenticatedUser,
+    models::board_model::{Board, BoardView, UpdateBoardSchema},
+    services::board_service::BoardService,
+    state::AppState,
+};
+
+/// Obtener el tablero Kanban de un proyecto con las tareas agrupadas por columna
+pub async fn get_board_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(project_id): Path<String>,
+) -> Result<Json<BoardView>, AppError> {
+    let project_id = ObjectId::parse_str(&project_id)
+        .map_err(|_| AppError::ValidationError("ID de proyecto inválido".to_string()))?;
+
+    let board_service = BoardService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let board = board_service.get_board(project_id, auth_user.id).await?;
+
+    Ok(Json(board))
+}
+
+/// Configurar columnas, filas y límites WIP del tablero
+pub async fn update_board_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(project_id): Path<String>,
+    Json(payload): Json<UpdateBoardSchema>,
+) -> Result<Json<Board>, AppError> {
+    let project_id = ObjectId::parse_str(&project_id)
+        .map_err(|_| AppError::ValidationError("ID de proyecto inválido".to_string()))?;
+
+    let board_service = BoardService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let board = board_service
+        .update_board(project_id, auth_user.id, payload)
+        .await?;
+
+    Ok(Json(board))
+}
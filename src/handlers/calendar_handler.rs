@@ -0,0 +1,67 @@
+use axum::{
+    Json,
+    extract::{Extension, Path, State},
+};
+use mongodb::bson::oid::ObjectId;
+use std::sync::Arc;
+
+use crate::{
+    errors::AppError,
+    middleware::auth_middleware::AuthenticatedUser,
+    models::calendar_model::{ProjectCalendar, UpdateCalendarSchema},
+    services::calendar_service::CalendarService,
+    state::AppState,
+};
+
+/// Obtener el calendario laboral de un proyecto
+pub async fn get_project_calendar_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(project_id): Path<String>,
+) -> Result<Json<ProjectCalendar>, AppError> {
+    let project_id = ObjectId::parse_str(&project_id)
+        .map_err(|_| AppError::ValidationError("ID de proyecto inválido".to_string()))?;
+
+    let calendar_service = CalendarService::new(app_state.db.clone());
+    let calendar = calendar_service
+        .get_calendar(project_id, auth_user.id)
+        .await?;
+
+    Ok(Json(calendar))
+}
+
+/// Sustituir el calendario laboral de un proyecto
+pub async fn update_project_calendar_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(project_id): Path<String>,
+    Json(schema): Json<UpdateCalendarSchema>,
+) -> Result<Json<ProjectCalendar>, AppError> {
+    let project_id = ObjectId::parse_str(&project_id)
+        .map_err(|_| AppError::ValidationError("ID de proyecto inválido".to_string()))?;
+
+    let calendar_service = CalendarService::new(app_state.db.clone());
+    let calendar = calendar_service
+        .update_calendar(project_id, auth_user.id, schema)
+        .await?;
+
+    Ok(Json(calendar))
+}
+
+/// Importar festivos desde un fichero iCalendar (`text/calendar`) enviado como cuerpo
+pub async fn import_project_holidays_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(project_id): Path<String>,
+    body: String,
+) -> Result<Json<ProjectCalendar>, AppError> {
+    let project_id = ObjectId::parse_str(&project_id)
+        .map_err(|_| AppError::ValidationError("ID de proyecto inválido".to_string()))?;
+
+    let calendar_service = CalendarService::new(app_state.db.clone());
+    let calendar = calendar_service
+        .import_holidays(project_id, auth_user.id, &body)
+        .await?;
+
+    Ok(Json(calendar))
+}
@@ -1,7 +1,7 @@
 use axum::{
     Extension, Json,
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
 };
 use mongodb::bson::oid::ObjectId;
 use std::sync::Arc;
@@ -9,9 +9,13 @@ use std::sync::Arc;
 use crate::{
     errors::AppError,
     middleware::auth_middleware::AuthenticatedUser,
-    models::comment_model::{CommentData, CreateCommentSchema, UpdateCommentSchema},
+    models::comment_model::{
+        CommentData, CommentListQuery, CommentPage, CommentRevisions, CreateCommentSchema,
+        HideCommentSchema, UpdateCommentSchema,
+    },
     services::comment_service::CommentService,
     state::AppState,
+    utils::etag::{ETagHeader, etag_header, if_match},
 };
 
 pub async fn create_comment_handler(
@@ -23,7 +27,7 @@ pub async fn create_comment_handler(
     let task_id = ObjectId::parse_str(&task_id)
         .map_err(|_| AppError::ValidationError("ID de tarea inválido.".to_string()))?;
 
-    let comment_service = CommentService::new(app_state.db.clone());
+    let comment_service = CommentService::new(app_state.db.clone(), app_state.ws_tx.clone());
     let new_comment = comment_service
         .create_comment(task_id, auth_user.id, payload)
         .await?;
@@ -31,18 +35,20 @@ pub async fn create_comment_handler(
     Ok((StatusCode::CREATED, Json(new_comment)))
 }
 
+/// Listar los hilos de comentarios de una tarea, paginados o solo los cambios desde `since`
 pub async fn get_comments_handler(
     State(app_state): State<Arc<AppState>>,
     Extension(auth_user): Extension<AuthenticatedUser>,
     Path(task_id): Path<String>,
-) -> Result<Json<Vec<CommentData>>, AppError> {
+    Query(query): Query<CommentListQuery>,
+) -> Result<Json<CommentPage>, AppError> {
     let task_id = ObjectId::parse_str(&task_id)
         .map_err(|_| AppError::ValidationError("ID de tarea inválido.".to_string()))?;
 
-    let comment_service = CommentService::new(app_state.db.clone());
+    let comment_service = CommentService::new(app_state.db.clone(), app_state.ws_tx.clone());
 
     let comments = comment_service
-        .get_comments_for_task(task_id, auth_user.id, None)
+        .list_comments(task_id, auth_user.id, query)
         .await?;
 
     Ok(Json(comments))
@@ -52,18 +58,20 @@ pub async fn update_comment_handler(
     State(app_state): State<Arc<AppState>>,
     Extension(auth_user): Extension<AuthenticatedUser>,
     Path((_task_id, comment_id)): Path<(String, String)>, // ← Aquí está el cambio
+    headers: HeaderMap,
     Json(payload): Json<UpdateCommentSchema>,
-) -> Result<Json<CommentData>, AppError> {
+) -> Result<(ETagHeader, Json<CommentData>), AppError> {
     let comment_id = ObjectId::parse_str(&comment_id)
         .map_err(|_| AppError::ValidationError("ID de comentario inválido.".to_string()))?;
+    let expected_version = if_match(&headers)?;
 
-    let comment_service = CommentService::new(app_state.db.clone());
+    let comment_service = CommentService::new(app_state.db.clone(), app_state.ws_tx.clone());
 
     let updated_comment = comment_service
-        .update_comment(comment_id, auth_user.id, payload)
+        .update_comment(comment_id, auth_user.id, payload, expected_version)
         .await?;
 
-    Ok(Json(updated_comment))
+    Ok((etag_header(updated_comment.version), Json(updated_comment)))
 }
 
 pub async fn delete_comment_handler(
@@ -74,10 +82,68 @@ pub async fn delete_comment_handler(
     let comment_id = ObjectId::parse_str(&comment_id)
         .map_err(|_| AppError::ValidationError("ID de comentario inválido.".to_string()))?;
 
-    let comment_service = CommentService::new(app_state.db.clone());
+    let comment_service = CommentService::new(app_state.db.clone(), app_state.ws_tx.clone());
     comment_service
         .delete_comment(comment_id, auth_user.id)
         .await?;
 
     Ok(StatusCode::NO_CONTENT)
 }
+
+// Parsear los IDs de tarea y comentario de la ruta
+fn parse_comment_path(task_id: &str, comment_id: &str) -> Result<(ObjectId, ObjectId), AppError> {
+    let task_id = ObjectId::parse_str(task_id)
+        .map_err(|_| AppError::ValidationError("ID de tarea inválido.".to_string()))?;
+    let comment_id = ObjectId::parse_str(comment_id)
+        .map_err(|_| AppError::ValidationError("ID de comentario inválido.".to_string()))?;
+    Ok((task_id, comment_id))
+}
+
+/// Obtener las versiones anteriores de un comentario
+pub async fn get_comment_revisions_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path((task_id, comment_id)): Path<(String, String)>,
+) -> Result<Json<CommentRevisions>, AppError> {
+    let (task_id, comment_id) = parse_comment_path(&task_id, &comment_id)?;
+
+    let comment_service = CommentService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let revisions = comment_service
+        .get_comment_revisions(task_id, comment_id, auth_user.id)
+        .await?;
+
+    Ok(Json(revisions))
+}
+
+/// Ocultar un comentario por moderación (solo administradores del proyecto)
+pub async fn hide_comment_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path((task_id, comment_id)): Path<(String, String)>,
+    Json(payload): Json<HideCommentSchema>,
+) -> Result<(ETagHeader, Json<CommentData>), AppError> {
+    let (task_id, comment_id) = parse_comment_path(&task_id, &comment_id)?;
+
+    let comment_service = CommentService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let comment = comment_service
+        .moderate_comment(task_id, comment_id, auth_user.id, Some(payload))
+        .await?;
+
+    Ok((etag_header(comment.version), Json(comment)))
+}
+
+/// Volver a mostrar un comentario oculto (solo administradores del proyecto)
+pub async fn unhide_comment_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path((task_id, comment_id)): Path<(String, String)>,
+) -> Result<(ETagHeader, Json<CommentData>), AppError> {
+    let (task_id, comment_id) = parse_comment_path(&task_id, &comment_id)?;
+
+    let comment_service = CommentService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let comment = comment_service
+        .moderate_comment(task_id, comment_id, auth_user.id, None)
+        .await?;
+
+    Ok((etag_header(comment.version), Json(comment)))
+}
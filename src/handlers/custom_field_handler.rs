@@ -0,0 +1,87 @@
+use axum::{
+    Json,
+    extract::{Extension, Path, State},
+    http::StatusCode,
+};
+use mongodb::bson::oid::ObjectId;
+use std::sync::Arc;
+
+use crate::{
+    errors::AppError,
+    middleware::auth_middleware::AuthenticatedUser,
+    models::custom_field_model::{
+        CreateCustomFieldSchema, CustomFieldDefinition, UpdateCustomFieldSchema,
+    },
+    services::custom_field_service::CustomFieldService,
+    state::AppState,
+};
+
+/// Listar los campos personalizados definidos en un proyecto
+pub async fn list_custom_fields_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(project_id): Path<String>,
+) -> Result<Json<Vec<CustomFieldDefinition>>, AppError> {
+    let project_id = ObjectId::parse_str(&project_id)
+        .map_err(|_| AppError::ValidationError("ID de proyecto inválido".to_string()))?;
+
+    let custom_field_service = CustomFieldService::new(app_state.db.clone());
+    let fields = custom_field_service
+        .list_fields(project_id, auth_user.id)
+        .await?;
+
+    Ok(Json(fields))
+}
+
+/// Definir un nuevo campo personalizado (solo el dueño del proyecto)
+pub async fn create_custom_field_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(project_id): Path<String>,
+    Json(payload): Json<CreateCustomFieldSchema>,
+) -> Result<(StatusCode, Json<CustomFieldDefinition>), AppError> {
+    let project_id = ObjectId::parse_str(&project_id)
+        .map_err(|_| AppError::ValidationError("ID de proyecto inválido".to_string()))?;
+
+    let custom_field_service = CustomFieldService::new(app_state.db.clone());
+    let field = custom_field_service
+        .create_field(project_id, auth_user.id, payload)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(field)))
+}
+
+/// Modificar nombre, obligatoriedad u opciones de un campo personalizado
+pub async fn update_custom_field_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path((project_id, key)): Path<(String, String)>,
+    Json(payload): Json<UpdateCustomFieldSchema>,
+) -> Result<Json<CustomFieldDefinition>, AppError> {
+    let project_id = ObjectId::parse_str(&project_id)
+        .map_err(|_| AppError::ValidationError("ID de proyecto inválido".to_string()))?;
+
+    let custom_field_service = CustomFieldService::new(app_state.db.clone());
+    let field = custom_field_service
+        .update_field(project_id, &key, auth_user.id, payload)
+        .await?;
+
+    Ok(Json(field))
+}
+
+/// Eliminar un campo personalizado y sus valores en las tareas
+pub async fn delete_custom_field_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path((project_id, key)): Path<(String, String)>,
+) -> Result<StatusCode, AppError> {
+    let project_id = ObjectId::parse_str(&project_id)
+        .map_err(|_| AppError::ValidationError("ID de proyecto inválido".to_string()))?;
+
+    let custom_field_service = CustomFieldService::new(app_state.db.clone());
+    custom_field_service
+        .delete_field(project_id, &key, auth_user.id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,7 +1,10 @@
 use crate::{
     errors::AppError,
     middleware::auth_middleware::AuthenticatedUser,
-    models::task_model::{CreateDateRangeSchema, DateRange, UpdateDateRangeSchema},
+    models::{
+        dependency_model::ReschedulePreview,
+        task_model::{CreateDateRangeSchema, DateRange, OverdueTask, UpdateDateRangeSchema},
+    },
     services::date_range_service::DateRangeService,
     state::AppState,
 };
@@ -23,16 +26,10 @@ pub async fn set_task_date_range_handler(
     let task_id = ObjectId::parse_str(&task_id)
         .map_err(|_| AppError::ValidationError("ID de tarea inválido".to_string()))?;
 
-    let date_range_data = DateRange {
-        task_id,
-        start_date: date_range_schema.start_date,
-        end_date: date_range_schema.end_date,
-    };
-
     let date_range_service = DateRangeService::new(app_state.db.clone());
 
     let date_range = date_range_service
-        .set_task_date_range(task_id, date_range_data, auth_user.id)
+        .set_task_date_range(task_id, date_range_schema, auth_user.id)
         .await?;
 
     Ok(Json(date_range))
@@ -110,3 +107,40 @@ pub async fn update_task_date_range_handler(
 
     Ok(Json(updated_date_range))
 }
+
+/// Vista previa de un cambio de fechas y de cómo se replanificarían las tareas dependientes
+pub async fn preview_task_date_range_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(task_id): Path<String>,
+    Json(update_data): Json<UpdateDateRangeSchema>,
+) -> Result<Json<ReschedulePreview>, AppError> {
+    let task_id = ObjectId::parse_str(&task_id)
+        .map_err(|_| AppError::ValidationError("ID de tarea inválido".to_string()))?;
+
+    let date_range_service = DateRangeService::new(app_state.db.clone());
+
+    let preview = date_range_service
+        .preview_task_date_range(task_id, update_data, auth_user.id)
+        .await?;
+
+    Ok(Json(preview))
+}
+
+/// Obtener las tareas del proyecto que han pasado su vencimiento sin terminarse
+pub async fn get_project_overdue_tasks_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(project_id): Path<String>,
+) -> Result<Json<Vec<OverdueTask>>, AppError> {
+    let project_id = ObjectId::parse_str(&project_id)
+        .map_err(|_| AppError::ValidationError("ID de proyecto inválido".to_string()))?;
+
+    let date_range_service = DateRangeService::new(app_state.db.clone());
+
+    let overdue_tasks = date_range_service
+        .overdue_tasks(project_id, auth_user.id)
+        .await?;
+
+    Ok(Json(overdue_tasks))
+}
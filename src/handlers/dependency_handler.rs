@@ -0,0 +1,69 @@
+use axum::{
+    Json,
+    extract::{Extension, Path, State},
+    http::StatusCode,
+};
+use mongodb::bson::oid::ObjectId;
+use std::sync::Arc;
+
+use crate::{
+    errors::AppError,
+    middleware::auth_middleware::AuthenticatedUser,
+    models::dependency_model::{CreateDependencySchema, TaskDependencies},
+    services::dependency_service::DependencyService,
+    state::AppState,
+};
+
+/// Obtener las predecesoras y sucesoras de una tarea
+pub async fn get_task_dependencies_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(task_id): Path<String>,
+) -> Result<Json<TaskDependencies>, AppError> {
+    let task_id = ObjectId::parse_str(&task_id)
+        .map_err(|_| AppError::ValidationError("ID de tarea inválido".to_string()))?;
+
+    let dependency_service = DependencyService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let dependencies = dependency_service
+        .get_dependencies(task_id, auth_user.id)
+        .await?;
+
+    Ok(Json(dependencies))
+}
+
+/// Añadir una predecesora (fin-inicio) a una tarea
+pub async fn add_task_dependency_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(task_id): Path<String>,
+    Json(schema): Json<CreateDependencySchema>,
+) -> Result<(StatusCode, Json<TaskDependencies>), AppError> {
+    let task_id = ObjectId::parse_str(&task_id)
+        .map_err(|_| AppError::ValidationError("ID de tarea inválido".to_string()))?;
+
+    let dependency_service = DependencyService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let dependencies = dependency_service
+        .add_dependency(task_id, auth_user.id, schema)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(dependencies)))
+}
+
+/// Quitar una predecesora de una tarea
+pub async fn remove_task_dependency_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path((task_id, predecessor_id)): Path<(String, String)>,
+) -> Result<Json<TaskDependencies>, AppError> {
+    let task_id = ObjectId::parse_str(&task_id)
+        .map_err(|_| AppError::ValidationError("ID de tarea inválido".to_string()))?;
+    let predecessor_id = ObjectId::parse_str(&predecessor_id)
+        .map_err(|_| AppError::ValidationError("ID de tarea predecesora inválido".to_string()))?;
+
+    let dependency_service = DependencyService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let dependencies = dependency_service
+        .remove_dependency(task_id, predecessor_id, auth_user.id)
+        .await?;
+
+    Ok(Json(dependencies))
+}
@@ -0,0 +1,49 @@
+use axum::{
+    Json,
+    extract::{Extension, Path, Query, State},
+};
+use mongodb::bson::oid::ObjectId;
+use std::sync::Arc;
+
+use crate::{
+    errors::AppError,
+    middleware::auth_middleware::AuthenticatedUser,
+    models::history_model::{ActivityPage, ActivityQuery, TaskHistoryEntry},
+    services::history_service::HistoryService,
+    state::AppState,
+};
+
+/// Obtener el historial de cambios de una tarea, incluso si ya fue eliminada
+pub async fn get_task_history_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(task_id): Path<String>,
+) -> Result<Json<Vec<TaskHistoryEntry>>, AppError> {
+    let task_id = ObjectId::parse_str(&task_id)
+        .map_err(|_| AppError::ValidationError("ID de tarea inválido".to_string()))?;
+
+    let history_service = HistoryService::new(app_state.db.clone());
+    let history = history_service
+        .get_task_history(task_id, auth_user.id)
+        .await?;
+
+    Ok(Json(history))
+}
+
+/// Obtener la actividad reciente de todas las tareas de un proyecto
+pub async fn get_project_activity_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(project_id): Path<String>,
+    Query(query): Query<ActivityQuery>,
+) -> Result<Json<ActivityPage>, AppError> {
+    let project_id = ObjectId::parse_str(&project_id)
+        .map_err(|_| AppError::ValidationError("ID de proyecto inválido".to_string()))?;
+
+    let history_service = HistoryService::new(app_state.db.clone());
+    let activity = history_service
+        .get_project_activity(project_id, auth_user.id, query)
+        .await?;
+
+    Ok(Json(activity))
+}
@@ -0,0 +1,89 @@
+use axum::{
+    Json,
+    extract::{Extension, Path, Query, State},
+};
+use mongodb::bson::oid::ObjectId;
+use std::sync::Arc;
+
+use crate::{
+    errors::AppError,
+    middleware::auth_middleware::AuthenticatedUser,
+    models::notification_model::{
+        Notification, NotificationCount, NotificationPage, NotificationPreferences,
+        NotificationQuery, UpdateNotificationPreferencesSchema,
+    },
+    services::notification_service::NotificationService,
+    state::AppState,
+};
+
+/// Obtener las notificaciones del usuario autenticado junto con el número de no leídas
+pub async fn list_notifications_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Query(query): Query<NotificationQuery>,
+) -> Result<Json<NotificationPage>, AppError> {
+    let notification_service =
+        NotificationService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let page = notification_service
+        .list_notifications(auth_user.id, query)
+        .await?;
+
+    Ok(Json(page))
+}
+
+/// Marcar una notificación como leída
+pub async fn mark_notification_read_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(notification_id): Path<String>,
+) -> Result<Json<Notification>, AppError> {
+    let notification_id = ObjectId::parse_str(&notification_id)
+        .map_err(|_| AppError::ValidationError("ID de notificación inválido".to_string()))?;
+
+    let notification_service =
+        NotificationService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let notification = notification_service
+        .mark_read(notification_id, auth_user.id)
+        .await?;
+
+    Ok(Json(notification))
+}
+
+/// Marcar como leídas todas las notificaciones del usuario
+pub async fn mark_all_notifications_read_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+) -> Result<Json<NotificationCount>, AppError> {
+    let notification_service =
+        NotificationService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let count = notification_service.mark_all_read(auth_user.id).await?;
+
+    Ok(Json(count))
+}
+
+/// Obtener las preferencias de correo del usuario autenticado
+pub async fn get_notification_preferences_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+) -> Result<Json<NotificationPreferences>, AppError> {
+    let notification_service =
+        NotificationService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let preferences = notification_service.get_preferences(auth_user.id).await?;
+
+    Ok(Json(preferences))
+}
+
+/// Cambiar el idioma y la forma de entrega por correo de cada tipo de aviso
+pub async fn update_notification_preferences_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Json(schema): Json<UpdateNotificationPreferencesSchema>,
+) -> Result<Json<NotificationPreferences>, AppError> {
+    let notification_service =
+        NotificationService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let preferences = notification_service
+        .update_preferences(auth_user.id, schema)
+        .await?;
+
+    Ok(Json(preferences))
+}
@@ -4,7 +4,7 @@ use crate::models::{project_models::{UpdateProjectSchema, ProjectWithRole}, user
 use axum::{
     Json,
     extract::{Extension, Path, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
 };
 use mongodb::bson::oid::ObjectId;
 
@@ -14,6 +14,7 @@ use crate::{
     models::project_models::{AddMemberSchema, CreateProjectSchema, Project},
     services::project_service::ProjectService,
     state::AppState,
+    utils::etag::{ETagHeader, etag_header, if_match},
 };
 
 pub async fn create_project_handler(
@@ -21,7 +22,7 @@ pub async fn create_project_handler(
     Extension(auth_user): Extension<AuthenticatedUser>,
     Json(payload): Json<CreateProjectSchema>,
 ) -> Result<(StatusCode, Json<Project>), AppError> {
-    let project_service = ProjectService::new(app_state.db.clone());
+    let project_service = ProjectService::new(app_state.db.clone(), app_state.ws_tx.clone());
     let new_project = project_service
         .create_project(payload, auth_user.id)
         .await?;
@@ -33,7 +34,7 @@ pub async fn get_project_handler(
     State(app_state): State<Arc<AppState>>,
     Extension(auth_user): Extension<AuthenticatedUser>,
 ) -> Result<Json<Vec<ProjectWithRole>>, AppError> {
-    let project_service = ProjectService::new(app_state.db.clone());
+    let project_service = ProjectService::new(app_state.db.clone(), app_state.ws_tx.clone());
     let projects = project_service.get_projects_with_role_for_user(auth_user.id).await?;
 
     Ok(Json(projects))
@@ -43,17 +44,19 @@ pub async fn update_project_handler(
     State(app_state): State<Arc<AppState>>,
     Extension(auth_user): Extension<AuthenticatedUser>,
     Path(project_id): Path<String>,
+    headers: HeaderMap,
     Json(payload): Json<UpdateProjectSchema>,
-) -> Result<Json<Project>, AppError> {
+) -> Result<(ETagHeader, Json<Project>), AppError> {
     let project_id = ObjectId::parse_str(&project_id)
         .map_err(|_| AppError::ValidationError("ID de proyecto inválido".to_string()))?;
+    let expected_version = if_match(&headers)?;
 
-    let project_service = ProjectService::new(app_state.db.clone());
+    let project_service = ProjectService::new(app_state.db.clone(), app_state.ws_tx.clone());
     let update_project = project_service
-        .update_project(project_id, auth_user.id, payload)
+        .update_project(project_id, auth_user.id, payload, expected_version)
         .await?;
 
-    Ok(Json(update_project))
+    Ok((etag_header(update_project.version), Json(update_project)))
 }
 
 pub async fn delete_project_handler(
@@ -64,7 +67,7 @@ pub async fn delete_project_handler(
     let project_id = ObjectId::parse_str(&project_id)
         .map_err(|_| AppError::ValidationError("ID de proyecto invalido. ".to_string()))?;
 
-    let project_service = ProjectService::new(app_state.db.clone());
+    let project_service = ProjectService::new(app_state.db.clone(), app_state.ws_tx.clone());
     project_service
         .delete_project(project_id, auth_user.id)
         .await?;
@@ -81,7 +84,7 @@ pub async fn add_member_handler(
     let project_id = ObjectId::parse_str(&project_id)
         .map_err(|_| AppError::ValidationError("ID de proyecto inválido".to_string()))?;
 
-    let project_service = ProjectService::new(app_state.db.clone());
+    let project_service = ProjectService::new(app_state.db.clone(), app_state.ws_tx.clone());
     project_service
         .add_member(project_id, auth_user.id, payload)
         .await?;
@@ -97,7 +100,7 @@ pub async fn list_members_handler(
     let project_id = ObjectId::parse_str(&project_id)
         .map_err(|_| AppError::ValidationError("ID de proyecto inválido".to_string()))?;
 
-    let project_service = ProjectService::new(app_state.db.clone());
+    let project_service = ProjectService::new(app_state.db.clone(), app_state.ws_tx.clone());
     let members = project_service
         .list_members(project_id, auth_user.id)
         .await?;
@@ -115,7 +118,7 @@ pub async fn remove_member_handler(
     let user_id = ObjectId::parse_str(&user_id)
         .map_err(|_| AppError::ValidationError("ID de usuario inválido".to_string()))?;
 
-    let project_service = ProjectService::new(app_state.db.clone());
+    let project_service = ProjectService::new(app_state.db.clone(), app_state.ws_tx.clone());
     project_service
         .remove_member(project_id, auth_user.id, user_id)
         .await?;
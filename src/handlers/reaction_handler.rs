@@ -0,0 +1,69 @@
+use axum::{
+    Json,
+    extract::{Extension, Path, State},
+};
+use mongodb::bson::oid::ObjectId;
+use std::sync::Arc;
+
+use crate::{
+    errors::AppError,
+    middleware::auth_middleware::AuthenticatedUser,
+    models::reaction_model::{ReactionSummary, ToggleReactionSchema},
+    services::reaction_service::ReactionService,
+    state::AppState,
+};
+
+/// Obtener las reacciones de una tarea
+pub async fn get_task_reactions_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(task_id): Path<String>,
+) -> Result<Json<Vec<ReactionSummary>>, AppError> {
+    let task_id = ObjectId::parse_str(&task_id)
+        .map_err(|_| AppError::ValidationError("ID de tarea inválido".to_string()))?;
+
+    let reaction_service = ReactionService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let reactions = reaction_service
+        .get_task_reactions(task_id, auth_user.id)
+        .await?;
+
+    Ok(Json(reactions))
+}
+
+/// Poner o quitar una reacción en una tarea
+pub async fn toggle_task_reaction_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(task_id): Path<String>,
+    Json(schema): Json<ToggleReactionSchema>,
+) -> Result<Json<Vec<ReactionSummary>>, AppError> {
+    let task_id = ObjectId::parse_str(&task_id)
+        .map_err(|_| AppError::ValidationError("ID de tarea inválido".to_string()))?;
+
+    let reaction_service = ReactionService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let reactions = reaction_service
+        .toggle_task_reaction(task_id, auth_user.id, schema)
+        .await?;
+
+    Ok(Json(reactions))
+}
+
+/// Poner o quitar una reacción en un comentario
+pub async fn toggle_comment_reaction_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path((task_id, comment_id)): Path<(String, String)>,
+    Json(schema): Json<ToggleReactionSchema>,
+) -> Result<Json<Vec<ReactionSummary>>, AppError> {
+    let task_id = ObjectId::parse_str(&task_id)
+        .map_err(|_| AppError::ValidationError("ID de tarea inválido".to_string()))?;
+    let comment_id = ObjectId::parse_str(&comment_id)
+        .map_err(|_| AppError::ValidationError("ID de comentario inválido".to_string()))?;
+
+    let reaction_service = ReactionService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let reactions = reaction_service
+        .toggle_comment_reaction(task_id, comment_id, auth_user.id, schema)
+        .await?;
+
+    Ok(Json(reactions))
+}
@@ -0,0 +1,37 @@
+use axum::{
+    Json,
+    extract::{Extension, Query, State},
+};
+use std::sync::Arc;
+
+use crate::{
+    errors::AppError,
+    middleware::auth_middleware::AuthenticatedUser,
+    models::search_model::{JqlSearchQuery, SearchResult, TextSearchQuery, TextSearchResult},
+    services::search_service::SearchService,
+    state::AppState,
+};
+
+/// Buscar tareas en todos los proyectos accesibles con una consulta JQL
+pub async fn search_jql_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Query(query): Query<JqlSearchQuery>,
+) -> Result<Json<SearchResult>, AppError> {
+    let search_service = SearchService::new(app_state.db.clone());
+    let result = search_service.search_jql(auth_user.id, query).await?;
+
+    Ok(Json(result))
+}
+
+/// Búsqueda de texto completo en tareas y comentarios de los proyectos accesibles
+pub async fn search_text_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Query(query): Query<TextSearchQuery>,
+) -> Result<Json<TextSearchResult>, AppError> {
+    let search_service = SearchService::new(app_state.db.clone());
+    let result = search_service.search_text(auth_user.id, query).await?;
+
+    Ok(Json(result))
+}
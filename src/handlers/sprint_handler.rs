@@ -0,0 +1,192 @@
+use axum::{
+    Json,
+    extract::{Extension, Path, State},
+    http::StatusCode,
+};
+use mongodb::bson::oid::ObjectId;
+use std::sync::Arc;
+
+use crate::{
+    errors::AppError,
+    middleware::auth_middleware::AuthenticatedUser,
+    models::{
+        sprint_model::{
+            CloseSprintResult, CloseSprintSchema, CreateSprintSchema, Sprint, SprintTasksSchema,
+            UpdateSprintSchema,
+        },
+        task_model::Task,
+    },
+    services::sprint_service::SprintService,
+    state::AppState,
+};
+
+fn parse_sprint_id(sprint_id: &str) -> Result<ObjectId, AppError> {
+    ObjectId::parse_str(sprint_id)
+        .map_err(|_| AppError::ValidationError("ID de sprint inválido".to_string()))
+}
+
+/// Crear un sprint en un proyecto
+pub async fn create_sprint_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(project_id): Path<String>,
+    Json(payload): Json<CreateSprintSchema>,
+) -> Result<(StatusCode, Json<Sprint>), AppError> {
+    let project_id = ObjectId::parse_str(&project_id)
+        .map_err(|_| AppError::ValidationError("ID de proyecto inválido".to_string()))?;
+
+    let sprint_service = SprintService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let sprint = sprint_service
+        .create_sprint(project_id, auth_user.id, payload)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(sprint)))
+}
+
+/// Listar los sprints de un proyecto
+pub async fn get_project_sprints_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(project_id): Path<String>,
+) -> Result<Json<Vec<Sprint>>, AppError> {
+    let project_id = ObjectId::parse_str(&project_id)
+        .map_err(|_| AppError::ValidationError("ID de proyecto inválido".to_string()))?;
+
+    let sprint_service = SprintService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let sprints = sprint_service
+        .get_sprints_for_project(project_id, auth_user.id)
+        .await?;
+
+    Ok(Json(sprints))
+}
+
+/// Obtener el backlog de un proyecto (tareas fuera del sprint activo)
+pub async fn get_project_backlog_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(project_id): Path<String>,
+) -> Result<Json<Vec<Task>>, AppError> {
+    let project_id = ObjectId::parse_str(&project_id)
+        .map_err(|_| AppError::ValidationError("ID de proyecto inválido".to_string()))?;
+
+    let sprint_service = SprintService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let tasks = sprint_service.get_backlog(project_id, auth_user.id).await?;
+
+    Ok(Json(tasks))
+}
+
+/// Actualizar nombre, objetivo o fechas de un sprint
+pub async fn update_sprint_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(sprint_id): Path<String>,
+    Json(payload): Json<UpdateSprintSchema>,
+) -> Result<Json<Sprint>, AppError> {
+    let sprint_id = parse_sprint_id(&sprint_id)?;
+
+    let sprint_service = SprintService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let sprint = sprint_service
+        .update_sprint(sprint_id, auth_user.id, payload)
+        .await?;
+
+    Ok(Json(sprint))
+}
+
+/// Eliminar un sprint no activo
+pub async fn delete_sprint_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(sprint_id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let sprint_id = parse_sprint_id(&sprint_id)?;
+
+    let sprint_service = SprintService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    sprint_service
+        .delete_sprint(sprint_id, auth_user.id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Iniciar un sprint planificado
+pub async fn start_sprint_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(sprint_id): Path<String>,
+) -> Result<Json<Sprint>, AppError> {
+    let sprint_id = parse_sprint_id(&sprint_id)?;
+
+    let sprint_service = SprintService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let sprint = sprint_service.start_sprint(sprint_id, auth_user.id).await?;
+
+    Ok(Json(sprint))
+}
+
+/// Cerrar el sprint activo
+pub async fn close_sprint_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(sprint_id): Path<String>,
+    payload: Option<Json<CloseSprintSchema>>,
+) -> Result<Json<CloseSprintResult>, AppError> {
+    let sprint_id = parse_sprint_id(&sprint_id)?;
+    let payload = payload.map(|Json(schema)| schema).unwrap_or_default();
+
+    let sprint_service = SprintService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let result = sprint_service
+        .close_sprint(sprint_id, auth_user.id, payload)
+        .await?;
+
+    Ok(Json(result))
+}
+
+/// Listar las tareas de un sprint
+pub async fn get_sprint_tasks_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(sprint_id): Path<String>,
+) -> Result<Json<Vec<Task>>, AppError> {
+    let sprint_id = parse_sprint_id(&sprint_id)?;
+
+    let sprint_service = SprintService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let tasks = sprint_service
+        .get_sprint_tasks(sprint_id, auth_user.id)
+        .await?;
+
+    Ok(Json(tasks))
+}
+
+/// Añadir tareas a un sprint
+pub async fn add_sprint_tasks_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(sprint_id): Path<String>,
+    Json(payload): Json<SprintTasksSchema>,
+) -> Result<Json<Vec<Task>>, AppError> {
+    let sprint_id = parse_sprint_id(&sprint_id)?;
+
+    let sprint_service = SprintService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let tasks = sprint_service
+        .add_tasks(sprint_id, auth_user.id, payload)
+        .await?;
+
+    Ok(Json(tasks))
+}
+
+/// Quitar una tarea de un sprint
+pub async fn remove_sprint_task_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path((sprint_id, task_id)): Path<(String, String)>,
+) -> Result<StatusCode, AppError> {
+    let sprint_id = parse_sprint_id(&sprint_id)?;
+    let task_id = ObjectId::parse_str(&task_id)
+        .map_err(|_| AppError::ValidationError("ID de tarea inválido".to_string()))?;
+
+    let sprint_service = SprintService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    sprint_service
+        .remove_task(sprint_id, task_id, auth_user.id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
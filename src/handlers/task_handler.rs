@@ -1,20 +1,26 @@
 use crate::{
     errors::AppError,
     middleware::auth_middleware::AuthenticatedUser,
+    models::bulk_task_model::{BulkTaskResponse, BulkTaskSchema},
     models::task_model::UpdateTaskSchema,
-    models::task_model::{CreateTaskSchema, DateRange, Task},
+    models::task_model::{
+        CreateTaskSchema, DateRange, MoveTaskSchema, RankTaskSchema, Task, TaskListQuery, TaskPage,
+    },
+    services::bulk_task_service::BulkTaskService,
     services::date_range_service::DateRangeService,
+    services::rank_service::RankService,
     services::task_service::TaskService,
     state::AppState,
+    utils::etag::{ETagHeader, etag_header, if_match},
 };
 use axum::{
     Json,
-    extract::{Extension, Path, State},
-    http::StatusCode,
+    extract::{Extension, Path, Query, State},
+    http::{HeaderMap, StatusCode},
 };
 use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct TaskWithDateRange {
@@ -44,13 +50,21 @@ pub async fn get_task_for_project_handler(
     State(app_state): State<Arc<AppState>>,
     Extension(auth_user): Extension<AuthenticatedUser>,
     Path(project_id): Path<String>,
-) -> Result<Json<Vec<Task>>, AppError> {
+    Query(query): Query<TaskListQuery>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<TaskPage>, AppError> {
     let project_id = ObjectId::parse_str(&project_id)
         .map_err(|_| AppError::ValidationError("ID de proyecto invalido".to_string()))?;
 
+    // Los filtros por campos personalizados llegan como `cf.<clave>=valor`
+    let custom_filters = params
+        .into_iter()
+        .filter_map(|(name, value)| Some((name.strip_prefix("cf.")?.to_string(), value)))
+        .collect();
+
     let task_service = TaskService::new(app_state.db.clone(), app_state.ws_tx.clone());
     let tasks = task_service
-        .get_task_for_project(project_id, auth_user.id)
+        .get_task_for_project(project_id, auth_user.id, query, custom_filters)
         .await?;
 
     Ok(Json(tasks))
@@ -60,31 +74,65 @@ pub async fn get_task_by_id_handler(
     State(app_state): State<Arc<AppState>>,
     Extension(auth_user): Extension<AuthenticatedUser>,
     Path(task_id): Path<String>,
-) -> Result<Json<Task>, AppError> {
+) -> Result<(ETagHeader, Json<Task>), AppError> {
     let task_id = ObjectId::parse_str(&task_id)
         .map_err(|_| AppError::ValidationError("ID de tarea invalido".to_string()))?;
 
     let task_service = TaskService::new(app_state.db.clone(), app_state.ws_tx.clone());
     let task = task_service.get_task_by_id(task_id, auth_user.id).await?;
 
-    Ok(Json(task))
+    Ok((etag_header(task.version), Json(task)))
+}
+
+/// Buscar una tarea por su clave (`PROYECTO-n`) o por una clave anterior
+pub async fn get_task_by_key_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(key): Path<String>,
+) -> Result<(ETagHeader, Json<Task>), AppError> {
+    let task_service = TaskService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let task = task_service.get_task_by_key(&key, auth_user.id).await?;
+
+    Ok((etag_header(task.version), Json(task)))
 }
 
 pub async fn update_task_handler(
     State(app_state): State<Arc<AppState>>,
     Extension(auth_user): Extension<AuthenticatedUser>,
     Path(task_id): Path<String>,
+    headers: HeaderMap,
     Json(payload): Json<UpdateTaskSchema>,
-) -> Result<Json<Task>, AppError> {
+) -> Result<(ETagHeader, Json<Task>), AppError> {
     let task_id = ObjectId::parse_str(&task_id)
         .map_err(|_| AppError::ValidationError("ID de tarea invalido".to_string()))?;
+    let expected_version = if_match(&headers)?;
 
     let task_service = TaskService::new(app_state.db.clone(), app_state.ws_tx.clone());
     let update_task = task_service
-        .update_task(task_id, auth_user.id, payload)
+        .update_task(task_id, auth_user.id, payload, expected_version)
+        .await?;
+
+    Ok((etag_header(update_task.version), Json(update_task)))
+}
+
+/// Mover una tarea a otro proyecto, con sus comentarios, fechas y adjuntos
+pub async fn move_task_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(task_id): Path<String>,
+    headers: HeaderMap,
+    Json(payload): Json<MoveTaskSchema>,
+) -> Result<(ETagHeader, Json<Task>), AppError> {
+    let task_id = ObjectId::parse_str(&task_id)
+        .map_err(|_| AppError::ValidationError("ID de tarea invalido".to_string()))?;
+    let expected_version = if_match(&headers)?;
+
+    let task_service = TaskService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let moved_task = task_service
+        .move_task(task_id, auth_user.id, payload, expected_version)
         .await?;
 
-    Ok(Json(update_task))
+    Ok((etag_header(moved_task.version), Json(moved_task)))
 }
 
 pub async fn delete_task_handler(
@@ -119,3 +167,38 @@ pub async fn get_task_with_date_range_handler(
 
     Ok(Json(TaskWithDateRange { task, date_range }))
 }
+
+pub async fn rank_task_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(task_id): Path<String>,
+    Json(payload): Json<RankTaskSchema>,
+) -> Result<Json<Task>, AppError> {
+    let task_id = ObjectId::parse_str(&task_id)
+        .map_err(|_| AppError::ValidationError("ID de tarea invalido".to_string()))?;
+
+    let rank_service = RankService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let task = rank_service
+        .rank_task(task_id, auth_user.id, payload)
+        .await?;
+
+    Ok(Json(task))
+}
+
+/// Aplicar una misma operación (update, move, delete o labels) a varias tareas del proyecto
+pub async fn bulk_tasks_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(project_id): Path<String>,
+    Json(payload): Json<BulkTaskSchema>,
+) -> Result<Json<BulkTaskResponse>, AppError> {
+    let project_id = ObjectId::parse_str(&project_id)
+        .map_err(|_| AppError::ValidationError("ID de proyecto invalido".to_string()))?;
+
+    let bulk_service = BulkTaskService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let response = bulk_service
+        .apply(project_id, auth_user.id, payload)
+        .await?;
+
+    Ok(Json(response))
+}
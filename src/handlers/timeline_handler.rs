@@ -0,0 +1,29 @@
+use axum::{
+    Json,
+    extract::{Extension, Path, State},
+};
+use mongodb::bson::oid::ObjectId;
+use std::sync::Arc;
+
+use crate::{
+    errors::AppError, middleware::auth_middleware::AuthenticatedUser,
+    models::dependency_model::Timeline, services::timeline_service::TimelineService,
+    state::AppState,
+};
+
+/// Obtener el cronograma del proyecto con dependencias, holgura y camino crítico
+pub async fn get_project_timeline_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(project_id): Path<String>,
+) -> Result<Json<Timeline>, AppError> {
+    let project_id = ObjectId::parse_str(&project_id)
+        .map_err(|_| AppError::ValidationError("ID de proyecto inválido".to_string()))?;
+
+    let timeline_service = TimelineService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let timeline = timeline_service
+        .project_timeline(project_id, auth_user.id)
+        .await?;
+
+    Ok(Json(timeline))
+}
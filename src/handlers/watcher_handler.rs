@@ -0,0 +1,71 @@
+use axum::{
+    Json,
+    extract::{Extension, Path, Query, State},
+};
+use mongodb::bson::oid::ObjectId;
+use std::sync::Arc;
+
+use crate::{
+    errors::AppError,
+    middleware::auth_middleware::AuthenticatedUser,
+    models::watcher_model::{TaskWatchers, WatchedTasksPage, WatchedTasksQuery},
+    services::watcher_service::WatcherService,
+    state::AppState,
+};
+
+/// Empezar a observar una tarea
+pub async fn watch_task_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(task_id): Path<String>,
+) -> Result<Json<TaskWatchers>, AppError> {
+    let task_id = ObjectId::parse_str(&task_id)
+        .map_err(|_| AppError::ValidationError("ID de tarea inválido".to_string()))?;
+
+    let watcher_service = WatcherService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let watchers = watcher_service.watch(task_id, auth_user.id).await?;
+
+    Ok(Json(watchers))
+}
+
+/// Dejar de observar una tarea
+pub async fn unwatch_task_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(task_id): Path<String>,
+) -> Result<Json<TaskWatchers>, AppError> {
+    let task_id = ObjectId::parse_str(&task_id)
+        .map_err(|_| AppError::ValidationError("ID de tarea inválido".to_string()))?;
+
+    let watcher_service = WatcherService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let watchers = watcher_service.unwatch(task_id, auth_user.id).await?;
+
+    Ok(Json(watchers))
+}
+
+/// Obtener los observadores de una tarea
+pub async fn get_task_watchers_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(task_id): Path<String>,
+) -> Result<Json<TaskWatchers>, AppError> {
+    let task_id = ObjectId::parse_str(&task_id)
+        .map_err(|_| AppError::ValidationError("ID de tarea inválido".to_string()))?;
+
+    let watcher_service = WatcherService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let watchers = watcher_service.get_watchers(task_id, auth_user.id).await?;
+
+    Ok(Json(watchers))
+}
+
+/// Obtener las tareas que observa el usuario autenticado
+pub async fn get_watched_tasks_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Query(query): Query<WatchedTasksQuery>,
+) -> Result<Json<WatchedTasksPage>, AppError> {
+    let watcher_service = WatcherService::new(app_state.db.clone(), app_state.ws_tx.clone());
+    let page = watcher_service.watched_tasks(auth_user.id, query).await?;
+
+    Ok(Json(page))
+}
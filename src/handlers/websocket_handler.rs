@@ -1,23 +1,58 @@
 use axum::{
     extract::{
-        State,
+        Query, State,
         ws::{Message, WebSocket, WebSocketUpgrade},
     },
     response::IntoResponse,
 };
+use mongodb::bson::oid::ObjectId;
+use serde::Deserialize;
 
-use crate::state::AppState;
+use crate::{errors::AppError, state::AppState, utils::jwt_utils};
 use futures::{sink::SinkExt, stream::StreamExt};
 use std::sync::Arc;
 
+#[derive(Deserialize, Debug, Default)]
+pub struct WebSocketQuery {
+    // JWT del usuario; el navegador no puede enviar cabeceras al abrir un WebSocket
+    pub token: Option<String>,
+}
+
 pub async fn websocket_handler(
     ws: WebSocketUpgrade,
     State(state): State<Arc<AppState>>,
-) -> impl IntoResponse {
-    ws.on_upgrade(|socket| handle_socket(socket, state))
+    Query(query): Query<WebSocketQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    // Con `?token=` la conexión queda asociada al usuario y recibe también sus notificaciones
+    let user_id = match query.token {
+        Some(token) => {
+            let claims = jwt_utils::verify_jwt(&token, &state.config)?;
+            Some(ObjectId::parse_str(&claims.sub).map_err(|_| {
+                AppError::Unauthorized("ID de usuario inválido en el token.".to_string())
+            })?)
+        }
+        None => None,
+    };
+
+    Ok(ws.on_upgrade(move |socket| handle_socket(socket, state, user_id)))
 }
 
-async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
+// Los mensajes con `target_user_id` solo se entregan a la conexión de ese usuario;
+// el resto se difunde a todas
+pub fn is_for_user(message: &str, user_id: Option<ObjectId>) -> bool {
+    if !message.contains("\"target_user_id\"") {
+        return true;
+    }
+    let target = serde_json::from_str::<serde_json::Value>(message)
+        .ok()
+        .and_then(|value| value.get("target_user_id")?.as_str().map(str::to_string));
+    match target {
+        Some(target) => user_id.is_some_and(|user_id| user_id.to_hex() == target),
+        None => true,
+    }
+}
+
+async fn handle_socket(socket: WebSocket, state: Arc<AppState>, user_id: Option<ObjectId>) {
     let (mut sender, mut receiver) = socket.split();
 
     let mut rx = state.ws_tx.subscribe();
@@ -26,6 +61,9 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
 
     let mut send_task = tokio::spawn(async move {
         while let Ok(msg) = rx.recv().await {
+            if !is_for_user(&msg, user_id) {
+                continue;
+            }
             // tracing::debug!("Enviando mensaje WebSocket: {}", msg);
             if sender.send(Message::Text(msg.into())).await.is_err() {
                 // tracing::warn!("Error enviando mensaje WebSocket, cerrando conexión");
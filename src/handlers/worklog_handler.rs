@@ -0,0 +1,125 @@
+use axum::{
+    Json,
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+};
+use mongodb::bson::oid::ObjectId;
+use std::sync::Arc;
+
+use crate::{
+    errors::AppError,
+    middleware::auth_middleware::AuthenticatedUser,
+    models::worklog_model::{
+        CreateWorklogSchema, TimesheetQuery, TimesheetReport, UpdateWorklogSchema, Worklog,
+    },
+    services::worklog_service::WorklogService,
+    state::AppState,
+};
+
+fn parse_ids(task_id: &str, worklog_id: &str) -> Result<(ObjectId, ObjectId), AppError> {
+    let task_id = ObjectId::parse_str(task_id)
+        .map_err(|_| AppError::ValidationError("ID de tarea inválido".to_string()))?;
+    let worklog_id = ObjectId::parse_str(worklog_id)
+        .map_err(|_| AppError::ValidationError("ID de registro de trabajo inválido".to_string()))?;
+    Ok((task_id, worklog_id))
+}
+
+/// Registrar tiempo trabajado en una tarea
+pub async fn create_worklog_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(task_id): Path<String>,
+    Json(payload): Json<CreateWorklogSchema>,
+) -> Result<(StatusCode, Json<Worklog>), AppError> {
+    let task_id = ObjectId::parse_str(&task_id)
+        .map_err(|_| AppError::ValidationError("ID de tarea inválido".to_string()))?;
+
+    let worklog_service = WorklogService::new(app_state.db.clone());
+    let worklog = worklog_service
+        .create_worklog(task_id, auth_user.id, payload)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(worklog)))
+}
+
+/// Listar el tiempo registrado en una tarea
+pub async fn get_worklogs_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(task_id): Path<String>,
+) -> Result<Json<Vec<Worklog>>, AppError> {
+    let task_id = ObjectId::parse_str(&task_id)
+        .map_err(|_| AppError::ValidationError("ID de tarea inválido".to_string()))?;
+
+    let worklog_service = WorklogService::new(app_state.db.clone());
+    let worklogs = worklog_service
+        .get_worklogs_for_task(task_id, auth_user.id)
+        .await?;
+
+    Ok(Json(worklogs))
+}
+
+/// Actualizar un registro de trabajo
+pub async fn update_worklog_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path((task_id, worklog_id)): Path<(String, String)>,
+    Json(payload): Json<UpdateWorklogSchema>,
+) -> Result<Json<Worklog>, AppError> {
+    let (task_id, worklog_id) = parse_ids(&task_id, &worklog_id)?;
+
+    let worklog_service = WorklogService::new(app_state.db.clone());
+    let worklog = worklog_service
+        .update_worklog(task_id, worklog_id, auth_user.id, payload)
+        .await?;
+
+    Ok(Json(worklog))
+}
+
+/// Eliminar un registro de trabajo
+pub async fn delete_worklog_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path((task_id, worklog_id)): Path<(String, String)>,
+) -> Result<StatusCode, AppError> {
+    let (task_id, worklog_id) = parse_ids(&task_id, &worklog_id)?;
+
+    let worklog_service = WorklogService::new(app_state.db.clone());
+    worklog_service
+        .delete_worklog(task_id, worklog_id, auth_user.id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Reporte de horas de un proyecto en un rango de fechas
+pub async fn get_project_timesheet_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(project_id): Path<String>,
+    Query(query): Query<TimesheetQuery>,
+) -> Result<Json<TimesheetReport>, AppError> {
+    let project_id = ObjectId::parse_str(&project_id)
+        .map_err(|_| AppError::ValidationError("ID de proyecto inválido".to_string()))?;
+
+    let worklog_service = WorklogService::new(app_state.db.clone());
+    let report = worklog_service
+        .project_timesheet(project_id, auth_user.id, query.from, query.to)
+        .await?;
+
+    Ok(Json(report))
+}
+
+/// Reporte de horas del usuario autenticado en un rango de fechas
+pub async fn get_my_timesheet_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Query(query): Query<TimesheetQuery>,
+) -> Result<Json<TimesheetReport>, AppError> {
+    let worklog_service = WorklogService::new(app_state.db.clone());
+    let report = worklog_service
+        .user_timesheet(auth_user.id, query.from, query.to)
+        .await?;
+
+    Ok(Json(report))
+}
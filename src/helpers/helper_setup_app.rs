@@ -13,10 +13,18 @@ use crate::{
     config::Config,
     db::DatabaseState,
     models::{
+        board_model::Board,
+        calendar_model::ProjectCalendar,
         comment_model::Comment,
+        custom_field_model::CustomFieldDefinition,
+        dependency_model::TaskDependency,
+        history_model::TaskHistoryEntry,
+        notification_model::{Notification, NotificationPreferences},
         project_models::Project,
-        task_model::Task,
+        sprint_model::Sprint,
+        task_model::{DateRange, Task},
         user_model::{LoginResponse, User},
+        worklog_model::Worklog,
     },
     router::router::get_app,
     state::AppState,
@@ -43,6 +51,15 @@ pub async fn setup_app() -> Router {
         gcs_bucket_name: "test-bucket".to_string(),
         gcs_project_id: "test-project".to_string(),
         google_application_credentials: None,
+        smtp_host: None,
+        smtp_port: 587,
+        smtp_username: None,
+        smtp_password: None,
+        mail_from: "Jira Clone <no-reply@localhost>".to_string(),
+        mail_outbox_dir: std::env::temp_dir()
+            .join("jira_clone_test_outbox")
+            .to_string_lossy()
+            .to_string(),
     });
 
     // Use a fixed database name for tests - this works because we use a mutex
@@ -76,6 +93,71 @@ pub async fn setup_app() -> Router {
         .delete_many(doc! {})
         .await
         .ok();
+    db_state
+        .get_db()
+        .collection::<Worklog>("worklogs")
+        .delete_many(doc! {})
+        .await
+        .ok();
+    db_state
+        .get_db()
+        .collection::<Sprint>("sprints")
+        .delete_many(doc! {})
+        .await
+        .ok();
+    db_state
+        .get_db()
+        .collection::<Board>("boards")
+        .delete_many(doc! {})
+        .await
+        .ok();
+    db_state
+        .get_db()
+        .collection::<CustomFieldDefinition>("custom_fields")
+        .delete_many(doc! {})
+        .await
+        .ok();
+    db_state
+        .get_db()
+        .collection::<TaskHistoryEntry>("task_history")
+        .delete_many(doc! {})
+        .await
+        .ok();
+    db_state
+        .get_db()
+        .collection::<Notification>("notifications")
+        .delete_many(doc! {})
+        .await
+        .ok();
+    db_state
+        .get_db()
+        .collection::<NotificationPreferences>("notification_preferences")
+        .delete_many(doc! {})
+        .await
+        .ok();
+    db_state
+        .get_db()
+        .collection::<DateRange>("task_date_ranges")
+        .delete_many(doc! {})
+        .await
+        .ok();
+    db_state
+        .get_db()
+        .collection::<TaskDependency>("task_dependencies")
+        .delete_many(doc! {})
+        .await
+        .ok();
+    db_state
+        .get_db()
+        .collection::<ProjectCalendar>("project_calendars")
+        .delete_many(doc! {})
+        .await
+        .ok();
+
+    db_state
+        .ensure_indexes()
+        .await
+        .expect("Fallo al crear los índices de la DB de prueba");
 
     // Create a temporary WebSocket channel for tests
     let (ws_tx, _) = broadcast::channel::<String>(100);
@@ -283,3 +365,29 @@ pub async fn create_task_for_project(
         serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
     task.id.unwrap().to_hex()
 }
+
+// Envía una petición autenticada con cuerpo JSON y devuelve el estado y el cuerpo crudo
+pub async fn send_request(
+    app: &Router,
+    method: &str,
+    uri: String,
+    token: &str,
+    body: serde_json::Value,
+) -> (StatusCode, Vec<u8>) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, bytes.to_vec())
+}
@@ -5,40 +5,88 @@ pub mod state;
 
 // Hasheo de contraseñas
 pub mod utils {
+    pub mod calendar;
+    pub mod critical_path;
+    pub mod cursor;
+    pub mod email_templates;
+    pub mod etag;
+    pub mod highlight;
+    pub mod jql;
     pub mod jwt_utils;
+    pub mod lexorank;
+    pub mod markdown;
+    pub mod mentions;
     pub mod password_utils;
     pub mod validation;
 }
 
 pub mod services {
     pub mod auth_service;
+    pub mod board_service;
+    pub mod bulk_task_service;
+    pub mod calendar_service;
     pub mod comment_service;
+    pub mod custom_field_service;
     pub mod date_range_service;
+    pub mod dependency_service;
+    pub mod email_notification_service;
+    pub mod history_service;
     pub mod image_service;
+    pub mod mail_service;
+    pub mod notification_service;
     pub mod permission_service;
     pub mod project_service;
+    pub mod rank_service;
+    pub mod reaction_service;
+    pub mod search_service;
+    pub mod sprint_service;
     pub mod task_service;
-    
+    pub mod timeline_service;
+    pub mod watcher_service;
+    pub mod worklog_service;
 }
 
 //Modelos para la base de datos
 pub mod models {
+    pub mod board_model;
+    pub mod bulk_task_model;
+    pub mod calendar_model;
     pub mod comment_model;
+    pub mod custom_field_model;
+    pub mod dependency_model;
+    pub mod history_model;
     pub mod image_model;
+    pub mod notification_model;
     pub mod project_models;
+    pub mod reaction_model;
+    pub mod search_model;
+    pub mod sprint_model;
     pub mod task_model;
     pub mod user_model;
+    pub mod watcher_model;
+    pub mod worklog_model;
 }
 
 pub mod handlers {
     pub mod auth_handler;
+    pub mod board_handler;
+    pub mod calendar_handler;
     pub mod comment_handler;
+    pub mod custom_field_handler;
     pub mod date_range_handler;
+    pub mod dependency_handler;
+    pub mod history_handler;
     pub mod image_handler;
+    pub mod notification_handler;
     pub mod project_handler;
+    pub mod reaction_handler;
+    pub mod search_handler;
+    pub mod sprint_handler;
     pub mod task_handler;
+    pub mod timeline_handler;
+    pub mod watcher_handler;
     pub mod websocket_handler;
-    
+    pub mod worklog_handler;
 }
 
 pub mod middleware {
@@ -47,16 +95,43 @@ pub mod middleware {
 
 #[cfg(test)]
 pub mod test {
+    pub mod auto_schedule_test;
+    pub mod board_test;
+    pub mod bulk_task_test;
+    pub mod calendar_test;
     pub mod comment_edit_test;
     pub mod comment_integration_test;
+    pub mod comment_mention_test;
+    pub mod comment_moderation_test;
+    pub mod comment_pagination_test;
+    pub mod comment_thread_test;
+    pub mod concurrency_test;
+    pub mod custom_field_test;
+    pub mod date_range_test;
+    pub mod email_notification_test;
+    pub mod highlight_test;
+    pub mod history_test;
+    pub mod jql_test;
+    pub mod lexorank_test;
+    pub mod markdown_test;
+    pub mod notification_test;
     pub mod project_edit_test;
     pub mod project_integration_test;
     pub mod project_membership_test;
     pub mod project_shared_access_test;
+    pub mod reaction_test;
+    pub mod search_test;
     pub mod simple_image_test;
+    pub mod sprint_test;
     pub mod task_creation_test;
     pub mod task_edit_test;
+    pub mod task_list_test;
+    pub mod task_move_test;
+    pub mod task_rank_test;
     pub mod task_read_test;
+    pub mod timeline_test;
+    pub mod watcher_test;
+    pub mod worklog_test;
 }
 
 pub mod router {
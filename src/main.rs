@@ -1,11 +1,15 @@
 use dotenvy::dotenv;
 use shuttle_runtime::SecretStore;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use tokio::sync::broadcast;
 
 use jira_clone_backend::config::Config;
 use jira_clone_backend::db::DatabaseState;
 use jira_clone_backend::router::router::get_app;
+use jira_clone_backend::services::date_range_service::DateRangeService;
+use jira_clone_backend::services::email_notification_service::EmailNotificationService;
+use jira_clone_backend::services::mail_service::mailer_from_config;
+use jira_clone_backend::services::rank_service::RankService;
 use jira_clone_backend::state::AppState;
 
 #[shuttle_runtime::main]
@@ -24,8 +28,33 @@ async fn main(#[shuttle_runtime::Secrets] secrets: SecretStore) -> shuttle_axum:
         })?;
     let db_state = Arc::new(db);
 
+    // Rangos de fechas guardados antes de validarlos que hay que corregir a mano
+    DateRangeService::new(db_state.clone())
+        .log_migration_report()
+        .await;
+
+    db_state.ensure_indexes().await.map_err(|e| {
+        shuttle_runtime::Error::Custom(anyhow::Error::msg(format!("Database error: {}", e)))
+    })?;
+
     let (ws_tx, _) = broadcast::channel(100);
 
+    // Rebalanceo periódico de los rangos de ordenación de tareas
+    RankService::spawn_rebalance_job(
+        db_state.clone(),
+        ws_tx.clone(),
+        Duration::from_secs(60 * 60),
+    );
+
+    // Envío de notificaciones por correo: inmediatas cada minuto y resumen diario
+    let mailer = mailer_from_config(&config).map_err(shuttle_runtime::Error::Custom)?;
+    EmailNotificationService::spawn_email_jobs(
+        db_state.clone(),
+        mailer,
+        Duration::from_secs(60),
+        Duration::from_secs(24 * 60 * 60),
+    );
+
     // Crear el estado compartido de la aplicación
     let app_state = Arc::new(AppState::new(db_state.clone(), config.clone(), ws_tx));
 
@@ -0,0 +1,153 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::models::task_model::{Task, TaskStatus};
+
+// Agrupación opcional de las filas del tablero
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SwimlaneBy {
+    #[default]
+    None,
+    Assignee,
+    Priority,
+    Epic,
+}
+
+// Qué hacer cuando un cambio de estado supera el límite WIP de una columna
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum WipPolicy {
+    #[default]
+    Warn,
+    Reject,
+}
+
+#[derive(Serialize, Deserialize, Validate, Debug, Clone)]
+pub struct BoardColumn {
+    #[validate(length(min = 1, message = "El nombre de la columna no puede estar vacío"))]
+    pub name: String,
+    #[validate(length(min = 1, message = "Cada columna debe tener al menos un estado"))]
+    pub statuses: Vec<TaskStatus>,
+    #[validate(range(min = 1, message = "El límite WIP debe ser mayor que cero"))]
+    pub wip_limit: Option<u32>,
+}
+
+// Configuración del tablero Kanban de un proyecto (uno por proyecto)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Board {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub project_id: ObjectId,
+    pub columns: Vec<BoardColumn>,
+    #[serde(default)]
+    pub swimlane_by: SwimlaneBy,
+    #[serde(default)]
+    pub wip_policy: WipPolicy,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Board {
+    // Tablero por defecto para proyectos que aún no lo han configurado
+    pub fn default_for_project(project_id: ObjectId) -> Self {
+        let now = Utc::now();
+        let column = |name: &str, statuses: Vec<TaskStatus>| BoardColumn {
+            name: name.to_string(),
+            statuses,
+            wip_limit: None,
+        };
+        Board {
+            id: None,
+            project_id,
+            columns: vec![
+                column("Por hacer", vec![TaskStatus::ToDo]),
+                column("En curso", vec![TaskStatus::InProgress]),
+                column("Hecho", vec![TaskStatus::Done, TaskStatus::Cancelled]),
+            ],
+            swimlane_by: SwimlaneBy::None,
+            wip_policy: WipPolicy::Warn,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn column_for_status(&self, status: &TaskStatus) -> Option<&BoardColumn> {
+        self.columns
+            .iter()
+            .find(|column| column.statuses.contains(status))
+    }
+
+    // Estado equivalente en este tablero para una tarea que viene de `source`: se
+    // conserva si tiene columna aquí y, si no, se usa la columna en la misma posición
+    pub fn remap_status(&self, source: &Board, status: &TaskStatus) -> TaskStatus {
+        if self.column_for_status(status).is_some() {
+            return status.clone();
+        }
+        let position = source
+            .columns
+            .iter()
+            .position(|column| column.statuses.contains(status))
+            .unwrap_or(0);
+        self.columns
+            .get(position)
+            .or(self.columns.last())
+            .and_then(|column| column.statuses.first())
+            .cloned()
+            .unwrap_or_else(|| status.clone())
+    }
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct UpdateBoardSchema {
+    #[validate(
+        length(min = 1, message = "El tablero debe tener al menos una columna"),
+        nested
+    )]
+    pub columns: Vec<BoardColumn>,
+    pub swimlane_by: Option<SwimlaneBy>,
+    pub wip_policy: Option<WipPolicy>,
+}
+
+// Columna que supera (o superaría) su límite WIP
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WipBreach {
+    pub column: String,
+    pub wip_limit: u32,
+    pub task_count: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BoardLane {
+    // Clave de la fila (ID de asignado, prioridad o épica); None agrupa lo que no tiene valor
+    pub key: Option<String>,
+    pub label: String,
+    pub tasks: Vec<Task>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BoardColumnView {
+    pub name: String,
+    pub statuses: Vec<TaskStatus>,
+    pub wip_limit: Option<u32>,
+    pub task_count: u64,
+    pub over_limit: bool,
+    pub lanes: Vec<BoardLane>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BoardView {
+    pub project_id: ObjectId,
+    // Si hay un sprint activo el tablero muestra solo sus tareas
+    pub sprint_id: Option<ObjectId>,
+    pub swimlane_by: SwimlaneBy,
+    pub wip_policy: WipPolicy,
+    pub columns: Vec<BoardColumnView>,
+    pub breaches: Vec<WipBreach>,
+    // Tareas cuyo estado no está asignado a ninguna columna
+    pub unmapped_task_count: u64,
+}
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::models::{
+    board_model::WipBreach,
+    task_model::{TaskPriority, TaskStatus},
+};
+
+// Operación a aplicar sobre todas las tareas del lote
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BulkTaskOperation {
+    // Solo se cambian los campos presentes; `assignee_id: "unassigned"` quita el asignado
+    Update {
+        status: Option<TaskStatus>,
+        priority: Option<TaskPriority>,
+        assignee_id: Option<String>,
+    },
+    // Mover a un sprint del proyecto; `sprint_id: null` las devuelve al backlog
+    Move {
+        sprint_id: Option<String>,
+    },
+    Delete,
+    Labels {
+        #[serde(default)]
+        add: Vec<String>,
+        #[serde(default)]
+        remove: Vec<String>,
+    },
+}
+
+impl BulkTaskOperation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BulkTaskOperation::Update { .. } => "update",
+            BulkTaskOperation::Move { .. } => "move",
+            BulkTaskOperation::Delete => "delete",
+            BulkTaskOperation::Labels { .. } => "labels",
+        }
+    }
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct BulkTaskSchema {
+    #[validate(length(min = 1, max = 200, message = "Debes indicar entre 1 y 200 tareas"))]
+    pub task_ids: Vec<String>,
+    pub operation: BulkTaskOperation,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkItemStatus {
+    Ok,
+    // ID mal formado
+    Invalid,
+    // No existe o no pertenece al proyecto
+    NotFound,
+    // El usuario no puede aplicar la operación sobre esta tarea
+    Forbidden,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BulkItemResult {
+    pub task_id: String,
+    pub status: BulkItemStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BulkTaskResponse {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BulkItemResult>,
+    // Límite WIP superado por el lote con la política `warn`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wip_warning: Option<WipBreach>,
+}
@@ -0,0 +1,59 @@
+use chrono::{DateTime, NaiveDate, Utc, Weekday};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+// Día festivo del calendario de un proyecto, en su fecha local
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Holiday {
+    pub date: NaiveDate,
+    pub name: String,
+}
+
+// Calendario laboral de un proyecto: días de la semana laborables, zona horaria (IANA, p. ej.
+// `Europe/Madrid`) y festivos. Un proyecto sin calendario trabaja todos los días en UTC, que es
+// como se calculaban las fechas antes de existir los calendarios.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProjectCalendar {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub project_id: ObjectId,
+    pub time_zone: String,
+    pub working_days: Vec<Weekday>,
+    #[serde(default)]
+    pub holidays: Vec<Holiday>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ProjectCalendar {
+    pub fn default_for(project_id: ObjectId) -> Self {
+        Self {
+            id: None,
+            project_id,
+            time_zone: "UTC".to_string(),
+            working_days: vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+                Weekday::Sat,
+                Weekday::Sun,
+            ],
+            holidays: Vec::new(),
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct UpdateCalendarSchema {
+    #[validate(length(min = 1, max = 64, message = "La zona horaria es obligatoria"))]
+    pub time_zone: String,
+    #[validate(length(min = 1, max = 7, message = "Debe haber al menos un día laborable"))]
+    pub working_days: Vec<Weekday>,
+    #[serde(default)]
+    #[validate(length(max = 1000, message = "Como máximo 1000 festivos"))]
+    pub holidays: Vec<Holiday>,
+}
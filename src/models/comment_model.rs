@@ -3,7 +3,10 @@ use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
-use super::user_model::UserData;
+use super::{
+    reaction_model::{ReactionSummary, Reactions},
+    user_model::UserData,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Comment {
@@ -11,25 +14,103 @@ pub struct Comment {
     pub id: Option<ObjectId>,
     pub task_id: ObjectId,
     pub user_id: ObjectId,
+    // Comentario al que responde; las respuestas no admiten respuestas
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<ObjectId>,
+    // Texto en Markdown tal como lo escribió el autor
     pub content: String,
+    // HTML saneado del contenido (ver utils::markdown)
+    #[serde(default)]
+    pub content_html: String,
+    // Miembros del proyecto mencionados con `@usuario` en el contenido
+    #[serde(default)]
+    pub mentions: Vec<ObjectId>,
+    #[serde(default)]
+    pub reactions: Reactions,
+    // Comentario borrado que se conserva vacío porque tiene respuestas
+    #[serde(default)]
+    pub deleted: bool,
+    // Versiones anteriores del contenido, de la más antigua a la más reciente
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub revisions: Vec<CommentRevision>,
+    // Presente si un moderador ha ocultado el comentario
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub moderation: Option<CommentModeration>,
+    #[serde(default)]
+    pub version: i64,
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub created_at: DateTime<Utc>,
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub updated_at: DateTime<Utc>,
+    // Último cambio visible (edición, reacción, moderación o borrado) para la sincronización
+    // incremental; `updated_at` solo cambia al editar el contenido
+    #[serde(
+        default = "Utc::now",
+        with = "bson::serde_helpers::chrono_datetime_as_bson_datetime"
+    )]
+    pub changed_at: DateTime<Utc>,
+}
+
+// Contenido de un comentario antes de una edición
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommentRevision {
+    pub content: String,
+    // Cuándo se escribió esta versión y cuándo la sustituyó la siguiente
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub written_at: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub replaced_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommentModeration {
+    pub moderator_id: ObjectId,
+    // Solo lo ven los administradores del proyecto
+    pub reason: String,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub hidden_at: DateTime<Utc>,
 }
 
 #[derive(Deserialize, Validate, Debug)]
 pub struct CreateCommentSchema {
     #[validate(length(min = 1, message = "El comentario no puede estar vacío"))]
     pub content: String,
+    // Responder a otro comentario de la misma tarea
+    pub parent_id: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct CommentData {
     pub id: String,
     pub task_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
     pub author: UserData,
     pub content: String,
+    #[serde(default)]
+    pub content_html: String,
+    // IDs de los usuarios mencionados
+    #[serde(default)]
+    pub mentions: Vec<String>,
+    #[serde(default)]
+    pub reactions: Vec<ReactionSummary>,
+    #[serde(default)]
+    pub deleted: bool,
+    // El contenido ha cambiado desde que se publicó
+    #[serde(default)]
+    pub edited: bool,
+    // Ocultado por moderación; el contenido solo lo ven los administradores
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub moderation_reason: Option<String>,
+    // Respuestas en orden cronológico; solo los comentarios de primer nivel tienen
+    #[serde(default)]
+    pub reply_count: usize,
+    #[serde(default)]
+    pub replies: Vec<CommentData>,
+    #[serde(default)]
+    pub version: i64,
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub created_at: DateTime<Utc>,
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
@@ -41,3 +122,50 @@ pub struct UpdateCommentSchema {
     #[validate(length(min = 1, message = "El comentario no puede estar vacío"))]
     pub content: String,
 }
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct HideCommentSchema {
+    #[validate(length(
+        min = 3,
+        max = 500,
+        message = "El motivo debe tener entre 3 y 500 caracteres"
+    ))]
+    pub reason: String,
+}
+
+// Historial de ediciones de un comentario
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CommentRevisions {
+    pub comment_id: String,
+    pub content: String,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: DateTime<Utc>,
+    pub revisions: Vec<CommentRevision>,
+}
+
+// Parámetros del listado de comentarios de una tarea
+#[derive(Deserialize, Validate, Debug, Default)]
+pub struct CommentListQuery {
+    #[validate(range(min = 1, max = 100, message = "El límite debe estar entre 1 y 100"))]
+    pub limit: Option<u32>,
+    // Cursores de `next_cursor` y `prev_cursor`; solo se admite uno de los dos
+    pub after: Option<String>,
+    pub before: Option<String>,
+    // Solo los cambios posteriores a esta fecha (normalmente el `synced_at` anterior)
+    pub since: Option<DateTime<Utc>>,
+}
+
+// Página de hilos de comentarios, del más antiguo al más reciente
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CommentPage {
+    pub items: Vec<CommentData>,
+    // Comentarios de primer nivel de la tarea
+    pub total: u64,
+    pub next_cursor: Option<String>,
+    pub prev_cursor: Option<String>,
+    // Con `since`: comentarios eliminados desde entonces
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deleted_ids: Vec<String>,
+    // Momento de la consulta, para usarlo como `since` en la siguiente sincronización
+    pub synced_at: DateTime<Utc>,
+}
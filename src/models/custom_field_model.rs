@@ -0,0 +1,217 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use mongodb::bson::{Bson, oid::ObjectId};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomFieldType {
+    Text,
+    Number,
+    // Fecha de calendario, guardada como `YYYY-MM-DD` para ordenar y comparar como texto
+    Date,
+    SingleSelect,
+    MultiSelect,
+    // ID de un miembro del proyecto
+    User,
+}
+
+// Definición de un campo personalizado de un proyecto
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CustomFieldDefinition {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub project_id: ObjectId,
+    // Clave con la que se guarda en `Task.custom_fields` y se filtra (`cf.<key>`)
+    pub key: String,
+    pub name: String,
+    pub field_type: CustomFieldType,
+    #[serde(default)]
+    pub required: bool,
+    // Opciones válidas para los campos de selección
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<String>,
+    // Límites para los campos numéricos
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    // Longitud máxima para los campos de texto
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<u32>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct CreateCustomFieldSchema {
+    #[validate(regex(
+        path = "crate::utils::validation::CUSTOM_FIELD_KEY_REGEX",
+        message = "La clave debe empezar por una letra minúscula y solo contener minúsculas, números y guiones bajos (máx. 32)."
+    ))]
+    pub key: String,
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "El nombre debe tener entre 1 y 100 caracteres"
+    ))]
+    pub name: String,
+    pub field_type: CustomFieldType,
+    pub required: Option<bool>,
+    pub options: Option<Vec<String>>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    #[validate(range(min = 1, message = "La longitud máxima debe ser mayor que cero"))]
+    pub max_length: Option<u32>,
+}
+
+// La clave y el tipo no se pueden cambiar una vez creados
+#[derive(Deserialize, Validate, Debug, Default)]
+pub struct UpdateCustomFieldSchema {
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "El nombre debe tener entre 1 y 100 caracteres"
+    ))]
+    pub name: Option<String>,
+    pub required: Option<bool>,
+    pub options: Option<Vec<String>>,
+    pub min: Option<Option<f64>>,
+    pub max: Option<Option<f64>>,
+    #[validate(range(min = 1, message = "La longitud máxima debe ser mayor que cero"))]
+    pub max_length: Option<Option<u32>>,
+}
+
+pub fn parse_date(value: &str) -> Option<String> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .ok()
+        .map(|date| date.format("%Y-%m-%d").to_string())
+}
+
+impl CustomFieldDefinition {
+    // //* Comprobar que la configuración tiene sentido para el tipo del campo
+    pub fn check_settings(&self) -> Result<(), String> {
+        let is_select = matches!(
+            self.field_type,
+            CustomFieldType::SingleSelect | CustomFieldType::MultiSelect
+        );
+        if is_select {
+            if self.options.is_empty() {
+                return Err(format!(
+                    "El campo '{}' necesita al menos una opción",
+                    self.key
+                ));
+            }
+            let mut seen = std::collections::HashSet::new();
+            for option in &self.options {
+                if option.trim().is_empty() {
+                    return Err("Las opciones no pueden estar vacías".to_string());
+                }
+                if !seen.insert(option.as_str()) {
+                    return Err(format!("Opción repetida: {}", option));
+                }
+            }
+        } else if !self.options.is_empty() {
+            return Err("Solo los campos de selección admiten opciones".to_string());
+        }
+
+        if self.field_type != CustomFieldType::Number && (self.min.is_some() || self.max.is_some())
+        {
+            return Err("Solo los campos numéricos admiten mínimo y máximo".to_string());
+        }
+        if let (Some(min), Some(max)) = (self.min, self.max)
+            && min > max
+        {
+            return Err("El mínimo no puede ser mayor que el máximo".to_string());
+        }
+        if self.field_type != CustomFieldType::Text && self.max_length.is_some() {
+            return Err("Solo los campos de texto admiten longitud máxima".to_string());
+        }
+        Ok(())
+    }
+
+    // //* Convertir el valor recibido en JSON al valor que se guarda en la tarea.
+    // //* `None` significa que el campo queda vacío. Los usuarios solo se validan
+    // //* como ObjectId; la pertenencia al proyecto la comprueba el servicio.
+    pub fn coerce_value(&self, value: &Value) -> Result<Option<Bson>, String> {
+        let invalid = |expected: &str| format!("El campo '{}' debe ser {}", self.key, expected);
+
+        let value = match value {
+            Value::Null => return Ok(None),
+            Value::String(text) if text.trim().is_empty() => return Ok(None),
+            Value::Array(items) if items.is_empty() => return Ok(None),
+            other => other,
+        };
+
+        let coerced = match self.field_type {
+            CustomFieldType::Text => {
+                let text = value.as_str().ok_or_else(|| invalid("un texto"))?.trim();
+                if let Some(max_length) = self.max_length
+                    && text.chars().count() > max_length as usize
+                {
+                    return Err(format!(
+                        "El campo '{}' admite como máximo {} caracteres",
+                        self.key, max_length
+                    ));
+                }
+                Bson::String(text.to_string())
+            }
+            CustomFieldType::Number => {
+                let number = value.as_f64().ok_or_else(|| invalid("un número"))?;
+                if self.min.is_some_and(|min| number < min)
+                    || self.max.is_some_and(|max| number > max)
+                {
+                    return Err(format!(
+                        "El campo '{}' está fuera del rango permitido",
+                        self.key
+                    ));
+                }
+                Bson::Double(number)
+            }
+            CustomFieldType::Date => value
+                .as_str()
+                .and_then(parse_date)
+                .map(Bson::String)
+                .ok_or_else(|| invalid("una fecha con formato YYYY-MM-DD"))?,
+            CustomFieldType::SingleSelect => {
+                let option = value
+                    .as_str()
+                    .ok_or_else(|| invalid("una de sus opciones"))?;
+                Bson::String(self.select_option(option)?)
+            }
+            CustomFieldType::MultiSelect => {
+                let items = value
+                    .as_array()
+                    .ok_or_else(|| invalid("una lista de opciones"))?;
+                let mut selected: Vec<Bson> = Vec::new();
+                for item in items {
+                    let option = item
+                        .as_str()
+                        .ok_or_else(|| invalid("una lista de opciones"))?;
+                    let option = Bson::String(self.select_option(option)?);
+                    if !selected.contains(&option) {
+                        selected.push(option);
+                    }
+                }
+                Bson::Array(selected)
+            }
+            CustomFieldType::User => value
+                .as_str()
+                .and_then(|id| ObjectId::parse_str(id).ok())
+                .map(Bson::ObjectId)
+                .ok_or_else(|| invalid("un ID de usuario válido"))?,
+        };
+        Ok(Some(coerced))
+    }
+
+    pub fn select_option(&self, option: &str) -> Result<String, String> {
+        self.options
+            .iter()
+            .find(|candidate| candidate.as_str() == option)
+            .cloned()
+            .ok_or_else(|| format!("'{}' no es una opción válida de '{}'", option, self.key))
+    }
+}
@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use crate::models::task_model::TaskStatus;
+
+// Dependencia fin-inicio: la sucesora no debería empezar antes de que acabe la predecesora.
+// Las dos tareas son siempre del mismo proyecto.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TaskDependency {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub project_id: ObjectId,
+    pub predecessor_id: ObjectId,
+    pub successor_id: ObjectId,
+    pub created_by: ObjectId,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CreateDependencySchema {
+    pub predecessor_id: String,
+}
+
+// Tarea al otro lado de una dependencia
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DependencyTask {
+    pub task_id: String,
+    pub key: Option<String>,
+    pub title: String,
+    pub status: TaskStatus,
+}
+
+// Predecesoras y sucesoras de una tarea
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TaskDependencies {
+    pub task_id: String,
+    pub predecessors: Vec<DependencyTask>,
+    pub successors: Vec<DependencyTask>,
+}
+
+// Tarea en el cronograma del proyecto. Las fechas tempranas y tardías y la holgura solo se
+// calculan para las tareas con inicio y fin.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TimelineItem {
+    pub task_id: String,
+    pub key: Option<String>,
+    pub title: String,
+    pub status: TaskStatus,
+    pub assignee_id: Option<String>,
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub earliest_start: Option<DateTime<Utc>>,
+    pub earliest_finish: Option<DateTime<Utc>>,
+    pub latest_start: Option<DateTime<Utc>>,
+    pub latest_finish: Option<DateTime<Utc>>,
+    // Duración y holgura en tiempo laborable del calendario del proyecto
+    pub working_days: Option<f64>,
+    pub slack_minutes: Option<i64>,
+    pub slack_working_days: Option<f64>,
+    pub critical: bool,
+    pub overdue: bool,
+}
+
+// Dependencia en el cronograma; `violated` si la sucesora empieza antes de que acabe la
+// predecesora según las fechas planificadas
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TimelineLink {
+    pub predecessor_id: String,
+    pub successor_id: String,
+    pub violated: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Timeline {
+    pub project_id: String,
+    pub time_zone: String,
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    pub items: Vec<TimelineItem>,
+    pub dependencies: Vec<TimelineLink>,
+    // Tareas del camino crítico, de la primera a la última
+    pub critical_path: Vec<String>,
+}
+
+// Cambio de fechas de una tarea al propagar un retraso por sus dependencias
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScheduleShift {
+    pub task_id: String,
+    pub key: Option<String>,
+    pub title: String,
+    pub old_start_date: DateTime<Utc>,
+    pub old_end_date: DateTime<Utc>,
+    pub new_start_date: DateTime<Utc>,
+    pub new_end_date: DateTime<Utc>,
+}
+
+// Vista previa de un cambio de fechas: el rango resultante y los desplazamientos que se
+// aplicarían a las tareas dependientes. Solo se aplican si el proyecto tiene activada la
+// planificación automática (`auto_schedule`).
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReschedulePreview {
+    pub task_id: String,
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub auto_schedule: bool,
+    pub shifts: Vec<ScheduleShift>,
+}
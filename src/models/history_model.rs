@@ -0,0 +1,97 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryAction {
+    Created,
+    Updated,
+    Deleted,
+    DateRangeChanged,
+    CommentAdded,
+    CommentUpdated,
+    CommentDeleted,
+    CommentHidden,
+    CommentRestored,
+    AttachmentAdded,
+    AttachmentRemoved,
+    DependencyAdded,
+    DependencyRemoved,
+}
+
+// Registro de un cambio sobre una tarea. Los valores se guardan como JSON
+// (IDs en hex, fechas en RFC 3339) para mostrarlos tal cual en el historial.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TaskHistoryEntry {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub task_id: ObjectId,
+    pub project_id: ObjectId,
+    pub actor_id: ObjectId,
+    pub action: HistoryAction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_value: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_value: Option<serde_json::Value>,
+    // Comentario o adjunto afectado
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub related_id: Option<ObjectId>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl TaskHistoryEntry {
+    pub fn new(
+        task_id: ObjectId,
+        project_id: ObjectId,
+        actor_id: ObjectId,
+        action: HistoryAction,
+    ) -> Self {
+        Self {
+            id: None,
+            task_id,
+            project_id,
+            actor_id,
+            action,
+            field: None,
+            old_value: None,
+            new_value: None,
+            related_id: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn with_change(
+        mut self,
+        field: &str,
+        old_value: Option<serde_json::Value>,
+        new_value: Option<serde_json::Value>,
+    ) -> Self {
+        self.field = Some(field.to_string());
+        self.old_value = old_value;
+        self.new_value = new_value;
+        self
+    }
+
+    pub fn with_related(mut self, related_id: Option<ObjectId>) -> Self {
+        self.related_id = related_id;
+        self
+    }
+}
+
+#[derive(Deserialize, Validate, Debug, Default)]
+pub struct ActivityQuery {
+    #[validate(range(min = 1, max = 200, message = "El límite debe estar entre 1 y 200"))]
+    pub limit: Option<u32>,
+    pub cursor: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ActivityPage {
+    pub items: Vec<TaskHistoryEntry>,
+    pub next_cursor: Option<String>,
+}
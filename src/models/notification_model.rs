@@ -0,0 +1,179 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::models::task_model::TaskStatus;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    TaskAssigned,
+    Mentioned,
+    // Cambio de estado de una tarea observada
+    StatusChanged,
+    AddedToProject,
+}
+
+// Aviso para un usuario concreto; se guarda en su bandeja y se le envía por WebSocket
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Notification {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    // Destinatario
+    pub user_id: ObjectId,
+    pub kind: NotificationKind,
+    // Usuario que provocó el aviso
+    pub actor_id: ObjectId,
+    pub project_id: ObjectId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<ObjectId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment_id: Option<ObjectId>,
+    // Estado al que pasó la tarea en los cambios de estado
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<TaskStatus>,
+    pub message: String,
+    #[serde(default)]
+    pub read: bool,
+    // Envío por correo pendiente; se borra cuando el correo sale
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<EmailDelivery>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl Notification {
+    pub fn new(
+        user_id: ObjectId,
+        kind: NotificationKind,
+        actor_id: ObjectId,
+        project_id: ObjectId,
+        message: String,
+    ) -> Self {
+        Self {
+            id: None,
+            user_id,
+            kind,
+            actor_id,
+            project_id,
+            task_id: None,
+            comment_id: None,
+            status: None,
+            message,
+            read: false,
+            email: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn with_task(mut self, task_id: Option<ObjectId>) -> Self {
+        self.task_id = task_id;
+        self
+    }
+
+    pub fn with_comment(mut self, comment_id: Option<ObjectId>) -> Self {
+        self.comment_id = comment_id;
+        self
+    }
+}
+
+#[derive(Deserialize, Validate, Debug, Default)]
+pub struct NotificationQuery {
+    // Solo las no leídas
+    pub unread: Option<bool>,
+    #[validate(range(min = 1, max = 200, message = "El límite debe estar entre 1 y 200"))]
+    pub limit: Option<u32>,
+    pub cursor: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct NotificationPage {
+    pub items: Vec<Notification>,
+    pub unread_count: u64,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct NotificationCount {
+    pub unread_count: u64,
+}
+
+// Cómo quiere recibir el usuario por correo cada tipo de aviso
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailDelivery {
+    Immediate,
+    // Agrupado en el resumen diario
+    Digest,
+    Off,
+}
+
+pub const SUPPORTED_LOCALES: [&str; 2] = ["es", "en"];
+
+fn default_locale() -> String {
+    SUPPORTED_LOCALES[0].to_string()
+}
+
+fn default_delivery() -> EmailDelivery {
+    EmailDelivery::Immediate
+}
+
+fn default_status_delivery() -> EmailDelivery {
+    EmailDelivery::Digest
+}
+
+// Preferencias de correo de un usuario; quien no las ha guardado usa las de por defecto
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NotificationPreferences {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub user_id: ObjectId,
+    // Idioma de los correos
+    #[serde(default = "default_locale")]
+    pub locale: String,
+    #[serde(default = "default_delivery")]
+    pub task_assigned: EmailDelivery,
+    #[serde(default = "default_delivery")]
+    pub mentioned: EmailDelivery,
+    #[serde(default = "default_status_delivery")]
+    pub status_changed: EmailDelivery,
+    #[serde(default = "default_delivery")]
+    pub added_to_project: EmailDelivery,
+}
+
+impl NotificationPreferences {
+    pub fn default_for(user_id: ObjectId) -> Self {
+        Self {
+            id: None,
+            user_id,
+            locale: default_locale(),
+            task_assigned: default_delivery(),
+            mentioned: default_delivery(),
+            status_changed: default_status_delivery(),
+            added_to_project: default_delivery(),
+        }
+    }
+
+    pub fn delivery_for(&self, kind: NotificationKind) -> EmailDelivery {
+        match kind {
+            NotificationKind::TaskAssigned => self.task_assigned,
+            NotificationKind::Mentioned => self.mentioned,
+            NotificationKind::StatusChanged => self.status_changed,
+            NotificationKind::AddedToProject => self.added_to_project,
+        }
+    }
+}
+
+#[derive(Deserialize, Validate, Debug, Default)]
+pub struct UpdateNotificationPreferencesSchema {
+    #[validate(regex(
+        path = "crate::utils::validation::LOCALE_REGEX",
+        message = "Idioma no soportado; usa 'es' o 'en'"
+    ))]
+    pub locale: Option<String>,
+    pub task_assigned: Option<EmailDelivery>,
+    pub mentioned: Option<EmailDelivery>,
+    pub status_changed: Option<EmailDelivery>,
+    pub added_to_project: Option<EmailDelivery>,
+}
@@ -15,6 +15,12 @@ pub struct Project {
     pub owner_id: ObjectId,
     #[serde(default)]
     pub members: Vec<ObjectId>, // Lista de IDs de miembros del proyecto
+    #[serde(default)]
+    pub task_counter: i64, // Último número usado en las claves de tarea (`KEY-n`)
+    #[serde(default)]
+    pub version: i64, // Se incrementa en cada edición; se expone como ETag
+    #[serde(default)]
+    pub auto_schedule: bool, // Retrasar las tareas dependientes al retrasarse una tarea
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub created_at: DateTime<Utc>,
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
@@ -51,6 +57,7 @@ pub struct UpdateProjectSchema {
     ))]
     pub name: Option<String>,
     pub description: Option<String>,
+    pub auto_schedule: Option<bool>,
 }
 
 #[derive(Deserialize, Validate, Debug)]
@@ -0,0 +1,38 @@
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use validator::Validate;
+
+// Reacciones de una tarea o un comentario: código del emoji -> usuarios que reaccionaron
+pub type Reactions = BTreeMap<String, Vec<ObjectId>>;
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct ToggleReactionSchema {
+    // Código corto del emoji, sin los dos puntos (`thumbsup`, `+1`, `tada`...)
+    #[validate(regex(
+        path = "crate::utils::validation::REACTION_REGEX",
+        message = "El código del emoji solo puede contener minúsculas, números, '_', '+' o '-' (máximo 32)"
+    ))]
+    pub emoji: String,
+}
+
+// Recuento de una reacción para el usuario que consulta
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReactionSummary {
+    pub emoji: String,
+    pub count: usize,
+    pub reacted_by_me: bool,
+}
+
+// Resumen ordenado por código de emoji; se omiten las reacciones sin usuarios
+pub fn summarize_reactions(reactions: &Reactions, user_id: ObjectId) -> Vec<ReactionSummary> {
+    reactions
+        .iter()
+        .filter(|(_, users)| !users.is_empty())
+        .map(|(emoji, users)| ReactionSummary {
+            emoji: emoji.clone(),
+            count: users.len(),
+            reacted_by_me: users.contains(&user_id),
+        })
+        .collect()
+}
@@ -0,0 +1,67 @@
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::models::task_model::Task;
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct JqlSearchQuery {
+    #[validate(length(max = 2000, message = "La consulta es demasiado larga"))]
+    pub jql: String,
+    #[serde(default)]
+    #[validate(range(max = 100000, message = "start_at no puede superar 100000"))]
+    pub start_at: u64,
+    #[validate(range(min = 1, max = 200, message = "max_results debe estar entre 1 y 200"))]
+    pub max_results: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SearchResult {
+    pub total: u64,
+    pub start_at: u64,
+    pub max_results: u32,
+    pub items: Vec<Task>,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct TextSearchQuery {
+    #[validate(length(
+        min = 2,
+        max = 200,
+        message = "La búsqueda debe tener entre 2 y 200 caracteres"
+    ))]
+    pub q: String,
+    #[serde(default)]
+    #[validate(range(max = 100000, message = "start_at no puede superar 100000"))]
+    pub start_at: u64,
+    #[validate(range(min = 1, max = 100, message = "max_results debe estar entre 1 y 100"))]
+    pub max_results: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TextSearchHitKind {
+    Task,
+    Comment,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TextSearchHit {
+    pub kind: TextSearchHitKind,
+    pub score: f64,
+    pub project_id: ObjectId,
+    pub task_id: ObjectId,
+    pub task_title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment_id: Option<ObjectId>,
+    // Fragmento HTML escapado con las coincidencias en <mark>
+    pub snippet: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TextSearchResult {
+    pub total: u64,
+    pub start_at: u64,
+    pub max_results: u32,
+    pub items: Vec<TextSearchHit>,
+}
@@ -0,0 +1,77 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum SprintState {
+    Planned,
+    Active,
+    Closed,
+}
+
+// Iteración de trabajo dentro de un proyecto
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Sprint {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub project_id: ObjectId,
+    pub name: String,
+    pub goal: Option<String>,
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub state: SprintState,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct CreateSprintSchema {
+    #[validate(length(min = 1, message = "El nombre del sprint no puede estar vacío"))]
+    pub name: String,
+    pub goal: Option<String>,
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Validate, Debug, Default)]
+pub struct UpdateSprintSchema {
+    #[validate(length(min = 1, message = "El nombre del sprint no puede estar vacío"))]
+    pub name: Option<String>,
+    pub goal: Option<String>,
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct SprintTasksSchema {
+    #[validate(length(min = 1, message = "Debes indicar al menos una tarea"))]
+    pub task_ids: Vec<String>,
+}
+
+// Destino de las tareas incompletas al cerrar un sprint
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IncompleteTasksTarget {
+    #[default]
+    Backlog,
+    NextSprint,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct CloseSprintSchema {
+    #[serde(default)]
+    pub move_to: IncompleteTasksTarget,
+    // Sprint destino explícito; si no se indica se usa el siguiente planificado
+    pub next_sprint_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CloseSprintResult {
+    pub sprint: Sprint,
+    pub completed_tasks: u64,
+    pub moved_tasks: u64,
+    pub moved_to_sprint_id: Option<ObjectId>,
+}
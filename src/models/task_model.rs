@@ -1,7 +1,10 @@
-use chrono::{DateTime, Utc};
-use mongodb::bson::oid::ObjectId;
+use chrono::{DateTime, Duration, Utc};
+use mongodb::bson::{Document, oid::ObjectId};
 use serde::{Deserialize, Serialize};
-use validator::Validate;
+use std::collections::HashMap;
+use validator::{Validate, ValidationError};
+
+use super::{board_model::WipBreach, reaction_model::Reactions};
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum TaskStatus {
@@ -24,13 +27,57 @@ pub struct Task {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
     pub id: Option<ObjectId>,
     pub project_id: ObjectId,
+    // Clave legible `PROYECTO-n`; las tareas anteriores a las claves no la tienen
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    // Claves anteriores de la tarea, que siguen resolviendo tras moverla de proyecto
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub key_aliases: Vec<String>,
     pub title: String,
+    // Descripción en Markdown y su HTML saneado (ver utils::markdown)
     pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description_html: Option<String>,
     pub status: TaskStatus,
     pub priority: TaskPriority,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub assignee_id: Option<ObjectId>,
     pub reporter_id: ObjectId,
+    // Estimaciones expresadas en minutos
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_estimate_minutes: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining_estimate_minutes: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub story_points: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sprint_id: Option<ObjectId>,
+    // Tarea épica a la que pertenece (mismo proyecto)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epic_id: Option<ObjectId>,
+    // Posición manual dentro del proyecto (ver utils::lexorank)
+    #[serde(default)]
+    pub rank: String,
+    // Valores de los campos personalizados del proyecto, por clave
+    #[serde(default, skip_serializing_if = "Document::is_empty")]
+    pub custom_fields: Document,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+    // Usuarios que siguen los cambios de la tarea: informador, asignado y quien se suscriba
+    #[serde(default)]
+    pub watchers: Vec<ObjectId>,
+    #[serde(default, skip_serializing_if = "Reactions::is_empty")]
+    pub reactions: Reactions,
+    // Comentarios de la tarea; solo lo rellena el listado de tareas y no se guarda
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment_count: Option<u64>,
+    // Límite WIP superado con la política `warn`; solo lo rellenan la creación, la edición y
+    // el movimiento de la tarea y no se guarda
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wip_warning: Option<WipBreach>,
+    // Se incrementa en cada edición; se expone como ETag
+    #[serde(default)]
+    pub version: i64,
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub created_at: DateTime<Utc>,
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
@@ -45,6 +92,16 @@ pub struct CreateTaskSchema {
     pub status: Option<TaskStatus>,
     pub priority: Option<TaskPriority>,
     pub assignee_id: Option<String>,
+    #[validate(range(min = 0, message = "La estimación original no puede ser negativa"))]
+    pub original_estimate_minutes: Option<i64>,
+    #[validate(range(min = 0, message = "La estimación restante no puede ser negativa"))]
+    pub remaining_estimate_minutes: Option<i64>,
+    #[validate(range(min = 0.0, message = "Los story points no pueden ser negativos"))]
+    pub story_points: Option<f64>,
+    pub epic_id: Option<String>,
+    // Valores de campos personalizados por clave
+    pub custom_fields: Option<HashMap<String, serde_json::Value>>,
+    pub labels: Option<Vec<String>>,
     pub created_at: Option<DateTime<Utc>>, // Fecha de creación manual
     pub updated_at: Option<DateTime<Utc>>, // Fecha de actualización manual
 }
@@ -57,24 +114,183 @@ pub struct UpdateTaskSchema {
     pub status: Option<TaskStatus>,
     pub priority: Option<TaskPriority>,
     pub assignee_id: Option<Option<String>>,
+    #[validate(range(min = 0, message = "La estimación original no puede ser negativa"))]
+    pub original_estimate_minutes: Option<Option<i64>>,
+    #[validate(range(min = 0, message = "La estimación restante no puede ser negativa"))]
+    pub remaining_estimate_minutes: Option<Option<i64>>,
+    #[validate(range(min = 0.0, message = "Los story points no pueden ser negativos"))]
+    pub story_points: Option<Option<f64>>,
+    pub epic_id: Option<Option<String>>,
+    // Solo se modifican las claves enviadas; `null` vacía el campo
+    pub custom_fields: Option<HashMap<String, serde_json::Value>>,
+    // Reemplaza la lista completa de etiquetas
+    pub labels: Option<Vec<String>>,
     pub updated_at: Option<DateTime<Utc>>, // Fecha de actualización manual
 }
 
-#[derive(Serialize, Deserialize, Validate, Debug)]
+// Nuevos vecinos de la tarea tras arrastrarla en el tablero
+#[derive(Deserialize, Debug, Default)]
+pub struct RankTaskSchema {
+    // Tarea que quedará justo antes (encima) de la tarea movida
+    pub before_task_id: Option<String>,
+    // Tarea que quedará justo después (debajo) de la tarea movida
+    pub after_task_id: Option<String>,
+}
+
+// Mover una tarea a otro proyecto
+#[derive(Deserialize, Debug)]
+pub struct MoveTaskSchema {
+    pub project_id: String,
+    // Estado en el proyecto destino; si no se indica se elige según sus columnas
+    pub status: Option<TaskStatus>,
+}
+
+// Filtros, orden y paginación del listado de tareas de un proyecto
+#[derive(Deserialize, Validate, Debug, Default)]
+pub struct TaskListQuery {
+    // Listas separadas por comas, p. ej. `status=ToDo,InProgress`
+    pub status: Option<String>,
+    pub priority: Option<String>,
+    // IDs de usuario, `me` o `unassigned`
+    pub assignee: Option<String>,
+    // IDs de usuario o `me`
+    pub reporter: Option<String>,
+    pub created_from: Option<DateTime<Utc>>,
+    pub created_to: Option<DateTime<Utc>>,
+    pub updated_from: Option<DateTime<Utc>>,
+    pub updated_to: Option<DateTime<Utc>>,
+    // Tareas con alguna de estas etiquetas
+    pub labels: Option<String>,
+    // Texto a buscar en el título (sin distinguir mayúsculas)
+    pub q: Option<String>,
+    // Campo de orden: rank, created_at, updated_at, priority, status, title o `cf.<clave>`;
+    // `-` para descendente
+    pub sort: Option<String>,
+    #[validate(range(min = 1, max = 200, message = "El límite debe estar entre 1 y 200"))]
+    pub limit: Option<u32>,
+    pub cursor: Option<String>,
+}
+
+// Etiquetas sin espacios sobrantes, sin vacías ni repetidas
+pub fn normalize_labels(labels: &[String]) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for label in labels.iter().map(|label| label.trim()) {
+        if label.is_empty() || label.chars().count() > 50 {
+            return Err("Las etiquetas deben tener entre 1 y 50 caracteres".to_string());
+        }
+        if !normalized.iter().any(|existing| existing == label) {
+            normalized.push(label.to_string());
+        }
+    }
+    Ok(normalized)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TaskPage {
+    pub items: Vec<Task>,
+    pub total: u64,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Validate, Debug, Clone)]
+#[validate(schema(function = "validate_date_range"))]
 pub struct DateRange {
     pub task_id: ObjectId,
     pub start_date: Option<DateTime<Utc>>,
     pub end_date: Option<DateTime<Utc>>,
 }
 
+// Días naturales que puede abarcar un rango: caben los 3650 días laborables de la duración
+// máxima con fines de semana y festivos. El calendario laboral recorre los rangos día a día.
+pub const MAX_RANGE_DAYS: i64 = 7300;
+
+// El fin puede coincidir con el inicio, pero no ser anterior ni estar demasiado lejos
+fn validate_date_range(range: &DateRange) -> Result<(), ValidationError> {
+    if let (Some(start), Some(end)) = (range.start_date, range.end_date)
+        && end - start > Duration::days(MAX_RANGE_DAYS)
+    {
+        let mut error = ValidationError::new("range_too_long");
+        error.message = Some(
+            format!(
+                "El rango de fechas no puede abarcar más de {} días",
+                MAX_RANGE_DAYS
+            )
+            .into(),
+        );
+        return Err(error);
+    }
+    if let (Some(start), Some(end)) = (range.start_date, range.end_date)
+        && end < start
+    {
+        let mut error = ValidationError::new("end_before_start");
+        error.message = Some(
+            format!(
+                "La fecha de fin ({}) no puede ser anterior a la de inicio ({})",
+                end.to_rfc3339(),
+                start.to_rfc3339()
+            )
+            .into(),
+        );
+        return Err(error);
+    }
+    Ok(())
+}
+
+// Problemas de los rangos guardados antes de validar las fechas
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DateRangeProblem {
+    EndBeforeStart,
+    // Más de un rango para la misma tarea
+    Duplicate,
+}
+
+// Entrada del informe de migración de rangos de fechas
+#[derive(Serialize, Deserialize, Debug)]
+pub struct InvalidDateRange {
+    pub range_id: ObjectId,
+    pub task_id: ObjectId,
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub problems: Vec<DateRangeProblem>,
+}
+
+// `working_days` calcula el fin a partir del inicio con el calendario del proyecto, en lugar
+// de indicarlo
 #[derive(Deserialize, Validate, Debug)]
 pub struct CreateDateRangeSchema {
     pub start_date: Option<DateTime<Utc>>,
     pub end_date: Option<DateTime<Utc>>,
+    #[validate(range(
+        min = 1,
+        max = 3650,
+        message = "La duración debe estar entre 1 y 3650 días"
+    ))]
+    pub working_days: Option<u32>,
 }
 
 #[derive(Deserialize, Validate, Debug, Default)]
 pub struct UpdateDateRangeSchema {
     pub start_date: Option<Option<DateTime<Utc>>>,
     pub end_date: Option<Option<DateTime<Utc>>>,
+    #[validate(range(
+        min = 1,
+        max = 3650,
+        message = "La duración debe estar entre 1 y 3650 días"
+    ))]
+    pub working_days: Option<u32>,
+}
+
+// Tarea sin terminar que ha pasado su vencimiento
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OverdueTask {
+    pub task_id: String,
+    pub key: Option<String>,
+    pub title: String,
+    pub status: TaskStatus,
+    pub assignee_id: Option<String>,
+    pub end_date: DateTime<Utc>,
+    // El fin o, si cae en un día no laborable, el comienzo del siguiente laborable
+    pub due_at: DateTime<Utc>,
+    pub overdue_working_days: f64,
 }
@@ -0,0 +1,27 @@
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::models::{task_model::Task, user_model::UserData};
+
+// Observadores de una tarea y si el usuario autenticado es uno de ellos
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TaskWatchers {
+    pub task_id: ObjectId,
+    pub watching: bool,
+    pub watchers: Vec<UserData>,
+}
+
+#[derive(Deserialize, Validate, Debug, Default)]
+pub struct WatchedTasksQuery {
+    #[validate(range(min = 1, max = 200, message = "El límite debe estar entre 1 y 200"))]
+    pub limit: Option<u32>,
+    pub cursor: Option<String>,
+}
+
+// Tareas observadas, de la modificada más recientemente a la más antigua
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WatchedTasksPage {
+    pub items: Vec<Task>,
+    pub next_cursor: Option<String>,
+}
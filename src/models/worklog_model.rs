@@ -0,0 +1,64 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+// Registro de trabajo (tiempo imputado) sobre una tarea
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Worklog {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub task_id: ObjectId,
+    pub project_id: ObjectId,
+    pub user_id: ObjectId,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub started_at: DateTime<Utc>,
+    pub duration_minutes: i64,
+    pub comment: Option<String>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct CreateWorklogSchema {
+    pub started_at: Option<DateTime<Utc>>, // Por defecto, el momento actual
+    #[validate(range(min = 1, message = "La duración debe ser de al menos 1 minuto"))]
+    pub duration_minutes: i64,
+    pub comment: Option<String>,
+}
+
+#[derive(Deserialize, Validate, Debug, Default)]
+pub struct UpdateWorklogSchema {
+    pub started_at: Option<DateTime<Utc>>,
+    #[validate(range(min = 1, message = "La duración debe ser de al menos 1 minuto"))]
+    pub duration_minutes: Option<i64>,
+    pub comment: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct TimesheetQuery {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+}
+
+// Una fila del reporte: minutos imputados por un usuario a una tarea en un día
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TimesheetEntry {
+    pub date: String,
+    pub user_id: String,
+    pub username: String,
+    pub task_id: String,
+    pub task_title: String,
+    pub project_id: String,
+    pub minutes: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TimesheetReport {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub total_minutes: i64,
+    pub entries: Vec<TimesheetEntry>,
+}
@@ -1,37 +1,80 @@
 use crate::{
     handlers::{
         auth_handler::{get_me_handler, login_handler, register_handler},
+        board_handler::{get_board_handler, update_board_handler},
+        calendar_handler::{
+            get_project_calendar_handler, import_project_holidays_handler,
+            update_project_calendar_handler,
+        },
         comment_handler::{
-            create_comment_handler, delete_comment_handler, get_comments_handler,
+            create_comment_handler, delete_comment_handler, get_comment_revisions_handler,
+            get_comments_handler, hide_comment_handler, unhide_comment_handler,
             update_comment_handler,
         },
+        custom_field_handler::{
+            create_custom_field_handler, delete_custom_field_handler, list_custom_fields_handler,
+            update_custom_field_handler,
+        },
         date_range_handler::{
             delete_task_date_range_handler, get_project_date_ranges_handler,
-            get_task_date_range_handler, set_task_date_range_handler,
+            get_project_overdue_tasks_handler, get_task_date_range_handler,
+            preview_task_date_range_handler, set_task_date_range_handler,
             update_task_date_range_handler,
         },
+        dependency_handler::{
+            add_task_dependency_handler, get_task_dependencies_handler,
+            remove_task_dependency_handler,
+        },
+        history_handler::{get_project_activity_handler, get_task_history_handler},
         image_handler::{
             delete_image_handler, download_image_handler, get_image_info_handler,
             list_project_images_handler, list_task_images_handler, list_user_images_handler,
             update_image_handler, upload_image_handler,
         },
+        notification_handler::{
+            get_notification_preferences_handler, list_notifications_handler,
+            mark_all_notifications_read_handler, mark_notification_read_handler,
+            update_notification_preferences_handler,
+        },
         project_handler::{
             add_member_handler, create_project_handler, delete_project_handler,
             get_project_handler, list_members_handler, remove_member_handler,
             update_project_handler,
         },
+        reaction_handler::{
+            get_task_reactions_handler, toggle_comment_reaction_handler,
+            toggle_task_reaction_handler,
+        },
+        search_handler::{search_jql_handler, search_text_handler},
+        sprint_handler::{
+            add_sprint_tasks_handler, close_sprint_handler, create_sprint_handler,
+            delete_sprint_handler, get_project_backlog_handler, get_project_sprints_handler,
+            get_sprint_tasks_handler, remove_sprint_task_handler, start_sprint_handler,
+            update_sprint_handler,
+        },
         task_handler::{
-            create_task_handler, delete_task_handler, get_task_by_id_handler,
-            get_task_for_project_handler, get_task_with_date_range_handler, update_task_handler,
+            bulk_tasks_handler, create_task_handler, delete_task_handler, get_task_by_id_handler,
+            get_task_by_key_handler, get_task_for_project_handler,
+            get_task_with_date_range_handler, move_task_handler, rank_task_handler,
+            update_task_handler,
+        },
+        timeline_handler::get_project_timeline_handler,
+        watcher_handler::{
+            get_task_watchers_handler, get_watched_tasks_handler, unwatch_task_handler,
+            watch_task_handler,
         },
         websocket_handler::websocket_handler,
+        worklog_handler::{
+            create_worklog_handler, delete_worklog_handler, get_my_timesheet_handler,
+            get_project_timesheet_handler, get_worklogs_handler, update_worklog_handler,
+        },
     },
     middleware::auth_middleware::auth_guard,
     state::AppState,
 };
 use axum::{
     Router, middleware,
-    routing::{delete, get, patch, post},
+    routing::{delete, get, patch, post, put},
 };
 use std::sync::Arc;
 
@@ -63,7 +106,10 @@ pub fn get_app(app_state: Arc<AppState>) -> Router {
             axum::http::header::AUTHORIZATION,
             axum::http::header::CONTENT_TYPE,
             axum::http::header::ACCEPT,
+            axum::http::header::IF_MATCH,
         ])
+        // El cliente necesita leer el ETag para enviarlo luego en If-Match
+        .expose_headers([axum::http::header::ETAG])
         .allow_credentials(true);
 
     let protected_routes = Router::new()
@@ -77,6 +123,10 @@ pub fn get_app(app_state: Arc<AppState>) -> Router {
             get(get_task_for_project_handler),
         )
         .route("/projects/{project_id}/tasks", post(create_task_handler))
+        .route(
+            "/projects/{project_id}/tasks/bulk",
+            post(bulk_tasks_handler),
+        )
         .route("/tasks/{task_id}", get(get_task_by_id_handler))
         .route(
             "/tasks/{task_id}/full",
@@ -84,6 +134,9 @@ pub fn get_app(app_state: Arc<AppState>) -> Router {
         )
         .route("/tasks/{task_id}", patch(update_task_handler))
         .route("/tasks/{task_id}", delete(delete_task_handler))
+        .route("/tasks/{task_id}/rank", post(rank_task_handler))
+        .route("/tasks/{task_id}/move", post(move_task_handler))
+        .route("/tasks/key/{key}", get(get_task_by_key_handler))
         .route("/projects/{project_id}/members", post(add_member_handler))
         .route("/projects/{project_id}/members", get(list_members_handler))
         .route(
@@ -100,6 +153,19 @@ pub fn get_app(app_state: Arc<AppState>) -> Router {
             "/tasks/{task_id}/comments/{comment_id}",
             delete(delete_comment_handler),
         )
+        .route(
+            "/tasks/{task_id}/comments/{comment_id}/revisions",
+            get(get_comment_revisions_handler),
+        )
+        // Moderación de comentarios por los administradores del proyecto
+        .route(
+            "/tasks/{task_id}/comments/{comment_id}/hide",
+            post(hide_comment_handler),
+        )
+        .route(
+            "/tasks/{task_id}/comments/{comment_id}/hide",
+            delete(unhide_comment_handler),
+        )
         // Endpoints para rangos de fechas
         .route(
             "/tasks/{task_id}/date-range",
@@ -117,10 +183,48 @@ pub fn get_app(app_state: Arc<AppState>) -> Router {
             "/tasks/{task_id}/date-range",
             delete(delete_task_date_range_handler),
         )
+        .route(
+            "/tasks/{task_id}/date-range/preview",
+            post(preview_task_date_range_handler),
+        )
         .route(
             "/projects/{project_id}/date-ranges",
             get(get_project_date_ranges_handler),
         )
+        .route(
+            "/projects/{project_id}/overdue",
+            get(get_project_overdue_tasks_handler),
+        )
+        // Calendario laboral del proyecto
+        .route(
+            "/projects/{project_id}/calendar",
+            get(get_project_calendar_handler),
+        )
+        .route(
+            "/projects/{project_id}/calendar",
+            put(update_project_calendar_handler),
+        )
+        .route(
+            "/projects/{project_id}/calendar/holidays/import",
+            post(import_project_holidays_handler),
+        )
+        // Dependencias entre tareas y cronograma del proyecto
+        .route(
+            "/tasks/{task_id}/dependencies",
+            get(get_task_dependencies_handler),
+        )
+        .route(
+            "/tasks/{task_id}/dependencies",
+            post(add_task_dependency_handler),
+        )
+        .route(
+            "/tasks/{task_id}/dependencies/{predecessor_id}",
+            delete(remove_task_dependency_handler),
+        )
+        .route(
+            "/projects/{project_id}/timeline",
+            get(get_project_timeline_handler),
+        )
         // Endpoints para imágenes
         .route("/images", post(upload_image_handler))
         .route("/images", get(list_user_images_handler))
@@ -128,8 +232,116 @@ pub fn get_app(app_state: Arc<AppState>) -> Router {
         .route("/images/{image_id}/download", get(download_image_handler))
         .route("/images/{image_id}", patch(update_image_handler))
         .route("/images/{image_id}", delete(delete_image_handler))
-        .route("/projects/{project_id}/images", get(list_project_images_handler))
+        .route(
+            "/projects/{project_id}/images",
+            get(list_project_images_handler),
+        )
         .route("/tasks/{task_id}/images", get(list_task_images_handler))
+        // Endpoints para registro de tiempo
+        .route("/tasks/{task_id}/worklogs", post(create_worklog_handler))
+        .route("/tasks/{task_id}/worklogs", get(get_worklogs_handler))
+        .route(
+            "/tasks/{task_id}/worklogs/{worklog_id}",
+            patch(update_worklog_handler),
+        )
+        .route(
+            "/tasks/{task_id}/worklogs/{worklog_id}",
+            delete(delete_worklog_handler),
+        )
+        .route(
+            "/projects/{project_id}/timesheet",
+            get(get_project_timesheet_handler),
+        )
+        .route("/me/timesheet", get(get_my_timesheet_handler))
+        // Endpoints para sprints y backlog
+        .route(
+            "/projects/{project_id}/sprints",
+            post(create_sprint_handler),
+        )
+        .route(
+            "/projects/{project_id}/sprints",
+            get(get_project_sprints_handler),
+        )
+        .route(
+            "/projects/{project_id}/backlog",
+            get(get_project_backlog_handler),
+        )
+        .route("/sprints/{sprint_id}", patch(update_sprint_handler))
+        .route("/sprints/{sprint_id}", delete(delete_sprint_handler))
+        .route("/sprints/{sprint_id}/start", post(start_sprint_handler))
+        .route("/sprints/{sprint_id}/close", post(close_sprint_handler))
+        .route("/sprints/{sprint_id}/tasks", get(get_sprint_tasks_handler))
+        .route("/sprints/{sprint_id}/tasks", post(add_sprint_tasks_handler))
+        .route(
+            "/sprints/{sprint_id}/tasks/{task_id}",
+            delete(remove_sprint_task_handler),
+        )
+        // Búsqueda de tareas entre proyectos
+        .route("/search", get(search_jql_handler))
+        .route("/search/text", get(search_text_handler))
+        // Endpoints para el tablero Kanban
+        .route("/projects/{project_id}/board", get(get_board_handler))
+        .route("/projects/{project_id}/board", put(update_board_handler))
+        // Campos personalizados del proyecto
+        .route(
+            "/projects/{project_id}/custom-fields",
+            get(list_custom_fields_handler),
+        )
+        .route(
+            "/projects/{project_id}/custom-fields",
+            post(create_custom_field_handler),
+        )
+        .route(
+            "/projects/{project_id}/custom-fields/{key}",
+            patch(update_custom_field_handler),
+        )
+        .route(
+            "/projects/{project_id}/custom-fields/{key}",
+            delete(delete_custom_field_handler),
+        )
+        // Historial de cambios y actividad del proyecto
+        .route("/tasks/{task_id}/history", get(get_task_history_handler))
+        .route(
+            "/projects/{project_id}/activity",
+            get(get_project_activity_handler),
+        )
+        // Observadores de tareas
+        .route("/tasks/{task_id}/watch", post(watch_task_handler))
+        .route("/tasks/{task_id}/watch", delete(unwatch_task_handler))
+        .route("/tasks/{task_id}/watchers", get(get_task_watchers_handler))
+        // Reacciones con emoji en tareas y comentarios
+        .route(
+            "/tasks/{task_id}/reactions",
+            get(get_task_reactions_handler),
+        )
+        .route(
+            "/tasks/{task_id}/reactions",
+            post(toggle_task_reaction_handler),
+        )
+        .route(
+            "/tasks/{task_id}/comments/{comment_id}/reactions",
+            post(toggle_comment_reaction_handler),
+        )
+        .route("/me/watching", get(get_watched_tasks_handler))
+        // Bandeja de notificaciones del usuario
+        .route("/notifications", get(list_notifications_handler))
+        .route(
+            "/notifications/read-all",
+            post(mark_all_notifications_read_handler),
+        )
+        .route(
+            "/notifications/{notification_id}/read",
+            post(mark_notification_read_handler),
+        )
+        // Preferencias de correo
+        .route(
+            "/me/notification-preferences",
+            get(get_notification_preferences_handler),
+        )
+        .route(
+            "/me/notification-preferences",
+            patch(update_notification_preferences_handler),
+        )
         .layer(auth_middleware);
 
     let auth_routes = Router::new()
@@ -0,0 +1,404 @@
+use bson::to_bson;
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::{
+    Collection,
+    bson::{Bson, Document, doc, oid::ObjectId},
+};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::broadcast;
+use validator::Validate;
+
+use crate::{
+    db::DatabaseState,
+    errors::AppError,
+    models::{
+        board_model::{
+            Board, BoardColumn, BoardColumnView, BoardLane, BoardView, SwimlaneBy,
+            UpdateBoardSchema, WipBreach, WipPolicy,
+        },
+        sprint_model::{Sprint, SprintState},
+        task_model::{Task, TaskPriority, TaskStatus},
+        user_model::User,
+    },
+    services::permission_service::PermissionService,
+};
+
+pub struct BoardService {
+    db_state: Arc<DatabaseState>,
+    ws_tx: broadcast::Sender<String>,
+}
+
+impl BoardService {
+    pub fn new(db_state: Arc<DatabaseState>, ws_tx: broadcast::Sender<String>) -> Self {
+        Self { db_state, ws_tx }
+    }
+
+    fn board_collection(&self) -> Collection<Board> {
+        self.db_state.get_db().collection::<Board>("boards")
+    }
+
+    fn task_collection(&self) -> Collection<Task> {
+        self.db_state.get_db().collection::<Task>("tasks")
+    }
+
+    // //* Configuración guardada del tablero o la configuración por defecto
+    pub async fn load_board(&self, project_id: ObjectId) -> Result<Board, AppError> {
+        let board = self
+            .board_collection()
+            .find_one(doc! {"project_id": project_id})
+            .await
+            .map_err(|_| AppError::InternalServerError)?;
+
+        Ok(board.unwrap_or_else(|| Board::default_for_project(project_id)))
+    }
+
+    async fn active_sprint_id(&self, project_id: ObjectId) -> Result<Option<ObjectId>, AppError> {
+        let sprint = self
+            .db_state
+            .get_db()
+            .collection::<Sprint>("sprints")
+            .find_one(doc! {
+                "project_id": project_id,
+                "state": to_bson(&SprintState::Active).unwrap(),
+            })
+            .await
+            .map_err(|_| AppError::InternalServerError)?;
+
+        Ok(sprint.and_then(|sprint| sprint.id))
+    }
+
+    // Tareas visibles en el tablero: las del sprint activo o, si no hay, todas las del proyecto
+    fn scope_filter(project_id: ObjectId, sprint_id: Option<ObjectId>) -> Document {
+        match sprint_id {
+            Some(sprint_id) => doc! {"project_id": project_id, "sprint_id": sprint_id},
+            None => doc! {"project_id": project_id},
+        }
+    }
+
+    fn statuses_bson(statuses: &[TaskStatus]) -> Vec<Bson> {
+        statuses
+            .iter()
+            .map(|status| to_bson(status).unwrap())
+            .collect()
+    }
+
+    // //* Reemplazar la configuración del tablero (solo el dueño del proyecto)
+    pub async fn update_board(
+        &self,
+        project_id: ObjectId,
+        user_id: ObjectId,
+        schema: UpdateBoardSchema,
+    ) -> Result<Board, AppError> {
+        schema
+            .validate()
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+        PermissionService::new(self.db_state.get_db())
+            .is_project_owner(project_id, user_id)
+            .await?;
+
+        // Un estado solo puede pertenecer a una columna
+        let mut seen: Vec<&TaskStatus> = Vec::new();
+        for status in schema.columns.iter().flat_map(|column| &column.statuses) {
+            if seen.contains(&status) {
+                return Err(AppError::ValidationError(format!(
+                    "El estado {:?} está asignado a más de una columna",
+                    status
+                )));
+            }
+            seen.push(status);
+        }
+
+        let current = self.load_board(project_id).await?;
+        let board = Board {
+            id: current.id,
+            project_id,
+            columns: schema.columns,
+            swimlane_by: schema.swimlane_by.unwrap_or(current.swimlane_by),
+            wip_policy: schema.wip_policy.unwrap_or(current.wip_policy),
+            created_at: current.created_at,
+            updated_at: Utc::now(),
+        };
+
+        self.board_collection()
+            .replace_one(doc! {"project_id": project_id}, &board)
+            .upsert(true)
+            .await
+            .map_err(|_| AppError::InternalServerError)?;
+
+        let broadcast_message = serde_json::json!({
+            "event_type": "BOARD_UPDATED",
+            "project_id": project_id.to_hex(),
+            "board": board,
+        })
+        .to_string();
+        if let Err(e) = self.ws_tx.send(broadcast_message) {
+            tracing::warn!("Error enviando mensaje WebSocket de tablero: {}", e);
+        }
+
+        self.load_board(project_id).await
+    }
+
+    // //* Tablero con las tareas agrupadas por columna y fila
+    pub async fn get_board(
+        &self,
+        project_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<BoardView, AppError> {
+        PermissionService::new(self.db_state.get_db())
+            .can_access_project(project_id, user_id)
+            .await?;
+
+        let board = self.load_board(project_id).await?;
+        let sprint_id = self.active_sprint_id(project_id).await?;
+
+        let tasks: Vec<Task> = self
+            .task_collection()
+            .find(Self::scope_filter(project_id, sprint_id))
+            .sort(doc! {"rank": 1, "created_at": 1})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let lanes = self.lane_definitions(board.swimlane_by, &tasks).await?;
+
+        let mut columns = Vec::with_capacity(board.columns.len());
+        let mut breaches = Vec::new();
+        for column in &board.columns {
+            let column_tasks: Vec<&Task> = tasks
+                .iter()
+                .filter(|task| column.statuses.contains(&task.status))
+                .collect();
+            let task_count = column_tasks.len() as u64;
+            let over_limit = column
+                .wip_limit
+                .is_some_and(|limit| task_count > limit as u64);
+            if let Some(wip_limit) = column.wip_limit
+                && over_limit
+            {
+                breaches.push(WipBreach {
+                    column: column.name.clone(),
+                    wip_limit,
+                    task_count,
+                });
+            }
+
+            let column_lanes = lanes
+                .iter()
+                .map(|(key, label)| BoardLane {
+                    key: key.clone(),
+                    label: label.clone(),
+                    tasks: column_tasks
+                        .iter()
+                        .filter(|task| Self::lane_key(board.swimlane_by, task) == *key)
+                        .map(|task| (*task).clone())
+                        .collect(),
+                })
+                .collect();
+
+            columns.push(BoardColumnView {
+                name: column.name.clone(),
+                statuses: column.statuses.clone(),
+                wip_limit: column.wip_limit,
+                task_count,
+                over_limit,
+                lanes: column_lanes,
+            });
+        }
+
+        let unmapped_task_count = tasks
+            .iter()
+            .filter(|task| board.column_for_status(&task.status).is_none())
+            .count() as u64;
+
+        Ok(BoardView {
+            project_id,
+            sprint_id,
+            swimlane_by: board.swimlane_by,
+            wip_policy: board.wip_policy,
+            columns,
+            breaches,
+            unmapped_task_count,
+        })
+    }
+
+    fn lane_key(swimlane_by: SwimlaneBy, task: &Task) -> Option<String> {
+        match swimlane_by {
+            SwimlaneBy::None => None,
+            SwimlaneBy::Assignee => task.assignee_id.map(|id| id.to_hex()),
+            SwimlaneBy::Priority => Some(format!("{:?}", task.priority)),
+            SwimlaneBy::Epic => task.epic_id.map(|id| id.to_hex()),
+        }
+    }
+
+    // //* Filas del tablero en orden de aparición; la fila sin valor va al final
+    async fn lane_definitions(
+        &self,
+        swimlane_by: SwimlaneBy,
+        tasks: &[Task],
+    ) -> Result<Vec<(Option<String>, String)>, AppError> {
+        match swimlane_by {
+            SwimlaneBy::None => Ok(vec![(None, "Todas las tareas".to_string())]),
+            SwimlaneBy::Priority => Ok([
+                (TaskPriority::Urgent, "Urgente"),
+                (TaskPriority::High, "Alta"),
+                (TaskPriority::Medium, "Media"),
+                (TaskPriority::Low, "Baja"),
+            ]
+            .into_iter()
+            .map(|(priority, label)| (Some(format!("{:?}", priority)), label.to_string()))
+            .collect()),
+            SwimlaneBy::Assignee | SwimlaneBy::Epic => {
+                let mut ids: Vec<ObjectId> = Vec::new();
+                for task in tasks {
+                    let id = match swimlane_by {
+                        SwimlaneBy::Assignee => task.assignee_id,
+                        _ => task.epic_id,
+                    };
+                    if let Some(id) = id
+                        && !ids.contains(&id)
+                    {
+                        ids.push(id);
+                    }
+                }
+
+                let labels = if swimlane_by == SwimlaneBy::Assignee {
+                    self.usernames(&ids).await?
+                } else {
+                    self.task_titles(&ids).await?
+                };
+                let empty_label = if swimlane_by == SwimlaneBy::Assignee {
+                    "Sin asignar"
+                } else {
+                    "Sin épica"
+                };
+
+                let mut lanes: Vec<(Option<String>, String)> = ids
+                    .iter()
+                    .map(|id| {
+                        let label = labels.get(id).cloned().unwrap_or_else(|| id.to_hex());
+                        (Some(id.to_hex()), label)
+                    })
+                    .collect();
+                lanes.push((None, empty_label.to_string()));
+                Ok(lanes)
+            }
+        }
+    }
+
+    async fn usernames(&self, ids: &[ObjectId]) -> Result<HashMap<ObjectId, String>, AppError> {
+        let users: Vec<User> = self
+            .db_state
+            .get_db()
+            .collection::<User>("users")
+            .find(doc! {"_id": {"$in": ids.to_vec()}})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(users
+            .into_iter()
+            .filter_map(|user| user.id.map(|id| (id, user.username)))
+            .collect())
+    }
+
+    async fn task_titles(&self, ids: &[ObjectId]) -> Result<HashMap<ObjectId, String>, AppError> {
+        let tasks: Vec<Task> = self
+            .task_collection()
+            .find(doc! {"_id": {"$in": ids.to_vec()}})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(tasks
+            .into_iter()
+            .filter_map(|task| task.id.map(|id| (id, task.title)))
+            .collect())
+    }
+
+    // //* Comprobar si mover una tarea a `new_status` supera el límite WIP de su columna.
+    // //* Con la política `Reject` devuelve un conflicto; con `Warn` devuelve el aviso.
+    pub async fn check_wip_limit(
+        &self,
+        project_id: ObjectId,
+        task_sprint_id: Option<ObjectId>,
+        previous_status: Option<&TaskStatus>,
+        new_status: &TaskStatus,
+    ) -> Result<Option<WipBreach>, AppError> {
+        self.check_wip_limit_for(project_id, &[(task_sprint_id, previous_status)], new_status)
+            .await
+    }
+
+    // //* Igual que `check_wip_limit` para varias tareas que pasan a la vez al mismo
+    // //* estado; cada par es (sprint de la tarea, estado anterior)
+    pub async fn check_wip_limit_for(
+        &self,
+        project_id: ObjectId,
+        moving: &[(Option<ObjectId>, Option<&TaskStatus>)],
+        new_status: &TaskStatus,
+    ) -> Result<Option<WipBreach>, AppError> {
+        let board = self.load_board(project_id).await?;
+        let Some(BoardColumn {
+            name,
+            statuses,
+            wip_limit: Some(wip_limit),
+        }) = board.column_for_status(new_status)
+        else {
+            return Ok(None);
+        };
+
+        // Moverse dentro de la misma columna no cambia su ocupación, y con un sprint
+        // activo las tareas fuera de él no aparecen en el tablero
+        let sprint_id = self.active_sprint_id(project_id).await?;
+        let entering = moving
+            .iter()
+            .filter(|(task_sprint_id, previous_status)| {
+                !previous_status.is_some_and(|status| statuses.contains(status))
+                    && (sprint_id.is_none() || sprint_id == *task_sprint_id)
+            })
+            .count() as u64;
+        if entering == 0 {
+            return Ok(None);
+        }
+
+        let mut filter = Self::scope_filter(project_id, sprint_id);
+        filter.insert("status", doc! {"$in": Self::statuses_bson(statuses)});
+        let current = self
+            .task_collection()
+            .count_documents(filter)
+            .await
+            .map_err(|_| AppError::InternalServerError)?;
+
+        if current + entering <= *wip_limit as u64 {
+            return Ok(None);
+        }
+
+        let breach = WipBreach {
+            column: name.clone(),
+            wip_limit: *wip_limit,
+            task_count: current + entering,
+        };
+        match board.wip_policy {
+            WipPolicy::Reject => Err(AppError::Conflict(format!(
+                "La columna '{}' superaría su límite WIP de {} tareas",
+                breach.column, breach.wip_limit
+            ))),
+            WipPolicy::Warn => {
+                tracing::warn!(
+                    "La columna '{}' supera su límite WIP ({} de {})",
+                    breach.column,
+                    breach.task_count,
+                    breach.wip_limit
+                );
+                Ok(Some(breach))
+            }
+        }
+    }
+}
@@ -0,0 +1,408 @@
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::{
+    Collection,
+    bson::{Bson, DateTime, Document, doc, oid::ObjectId, to_bson},
+};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::broadcast;
+use validator::Validate;
+
+use crate::{
+    db::DatabaseState,
+    errors::AppError,
+    models::{
+        board_model::WipBreach,
+        bulk_task_model::{
+            BulkItemResult, BulkItemStatus, BulkTaskOperation, BulkTaskResponse, BulkTaskSchema,
+        },
+        history_model::{HistoryAction, TaskHistoryEntry},
+        project_models::Project,
+        sprint_model::{Sprint, SprintState},
+        task_model::{Task, TaskStatus, normalize_labels},
+    },
+    services::{
+        board_service::BoardService,
+        history_service::{HistoryService, history_value},
+        notification_service::NotificationService,
+        permission_service::PermissionService,
+    },
+};
+
+pub struct BulkTaskService {
+    db_state: Arc<DatabaseState>,
+    ws_tx: broadcast::Sender<String>,
+}
+
+impl BulkTaskService {
+    pub fn new(db_state: Arc<DatabaseState>, ws_tx: broadcast::Sender<String>) -> Self {
+        Self { db_state, ws_tx }
+    }
+
+    fn task_collection(&self) -> Collection<Task> {
+        self.db_state.get_db().collection::<Task>("tasks")
+    }
+
+    // //* Aplicar una operación a muchas tareas de un proyecto. Cada tarea se comprueba
+    // //* por separado y se informa en `results`; las válidas se modifican con una sola
+    // //* escritura y se emite un único evento en tiempo real.
+    pub async fn apply(
+        &self,
+        project_id: ObjectId,
+        user_id: ObjectId,
+        schema: BulkTaskSchema,
+    ) -> Result<BulkTaskResponse, AppError> {
+        schema
+            .validate()
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+        let project = PermissionService::new(self.db_state.get_db())
+            .can_access_project(project_id, user_id)
+            .await?;
+
+        // IDs repetidos cuentan una sola vez
+        let mut requested: Vec<String> = Vec::new();
+        for task_id in schema.task_ids {
+            if !requested.contains(&task_id) {
+                requested.push(task_id);
+            }
+        }
+        let parsed: Vec<ObjectId> = requested
+            .iter()
+            .filter_map(|task_id| ObjectId::parse_str(task_id).ok())
+            .collect();
+
+        let found: HashMap<ObjectId, Task> = self
+            .task_collection()
+            .find(doc! {"_id": {"$in": &parsed}})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .try_collect::<Vec<Task>>()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .into_iter()
+            .filter_map(|task| Some((task.id?, task)))
+            .collect();
+
+        let mut results = Vec::with_capacity(requested.len());
+        let mut eligible: Vec<Task> = Vec::new();
+        for task_id in &requested {
+            let (status, error) = match ObjectId::parse_str(task_id) {
+                Err(_) => (BulkItemStatus::Invalid, Some("ID de tarea inválido")),
+                Ok(id) => match found.get(&id) {
+                    Some(task) if task.project_id == project_id => {
+                        if Self::can_apply(&schema.operation, &project, task, user_id) {
+                            eligible.push(task.clone());
+                            (BulkItemStatus::Ok, None)
+                        } else {
+                            (
+                                BulkItemStatus::Forbidden,
+                                Some("No tienes permiso para eliminar esta tarea"),
+                            )
+                        }
+                    }
+                    // Una tarea de otro proyecto se trata como inexistente
+                    _ => (
+                        BulkItemStatus::NotFound,
+                        Some("La tarea no existe en este proyecto"),
+                    ),
+                },
+            };
+            results.push(BulkItemResult {
+                task_id: task_id.clone(),
+                status,
+                error: error.map(str::to_string),
+            });
+        }
+
+        let succeeded = eligible.len();
+        let failed = results.len() - succeeded;
+        let wip_warning = if eligible.is_empty() {
+            None
+        } else {
+            self.execute(&project, user_id, &schema.operation, &eligible)
+                .await?
+        };
+
+        Ok(BulkTaskResponse {
+            succeeded,
+            failed,
+            results,
+            wip_warning,
+        })
+    }
+
+    // //* Misma regla que al eliminar una tarea suelta: dueño del proyecto o asignado.
+    // //* El resto de operaciones las puede hacer cualquier miembro.
+    fn can_apply(
+        operation: &BulkTaskOperation,
+        project: &Project,
+        task: &Task,
+        user_id: ObjectId,
+    ) -> bool {
+        match operation {
+            BulkTaskOperation::Delete => {
+                project.owner_id == user_id || task.assignee_id == Some(user_id)
+            }
+            _ => true,
+        }
+    }
+
+    async fn execute(
+        &self,
+        project: &Project,
+        user_id: ObjectId,
+        operation: &BulkTaskOperation,
+        tasks: &[Task],
+    ) -> Result<Option<WipBreach>, AppError> {
+        let project_id = project.id.ok_or(AppError::InternalServerError)?;
+        let ids: Vec<ObjectId> = tasks.iter().filter_map(|task| task.id).collect();
+        let filter = doc! {"_id": {"$in": &ids}, "project_id": project_id};
+        let now = DateTime::from_chrono(Utc::now());
+
+        let mut wip_warning = None;
+        let update: Vec<Document> = match operation {
+            BulkTaskOperation::Delete => {
+                self.task_collection()
+                    .delete_many(filter)
+                    .await
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                // Las dependencias de las tareas borradas dejan de tener sentido
+                self.db_state
+                    .get_db()
+                    .collection::<Document>("task_dependencies")
+                    .delete_many(doc! {"$or": [
+                        {"predecessor_id": {"$in": &ids}},
+                        {"successor_id": {"$in": &ids}},
+                    ]})
+                    .await
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+                // El historial se conserva para la actividad del proyecto
+                let entries = tasks
+                    .iter()
+                    .filter_map(|task| {
+                        Some(
+                            TaskHistoryEntry::new(
+                                task.id?,
+                                project_id,
+                                user_id,
+                                HistoryAction::Deleted,
+                            )
+                            .with_change(
+                                "title",
+                                history_value(&task.title),
+                                None,
+                            ),
+                        )
+                    })
+                    .collect();
+                HistoryService::new(self.db_state.clone())
+                    .record(entries)
+                    .await;
+
+                self.broadcast(project_id, operation, &ids, &[], None);
+                return Ok(None);
+            }
+            BulkTaskOperation::Update {
+                status,
+                priority,
+                assignee_id,
+            } => {
+                let mut set = doc! {};
+                if let Some(status) = status {
+                    wip_warning = self.check_wip_limit(project_id, tasks, status).await?;
+                    set.insert("status", to_bson(status).unwrap());
+                }
+                if let Some(priority) = priority {
+                    set.insert("priority", to_bson(priority).unwrap());
+                }
+                if let Some(assignee) = assignee_id.as_deref() {
+                    let assignee = Self::resolve_assignee(project, assignee)?;
+                    // El nuevo asignado pasa a observar las tareas
+                    if let Bson::ObjectId(assignee_id) = assignee {
+                        let watchers = doc! {"$ifNull": ["$watchers", []]};
+                        set.insert(
+                            "watchers",
+                            doc! {"$cond": [
+                                {"$in": [assignee_id, &watchers]},
+                                &watchers,
+                                {"$concatArrays": [&watchers, [assignee_id]]},
+                            ]},
+                        );
+                    }
+                    set.insert("assignee_id", assignee);
+                }
+                if set.is_empty() {
+                    return Err(AppError::ValidationError(
+                        "Indica al menos un campo a modificar".to_string(),
+                    ));
+                }
+                vec![doc! {"$set": set}]
+            }
+            BulkTaskOperation::Move { sprint_id } => match sprint_id {
+                Some(sprint_id) => {
+                    let sprint_id = self.resolve_sprint(project_id, sprint_id).await?;
+                    vec![doc! {"$set": {"sprint_id": sprint_id}}]
+                }
+                None => vec![doc! {"$unset": "sprint_id"}],
+            },
+            BulkTaskOperation::Labels { add, remove } => {
+                let add = normalize_labels(add).map_err(AppError::ValidationError)?;
+                let remove = normalize_labels(remove).map_err(AppError::ValidationError)?;
+                if add.is_empty() && remove.is_empty() {
+                    return Err(AppError::ValidationError(
+                        "Indica etiquetas para añadir o quitar".to_string(),
+                    ));
+                }
+                // Quitar primero y añadir después, conservando el orden existente.
+                // $literal evita que una etiqueta que empiece por `$` se lea como campo
+                vec![doc! {"$set": {"labels": {"$concatArrays": [
+                    {"$filter": {
+                        "input": {"$ifNull": ["$labels", []]},
+                        "cond": {"$not": [{"$in": ["$$this", {"$literal": &remove}]}]},
+                    }},
+                    {"$filter": {
+                        "input": {"$literal": &add},
+                        "cond": {"$not": [{"$in": ["$$this", {"$ifNull": ["$labels", []]}]}]},
+                    }},
+                ]}}}]
+            }
+        };
+
+        // Todas las tareas del lote se actualizan con una sola escritura
+        let mut pipeline = update;
+        pipeline.push(doc! {"$set": {
+            "updated_at": now,
+            "version": {"$add": [{"$ifNull": ["$version", 0]}, 1]},
+        }});
+        self.task_collection()
+            .update_many(filter.clone(), pipeline)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let updated: Vec<Task> = self
+            .task_collection()
+            .find(filter)
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let entries = updated
+            .iter()
+            .filter_map(|after| {
+                let before = tasks.iter().find(|task| task.id == after.id)?;
+                Some(HistoryService::task_changes(before, after, user_id))
+            })
+            .flatten()
+            .collect();
+        HistoryService::new(self.db_state.clone())
+            .record(entries)
+            .await;
+
+        let notifications = updated
+            .iter()
+            .filter_map(|after| {
+                let before = tasks.iter().find(|task| task.id == after.id)?;
+                Some(NotificationService::task_notifications(
+                    Some(before),
+                    after,
+                    user_id,
+                ))
+            })
+            .flatten()
+            .collect();
+        NotificationService::new(self.db_state.clone(), self.ws_tx.clone())
+            .notify(notifications)
+            .await;
+
+        self.broadcast(project_id, operation, &ids, &updated, wip_warning.as_ref());
+        Ok(wip_warning)
+    }
+
+    // //* El límite WIP se comprueba con todas las tareas del lote a la vez
+    async fn check_wip_limit(
+        &self,
+        project_id: ObjectId,
+        tasks: &[Task],
+        status: &TaskStatus,
+    ) -> Result<Option<WipBreach>, AppError> {
+        let moving: Vec<(Option<ObjectId>, Option<&TaskStatus>)> = tasks
+            .iter()
+            .filter(|task| task.status != *status)
+            .map(|task| (task.sprint_id, Some(&task.status)))
+            .collect();
+
+        BoardService::new(self.db_state.clone(), self.ws_tx.clone())
+            .check_wip_limit_for(project_id, &moving, status)
+            .await
+    }
+
+    fn resolve_assignee(project: &Project, assignee: &str) -> Result<Bson, AppError> {
+        if assignee == "unassigned" {
+            return Ok(Bson::Null);
+        }
+        let assignee_id = ObjectId::parse_str(assignee).map_err(|_| {
+            AppError::ValidationError("El ID del asignado no es válido".to_string())
+        })?;
+        if project.owner_id != assignee_id && !project.members.contains(&assignee_id) {
+            return Err(AppError::ValidationError(
+                "El asignado no pertenece al proyecto".to_string(),
+            ));
+        }
+        Ok(Bson::ObjectId(assignee_id))
+    }
+
+    async fn resolve_sprint(
+        &self,
+        project_id: ObjectId,
+        sprint_id: &str,
+    ) -> Result<ObjectId, AppError> {
+        let sprint_id = ObjectId::parse_str(sprint_id)
+            .map_err(|_| AppError::ValidationError("ID de sprint inválido".to_string()))?;
+        let sprint = self
+            .db_state
+            .get_db()
+            .collection::<Sprint>("sprints")
+            .find_one(doc! {"_id": sprint_id, "project_id": project_id})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .ok_or_else(|| {
+                AppError::ValidationError("El sprint no existe en este proyecto".to_string())
+            })?;
+        if sprint.state == SprintState::Closed {
+            return Err(AppError::ValidationError(
+                "No se pueden añadir tareas a un sprint cerrado".to_string(),
+            ));
+        }
+        Ok(sprint_id)
+    }
+
+    fn broadcast(
+        &self,
+        project_id: ObjectId,
+        operation: &BulkTaskOperation,
+        task_ids: &[ObjectId],
+        tasks: &[Task],
+        wip_warning: Option<&WipBreach>,
+    ) {
+        let broadcast_message = serde_json::json!({
+            "event_type": "TASKS_BULK_UPDATED",
+            "project_id": project_id.to_hex(),
+            "operation": operation.name(),
+            "task_ids": task_ids.iter().map(|id| id.to_hex()).collect::<Vec<_>>(),
+            "tasks": tasks,
+            "wip_warning": wip_warning,
+        })
+        .to_string();
+
+        if let Err(e) = self.ws_tx.send(broadcast_message) {
+            tracing::warn!(
+                "Error enviando mensaje WebSocket para la operación masiva: {}",
+                e
+            );
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use chrono::{Utc, Weekday};
+use chrono_tz::Tz;
+use mongodb::{
+    Collection,
+    bson::{doc, oid::ObjectId, to_bson},
+    options::{FindOneAndUpdateOptions, ReturnDocument},
+};
+use std::{collections::BTreeMap, sync::Arc};
+use validator::Validate;
+
+use crate::{
+    db::DatabaseState,
+    errors::AppError,
+    models::{
+        calendar_model::{Holiday, ProjectCalendar, UpdateCalendarSchema},
+        project_models::Project,
+    },
+    services::permission_service::PermissionService,
+    utils::calendar::{WorkCalendar, parse_icalendar_holidays},
+};
+
+pub struct CalendarService {
+    db_state: Arc<DatabaseState>,
+}
+
+impl CalendarService {
+    pub fn new(db_state: Arc<DatabaseState>) -> Self {
+        Self { db_state }
+    }
+
+    fn calendar_collection(&self) -> Collection<ProjectCalendar> {
+        self.db_state
+            .get_db()
+            .collection::<ProjectCalendar>("project_calendars")
+    }
+
+    // //* Calendario guardado del proyecto o el de por defecto, sin comprobar permisos
+    pub async fn project_calendar(
+        &self,
+        project_id: ObjectId,
+    ) -> Result<ProjectCalendar, AppError> {
+        let calendar = self
+            .calendar_collection()
+            .find_one(doc! {"project_id": project_id})
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        Ok(calendar.unwrap_or_else(|| ProjectCalendar::default_for(project_id)))
+    }
+
+    // //* Calendario del proyecto listo para calcular con él, sin comprobar permisos
+    pub async fn work_calendar(&self, project_id: ObjectId) -> Result<WorkCalendar, AppError> {
+        let calendar = self.project_calendar(project_id).await?;
+        // La zona se valida al guardarla; una inválida en la base de datos se trata como UTC
+        let time_zone = calendar.time_zone.parse::<Tz>().unwrap_or(Tz::UTC);
+        Ok(WorkCalendar::new(
+            time_zone,
+            calendar.working_days,
+            calendar.holidays.into_iter().map(|holiday| holiday.date),
+        ))
+    }
+
+    // //* Obtener el calendario laboral de un proyecto
+    pub async fn get_calendar(
+        &self,
+        project_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<ProjectCalendar, AppError> {
+        PermissionService::new(self.db_state.get_db())
+            .can_access_project(project_id, user_id)
+            .await?;
+        self.project_calendar(project_id).await
+    }
+
+    // //! Solo el administrador del proyecto cambia su calendario
+    async fn find_admin_project(
+        &self,
+        project_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<Project, AppError> {
+        let project = PermissionService::new(self.db_state.get_db())
+            .can_access_project(project_id, user_id)
+            .await?;
+        if !PermissionService::is_project_admin(&project, user_id) {
+            return Err(AppError::Unauthorized(
+                "Solo el dueño del proyecto puede cambiar su calendario".to_string(),
+            ));
+        }
+        Ok(project)
+    }
+
+    // //* Sustituir el calendario laboral de un proyecto
+    pub async fn update_calendar(
+        &self,
+        project_id: ObjectId,
+        user_id: ObjectId,
+        schema: UpdateCalendarSchema,
+    ) -> Result<ProjectCalendar, AppError> {
+        schema
+            .validate()
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+        if schema.time_zone.parse::<Tz>().is_err() {
+            return Err(AppError::ValidationError(format!(
+                "Zona horaria desconocida: {}",
+                schema.time_zone
+            )));
+        }
+        self.find_admin_project(project_id, user_id).await?;
+
+        let mut working_days = schema.working_days;
+        working_days.sort_by_key(|day| day.num_days_from_monday());
+        working_days.dedup();
+
+        self.save_calendar(
+            project_id,
+            &schema.time_zone,
+            &working_days,
+            sorted_holidays(schema.holidays),
+        )
+        .await
+    }
+
+    // //* Añadir al calendario los festivos de un fichero iCalendar. Un festivo importado en una
+    // //* fecha que ya lo era sustituye su nombre.
+    pub async fn import_holidays(
+        &self,
+        project_id: ObjectId,
+        user_id: ObjectId,
+        source: &str,
+    ) -> Result<ProjectCalendar, AppError> {
+        let imported = parse_icalendar_holidays(source).map_err(AppError::ValidationError)?;
+        self.find_admin_project(project_id, user_id).await?;
+
+        let calendar = self.project_calendar(project_id).await?;
+        let mut holidays = calendar.holidays;
+        holidays.extend(imported);
+
+        self.save_calendar(
+            project_id,
+            &calendar.time_zone,
+            &calendar.working_days,
+            sorted_holidays(holidays),
+        )
+        .await
+    }
+
+    async fn save_calendar(
+        &self,
+        project_id: ObjectId,
+        time_zone: &str,
+        working_days: &[Weekday],
+        holidays: Vec<Holiday>,
+    ) -> Result<ProjectCalendar, AppError> {
+        self.calendar_collection()
+            .find_one_and_update(
+                doc! {"project_id": project_id},
+                doc! {
+                    "$set": {
+                        "time_zone": time_zone,
+                        "working_days": to_bson(working_days)
+                            .map_err(|_| AppError::InternalServerError)?,
+                        "holidays": to_bson(&holidays).map_err(|_| AppError::InternalServerError)?,
+                        "updated_at": Utc::now(),
+                    }
+                },
+            )
+            .with_options(
+                FindOneAndUpdateOptions::builder()
+                    .upsert(true)
+                    .return_document(ReturnDocument::After)
+                    .build(),
+            )
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or(AppError::InternalServerError)
+    }
+}
+
+// Festivos por fecha, uno por día; si se repite una fecha gana el último
+fn sorted_holidays(holidays: Vec<Holiday>) -> Vec<Holiday> {
+    holidays
+        .into_iter()
+        .map(|holiday| (holiday.date, holiday))
+        .collect::<BTreeMap<_, _>>()
+        .into_values()
+        .collect()
+}
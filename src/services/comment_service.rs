@@ -1,41 +1,69 @@
-use chrono::Utc;
-use futures::stream::StreamExt;
+use chrono::{DateTime, Utc};
+use futures::{TryStreamExt, stream::StreamExt};
 use mongodb::{
     Collection,
-    bson::{Document, doc, from_document, oid::ObjectId},
+    bson::{Bson, Document, doc, from_document, oid::ObjectId, to_bson},
 };
 use std::sync::Arc;
+use tokio::sync::broadcast;
 use validator::Validate;
 
 use crate::{
     db::DatabaseState,
     errors::AppError,
     models::{
-        comment_model::{Comment, CommentData, CreateCommentSchema, UpdateCommentSchema},
+        comment_model::{
+            Comment, CommentData, CommentListQuery, CommentModeration, CommentPage,
+            CommentRevision, CommentRevisions, CreateCommentSchema, HideCommentSchema,
+            UpdateCommentSchema,
+        },
+        history_model::{HistoryAction, TaskHistoryEntry},
+        notification_model::{Notification, NotificationKind},
+        project_models::Project,
+        reaction_model::Reactions,
         task_model::Task,
     },
-    services::permission_service::PermissionService,
+    services::{
+        history_service::HistoryService,
+        notification_service::{NotificationService, task_label},
+        permission_service::PermissionService,
+        project_service::ProjectService,
+    },
+    utils::{cursor, markdown::render_markdown},
 };
 
 pub struct CommentService {
     db: Arc<DatabaseState>,
+    ws_tx: broadcast::Sender<String>,
 }
 
 impl CommentService {
-    pub fn new(db_state: Arc<DatabaseState>) -> Self {
-        Self { db: db_state }
+    pub fn new(db_state: Arc<DatabaseState>, ws_tx: broadcast::Sender<String>) -> Self {
+        Self {
+            db: db_state,
+            ws_tx,
+        }
     }
 
     fn comments_collection(&self) -> Collection<Comment> {
         self.db.db.collection("comments")
     }
 
-    // //* Revisar permisos para acceder a la tarea
+    // //* Revisar permisos para acceder a la tarea; devuelve la tarea encontrada
     async fn check_permissions(
         &self,
         task_id: ObjectId,
         user_id: ObjectId,
-    ) -> Result<(), AppError> {
+    ) -> Result<Task, AppError> {
+        Ok(self.task_and_project(task_id, user_id).await?.0)
+    }
+
+    // //* Tarea y proyecto al que pertenece, si el usuario tiene acceso
+    async fn task_and_project(
+        &self,
+        task_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<(Task, Project), AppError> {
         let task = self
             .db
             .db
@@ -45,11 +73,120 @@ impl CommentService {
             .map_err(|_| AppError::DatabaseError("Failed to fetch task".to_string()))?
             .ok_or(AppError::NotFound("Task not found".to_string()))?;
 
-        PermissionService::new(&self.db.db)
+        let project = PermissionService::new(&self.db.db)
             .can_access_project(task.project_id, user_id)
             .await?;
 
-        Ok(())
+        Ok((task, project))
+    }
+
+    // //* Error 412 con el comentario tal como está ahora, en el mismo formato de la API
+    async fn comment_precondition_failed(&self, comment: &Comment, user_id: ObjectId) -> AppError {
+        let (Some(comment_id), task_id) = (comment.id, comment.task_id) else {
+            return AppError::InternalServerError;
+        };
+        match self
+            .get_comments_for_task(task_id, user_id, Some(comment_id))
+            .await
+        {
+            Ok(current) => match current.into_iter().next() {
+                Some(current) => AppError::precondition_failed(&current),
+                None => AppError::NotFound("Comentario no encontrado".to_string()),
+            },
+            Err(e) => e,
+        }
+    }
+
+    // //* Registrar en el historial de la tarea un cambio sobre un comentario. Solo se guarda
+    // //* su id: el texto se consulta en el comentario, que respeta la moderación.
+    async fn record_comment_event(
+        &self,
+        task_id: ObjectId,
+        actor_id: ObjectId,
+        action: HistoryAction,
+        comment_id: ObjectId,
+    ) {
+        let project_id = match self
+            .db
+            .db
+            .collection::<Task>("tasks")
+            .find_one(doc! {"_id": task_id})
+            .await
+        {
+            Ok(Some(task)) => task.project_id,
+            _ => return,
+        };
+
+        let entry = TaskHistoryEntry::new(task_id, project_id, actor_id, action)
+            .with_related(Some(comment_id));
+        HistoryService::new(self.db.clone())
+            .record(vec![entry])
+            .await;
+    }
+
+    // //* Miembros mencionados en el comentario y su HTML saneado, con las menciones enlazadas
+    async fn render_content(
+        &self,
+        task: &Task,
+        author_id: ObjectId,
+        content: &str,
+    ) -> Result<(Vec<ObjectId>, String), AppError> {
+        let members = ProjectService::new(self.db.clone(), self.ws_tx.clone())
+            .mentioned_members(task.project_id, author_id, content)
+            .await?;
+
+        let mentions = members
+            .iter()
+            .filter_map(|member| ObjectId::parse_str(&member.id).ok())
+            .collect();
+        Ok((mentions, render_markdown(content, &members)))
+    }
+
+    // //* Los recién mencionados pasan a observar la tarea y reciben un aviso
+    async fn handle_new_mentions(
+        &self,
+        task: &Task,
+        comment_id: ObjectId,
+        author_id: ObjectId,
+        mentioned: Vec<ObjectId>,
+    ) {
+        if mentioned.is_empty() {
+            return;
+        }
+
+        if let Err(e) = self
+            .db
+            .db
+            .collection::<Task>("tasks")
+            .update_one(
+                doc! {"_id": task.id},
+                doc! {
+                    "$addToSet": {"watchers": {"$each": &mentioned}},
+                    "$inc": {"version": 1},
+                },
+            )
+            .await
+        {
+            tracing::warn!("Error añadiendo observadores mencionados: {}", e);
+        }
+
+        let notifications = mentioned
+            .into_iter()
+            .map(|user_id| {
+                Notification::new(
+                    user_id,
+                    NotificationKind::Mentioned,
+                    author_id,
+                    task.project_id,
+                    format!("Te han mencionado en la tarea {}", task_label(task)),
+                )
+                .with_task(task.id)
+                .with_comment(Some(comment_id))
+            })
+            .collect();
+        NotificationService::new(self.db.clone(), self.ws_tx.clone())
+            .notify(notifications)
+            .await;
     }
 
     // //* Crea un nuevo comentario
@@ -63,15 +200,54 @@ impl CommentService {
             .validate()
             .map_err(|e| AppError::ValidationError(e.to_string()))?;
 
-        self.check_permissions(task_id, author_id).await?;
+        let task = self.check_permissions(task_id, author_id).await?;
+
+        // Las respuestas cuelgan de un comentario de primer nivel de la misma tarea
+        let parent_id = match &schema.parent_id {
+            Some(parent_id) => {
+                let parent_id = ObjectId::parse_str(parent_id).map_err(|_| {
+                    AppError::ValidationError("ID de comentario padre inválido".to_string())
+                })?;
+                let parent = self
+                    .comments_collection()
+                    .find_one(doc! {"_id": parent_id, "task_id": task_id, "deleted": {"$ne": true}})
+                    .await
+                    .map_err(|e| {
+                        AppError::DatabaseError(format!("Failed to fetch comment: {}", e))
+                    })?
+                    .ok_or(AppError::NotFound(
+                        "Comentario padre no encontrado".to_string(),
+                    ))?;
+                if parent.parent_id.is_some() {
+                    return Err(AppError::ValidationError(
+                        "No se puede responder a una respuesta".to_string(),
+                    ));
+                }
+                Some(parent_id)
+            }
+            None => None,
+        };
+
+        let (mentions, content_html) = self
+            .render_content(&task, author_id, &schema.content)
+            .await?;
 
         let new_comment = Comment {
             id: None,
             task_id,
             user_id: author_id,
+            parent_id,
             content: schema.content,
+            content_html,
+            mentions,
+            reactions: Reactions::new(),
+            deleted: false,
+            revisions: Vec::new(),
+            moderation: None,
+            version: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            changed_at: Utc::now(),
         };
 
         let result = self
@@ -80,6 +256,21 @@ impl CommentService {
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to insert comment: {}", e)))?;
         let comment_id = result.inserted_id.as_object_id().unwrap();
+
+        let entry = TaskHistoryEntry::new(
+            task_id,
+            task.project_id,
+            author_id,
+            HistoryAction::CommentAdded,
+        )
+        .with_related(Some(comment_id));
+        HistoryService::new(self.db.clone())
+            .record(vec![entry])
+            .await;
+
+        self.handle_new_mentions(&task, comment_id, author_id, new_comment.mentions.clone())
+            .await;
+
         let comments = self
             .get_comments_for_task(task_id, author_id, Some(comment_id))
             .await?;
@@ -89,24 +280,101 @@ impl CommentService {
             .ok_or(AppError::InternalServerError)
     }
 
-    // //* Actualiza un comentario existente
+    // //* Comentarios de la tarea agrupados en hilos: los de primer nivel con sus respuestas.
+    // //* Con `single_comment_id` se devuelve solo ese comentario (con sus respuestas si tiene).
     pub async fn get_comments_for_task(
         &self,
         task_id: ObjectId,
         user_id: ObjectId,
         single_comment_id: Option<ObjectId>,
     ) -> Result<Vec<CommentData>, AppError> {
-        self.check_permissions(task_id, user_id).await?;
+        let (_, project) = self.task_and_project(task_id, user_id).await?;
 
-        // //? Pipeline de agregación para obtener comentarios con datos del usuario
         let mut initial_match = doc! {"task_id": task_id};
         if let Some(comment_id) = single_comment_id {
-            initial_match.insert("_id", comment_id);
+            initial_match.insert(
+                "$or",
+                vec![doc! {"_id": comment_id}, doc! {"parent_id": comment_id}],
+            );
+        }
+        let comments = self.find_comments(initial_match, &project, user_id).await?;
+
+        Ok(Self::into_threads(
+            comments,
+            single_comment_id.map(|id| id.to_hex()),
+        ))
+    }
+
+    // //? Comentarios que cumplen `filter` en orden cronológico, con los datos del autor y
+    // //? los campos calculados para el usuario que consulta
+    async fn find_comments(
+        &self,
+        filter: Document,
+        project: &Project,
+        user_id: ObjectId,
+    ) -> Result<Vec<CommentData>, AppError> {
+        let is_admin = PermissionService::is_project_admin(project, user_id);
+
+        // //? Un comentario oculto solo muestra su contenido y el motivo a los administradores
+        let visible = |field: &str| -> Bson {
+            if is_admin {
+                field.into()
+            } else {
+                doc! {"$cond": [{"$gt": ["$moderation", null]}, "", field]}.into()
+            }
+        };
+        let mut projection = doc! {
+            "_id": 0,
+            "id": {"$toString": "$_id"},
+            "task_id": {"$toString": "$task_id"},
+            "parent_id": {"$toString": "$parent_id"},
+            "content": visible("$content"),
+            "content_html": visible("$content_html"),
+            "deleted": {"$ifNull": ["$deleted", false]},
+            "edited": {"$gt": [{"$size": {"$ifNull": ["$revisions", []]}}, 0]},
+            "hidden": {"$gt": ["$moderation", null]},
+            // //? Recuento por emoji y si el usuario que consulta está entre quienes reaccionaron
+            "reactions": {
+                "$filter": {
+                    "input": {
+                        "$map": {
+                            "input": {"$objectToArray": {"$ifNull": ["$reactions", {}]}},
+                            "as": "reaction",
+                            "in": {
+                                "emoji": "$$reaction.k",
+                                "count": {"$size": "$$reaction.v"},
+                                "reacted_by_me": {"$in": [user_id, "$$reaction.v"]},
+                            },
+                        }
+                    },
+                    "as": "reaction",
+                    "cond": {"$gt": ["$$reaction.count", 0]},
+                }
+            },
+            "mentions": {
+                "$map": {
+                    "input": {"$ifNull": ["$mentions", []]},
+                    "as": "mention",
+                    "in": {"$toString": "$$mention"},
+                }
+            },
+            "version": 1,
+            "created_at": 1,
+            "updated_at": 1,
+            "author": {
+                "id": {"$toString": "$author._id"},
+                "username": "$author.username",
+                "email": "$author.email",
+            }
+        };
+        if is_admin {
+            projection.insert("moderation_reason", "$moderation.reason");
         }
 
+        // //? Pipeline de agregación para obtener comentarios con datos del usuario
         let pipeline: Vec<Document> = vec![
-            doc! { "$match": initial_match },
-            doc! { "$sort": { "created_at": 1 } },
+            doc! { "$match": filter },
+            doc! { "$sort": { "created_at": 1, "_id": 1 } },
             doc! {
                 "$lookup": {
                     "from": "users",
@@ -116,21 +384,7 @@ impl CommentService {
                 }
             },
             doc! {"$unwind": "$author"},
-            doc! {
-                "$project": {
-                    "_id": 0,
-                    "id": {"$toString": "$_id"},
-                    "task_id": {"$toString": "$task_id"},
-                    "content": 1,
-                    "created_at": 1,
-                    "updated_at": 1,
-                    "author": {
-                        "id": {"$toString": "$author._id"},
-                        "username": "$author.username",
-                        "email": "$author.email",
-                    }
-                }
-            },
+            doc! {"$project": projection},
         ];
 
         let mut cursor = self
@@ -152,6 +406,243 @@ impl CommentService {
         Ok(comments)
     }
 
+    // //* Página de hilos de la tarea, del más antiguo al más reciente. `after` avanza y
+    // //* `before` retrocede desde el cursor; con `since` se devuelven solo los cambios.
+    pub async fn list_comments(
+        &self,
+        task_id: ObjectId,
+        user_id: ObjectId,
+        query: CommentListQuery,
+    ) -> Result<CommentPage, AppError> {
+        query
+            .validate()
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+        let (_, project) = self.task_and_project(task_id, user_id).await?;
+        let synced_at = Utc::now();
+
+        let roots_filter = doc! {"task_id": task_id, "parent_id": null};
+        let total = self
+            .comments_collection()
+            .count_documents(roots_filter.clone())
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to count comments: {}", e)))?;
+
+        let (cursor, forward) = match (&query.since, &query.after, &query.before) {
+            (Some(since), None, None) => {
+                let (items, deleted_ids) = self
+                    .comment_changes(task_id, &project, user_id, *since)
+                    .await?;
+                return Ok(CommentPage {
+                    items,
+                    total,
+                    next_cursor: None,
+                    prev_cursor: None,
+                    deleted_ids,
+                    synced_at,
+                });
+            }
+            (Some(_), _, _) => {
+                return Err(AppError::ValidationError(
+                    "`since` no se puede combinar con un cursor".to_string(),
+                ));
+            }
+            (None, Some(_), Some(_)) => {
+                return Err(AppError::ValidationError(
+                    "Indica solo uno de `after` o `before`".to_string(),
+                ));
+            }
+            (None, Some(after), None) => (Some(cursor::decode(after)?), true),
+            (None, None, Some(before)) => (Some(cursor::decode(before)?), false),
+            (None, None, None) => (None, true),
+        };
+
+        // //? El cursor guarda la fecha y el _id del comentario de primer nivel en el borde
+        let mut filter = roots_filter;
+        if let Some(cursor) = &cursor {
+            let invalid = || AppError::ValidationError("Cursor de paginación inválido".to_string());
+            let created_at = *cursor.get_datetime("created_at").map_err(|_| invalid())?;
+            let id = cursor.get_object_id("id").map_err(|_| invalid())?;
+            let op = if forward { "$gt" } else { "$lt" };
+            filter.insert(
+                "$or",
+                vec![
+                    doc! {"created_at": {op: created_at}},
+                    doc! {"created_at": created_at, "_id": {op: id}},
+                ],
+            );
+        }
+
+        let direction = if forward { 1 } else { -1 };
+        let limit = query.limit.unwrap_or(50) as i64;
+        let mut roots: Vec<Document> = self
+            .db
+            .db
+            .collection::<Document>("comments")
+            .find(filter)
+            .sort(doc! {"created_at": direction, "_id": direction})
+            .limit(limit + 1)
+            .projection(doc! {"created_at": 1})
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let has_more = roots.len() as i64 > limit;
+        roots.truncate(limit as usize);
+        if !forward {
+            roots.reverse();
+        }
+
+        // Quedan comentarios en la dirección recorrida si sobró uno, y en la contraria si se
+        // llegó desde un cursor
+        let (has_previous, has_next) = if forward {
+            (cursor.is_some(), has_more)
+        } else {
+            (has_more, cursor.is_some())
+        };
+        let encode = |root: &Document| -> Result<String, AppError> {
+            Ok(cursor::encode(&doc! {
+                "created_at": root
+                    .get_datetime("created_at")
+                    .map_err(|_| AppError::InternalServerError)?,
+                "id": root.get_object_id("_id").map_err(|_| AppError::InternalServerError)?,
+            }))
+        };
+        let prev_cursor = match roots.first() {
+            Some(first) if has_previous => Some(encode(first)?),
+            _ => None,
+        };
+        let next_cursor = match roots.last() {
+            Some(last) if has_next => Some(encode(last)?),
+            _ => None,
+        };
+
+        let root_ids: Vec<ObjectId> = roots
+            .iter()
+            .filter_map(|root| root.get_object_id("_id").ok())
+            .collect();
+        let comments = self
+            .find_comments(
+                doc! {
+                    "task_id": task_id,
+                    "$or": [{"_id": {"$in": &root_ids}}, {"parent_id": {"$in": &root_ids}}],
+                },
+                &project,
+                user_id,
+            )
+            .await?;
+
+        Ok(CommentPage {
+            items: Self::into_threads(comments, None),
+            total,
+            next_cursor,
+            prev_cursor,
+            deleted_ids: Vec::new(),
+            synced_at,
+        })
+    }
+
+    // //? Cambios desde `since` en una lista plana (cada respuesta lleva su `parent_id`) y los
+    // //? IDs de los comentarios eliminados, que solo quedan registrados en el historial
+    async fn comment_changes(
+        &self,
+        task_id: ObjectId,
+        project: &Project,
+        user_id: ObjectId,
+        since: DateTime<Utc>,
+    ) -> Result<(Vec<CommentData>, Vec<String>), AppError> {
+        let mut items = self
+            .find_comments(
+                doc! {"task_id": task_id, "changed_at": {"$gt": since}},
+                project,
+                user_id,
+            )
+            .await?;
+
+        // Las respuestas no van anidadas, pero el recuento sí debe ser el real
+        let root_ids: Vec<ObjectId> = items
+            .iter()
+            .filter(|comment| comment.parent_id.is_none())
+            .filter_map(|comment| ObjectId::parse_str(&comment.id).ok())
+            .collect();
+        let reply_counts: Vec<Document> = self
+            .comments_collection()
+            .aggregate(vec![
+                doc! {"$match": {"parent_id": {"$in": &root_ids}}},
+                doc! {"$group": {"_id": {"$toString": "$parent_id"}, "count": {"$sum": 1}}},
+            ])
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        for item in items.iter_mut() {
+            item.reactions.sort_by(|a, b| a.emoji.cmp(&b.emoji));
+            item.reply_count = reply_counts
+                .iter()
+                .find(|count| count.get_str("_id") == Ok(item.id.as_str()))
+                .and_then(|count| count.get_i32("count").ok())
+                .unwrap_or(0) as usize;
+        }
+
+        // //? Los borrados con respuestas siguen como lápida y ya vienen entre los cambios
+        let deleted: Vec<Bson> = self
+            .db
+            .db
+            .collection::<TaskHistoryEntry>("task_history")
+            .distinct(
+                "related_id",
+                doc! {
+                    "task_id": task_id,
+                    "action": "comment_deleted",
+                    "created_at": {"$gt": since},
+                },
+            )
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        let remaining: Vec<Bson> = self
+            .comments_collection()
+            .distinct("_id", doc! {"_id": {"$in": &deleted}})
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        let deleted_ids = deleted
+            .iter()
+            .filter(|id| !remaining.contains(id))
+            .filter_map(Bson::as_object_id)
+            .map(|id| id.to_hex())
+            .collect();
+
+        Ok((items, deleted_ids))
+    }
+
+    // //? Colgar cada respuesta de su comentario padre, conservando el orden cronológico
+    fn into_threads(comments: Vec<CommentData>, single_id: Option<String>) -> Vec<CommentData> {
+        let (mut roots, replies): (Vec<CommentData>, Vec<CommentData>) =
+            comments.into_iter().partition(|comment| {
+                comment.parent_id.is_none() || single_id.as_ref() == Some(&comment.id)
+            });
+
+        for reply in replies {
+            if let Some(parent) = roots
+                .iter_mut()
+                .find(|root| reply.parent_id.as_ref() == Some(&root.id))
+            {
+                parent.replies.push(reply);
+            }
+        }
+        for root in roots.iter_mut() {
+            root.reply_count = root.replies.len();
+            // El orden de las claves en MongoDB es el de inserción; se devuelven por emoji
+            root.reactions.sort_by(|a, b| a.emoji.cmp(&b.emoji));
+            for reply in root.replies.iter_mut() {
+                reply.reactions.sort_by(|a, b| a.emoji.cmp(&b.emoji));
+            }
+        }
+
+        roots
+    }
+
     // //* Actualiza un comentario existente
 
     pub async fn update_comment(
@@ -159,6 +650,7 @@ impl CommentService {
         comment_id: ObjectId,
         user_id: ObjectId,
         shcema: UpdateCommentSchema,
+        expected_version: Option<i64>,
     ) -> Result<CommentData, AppError> {
         shcema
             .validate()
@@ -166,7 +658,7 @@ impl CommentService {
 
         let comment = self
             .comments_collection()
-            .find_one(doc! {"_id": comment_id, })
+            .find_one(doc! {"_id": comment_id, "deleted": {"$ne": true}})
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to fetch comment: {}", e)))?
             .ok_or(AppError::NotFound("Comentario no encontrado".to_string()))?;
@@ -176,19 +668,77 @@ impl CommentService {
                 "No tienes permiso para actualizar este comentario".to_string(),
             ));
         }
+        if comment.moderation.is_some() {
+            return Err(AppError::Unauthorized(
+                "El comentario está oculto por moderación y no se puede editar".to_string(),
+            ));
+        }
 
-        let update_doc = doc! {
+        // Si el cliente envió If-Match, su versión debe ser la actual
+        if let Some(expected) = expected_version
+            && expected != comment.version
+        {
+            return Err(self.comment_precondition_failed(&comment, user_id).await);
+        }
+
+        let task = self.check_permissions(comment.task_id, user_id).await?;
+        let (mentions, content_html) = self.render_content(&task, user_id, &shcema.content).await?;
+
+        let now = Utc::now();
+        let new_content = shcema.content.clone();
+        let mut update_doc = doc! {
             "$set": {
                 "content": shcema.content,
-                "updated_at": Utc::now(),
-            }
+                "content_html": content_html,
+                "mentions": &mentions,
+                "updated_at": now,
+                "changed_at": now,
+            },
+            "$inc": {"version": 1},
         };
+        // //? La versión sustituida se guarda en el historial de ediciones
+        if comment.content != new_content {
+            let revision = CommentRevision {
+                content: comment.content.clone(),
+                written_at: comment.updated_at,
+                replaced_at: now,
+            };
+            let revision = to_bson(&revision).map_err(|_| AppError::InternalServerError)?;
+            update_doc.insert("$push", doc! {"revisions": revision});
+        }
 
-        self.comments_collection()
-            .update_one(doc! {"_id": comment_id}, update_doc)
+        let mut filter = doc! {"_id": comment_id};
+        if expected_version.is_some() {
+            filter.insert("version", comment.version);
+        }
+        let result = self
+            .comments_collection()
+            .update_one(filter, update_doc)
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to update comment: {}", e)))?;
 
+        if result.matched_count == 0 {
+            return Err(self.comment_precondition_failed(&comment, user_id).await);
+        }
+
+        if comment.content != new_content {
+            self.record_comment_event(
+                comment.task_id,
+                user_id,
+                HistoryAction::CommentUpdated,
+                comment_id,
+            )
+            .await;
+        }
+
+        // Solo se avisa a quienes no estaban ya mencionados
+        let new_mentions = mentions
+            .into_iter()
+            .filter(|mention| !comment.mentions.contains(mention))
+            .collect();
+        self.handle_new_mentions(&task, comment_id, user_id, new_mentions)
+            .await;
+
         let updated_comment = self
             .get_comments_for_task(comment.task_id, user_id, Some(comment_id))
             .await?;
@@ -206,7 +756,7 @@ impl CommentService {
     ) -> Result<(), AppError> {
         let comment = self
             .comments_collection()
-            .find_one(doc! { "_id": comment_id })
+            .find_one(doc! { "_id": comment_id, "deleted": {"$ne": true} })
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to fetch comment: {}", e)))?
             .ok_or(AppError::NotFound("Comentario no encontrado".to_string()))?;
@@ -217,11 +767,167 @@ impl CommentService {
             ));
         }
 
-        self.comments_collection()
-            .delete_one(doc! { "_id": comment_id })
+        let reply_count = self
+            .comments_collection()
+            .count_documents(doc! {"parent_id": comment_id})
             .await
-            .map_err(|e| AppError::DatabaseError(format!("Failed to delete comment: {}", e)))?;
+            .map_err(|e| AppError::DatabaseError(format!("Failed to count replies: {}", e)))?;
+
+        if reply_count > 0 {
+            // //? Con respuestas queda una lápida vacía para no dejarlas huérfanas
+            self.comments_collection()
+                .update_one(
+                    doc! {"_id": comment_id},
+                    doc! {
+                        "$set": {
+                            "content": "",
+                            "content_html": "",
+                            "mentions": [],
+                            "reactions": {},
+                            "revisions": [],
+                            "deleted": true,
+                            "updated_at": Utc::now(),
+                            "changed_at": Utc::now(),
+                        },
+                        "$inc": {"version": 1},
+                    },
+                )
+                .await
+                .map_err(|e| AppError::DatabaseError(format!("Failed to delete comment: {}", e)))?;
+        } else {
+            self.comments_collection()
+                .delete_one(doc! { "_id": comment_id })
+                .await
+                .map_err(|e| AppError::DatabaseError(format!("Failed to delete comment: {}", e)))?;
+
+            // //? La lápida del padre desaparece cuando se borra su última respuesta
+            if let Some(parent_id) = comment.parent_id {
+                let remaining = self
+                    .comments_collection()
+                    .count_documents(doc! {"parent_id": parent_id})
+                    .await
+                    .map_err(|e| {
+                        AppError::DatabaseError(format!("Failed to count replies: {}", e))
+                    })?;
+                if remaining == 0 {
+                    self.comments_collection()
+                        .delete_one(doc! {"_id": parent_id, "deleted": true})
+                        .await
+                        .map_err(|e| {
+                            AppError::DatabaseError(format!("Failed to delete comment: {}", e))
+                        })?;
+                }
+            }
+        }
+
+        self.record_comment_event(
+            comment.task_id,
+            user_id,
+            HistoryAction::CommentDeleted,
+            comment_id,
+        )
+        .await;
 
         Ok(())
     }
+
+    // //* Versiones anteriores de un comentario. Si está oculto solo las ven los administradores.
+    pub async fn get_comment_revisions(
+        &self,
+        task_id: ObjectId,
+        comment_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<CommentRevisions, AppError> {
+        let (_, project) = self.task_and_project(task_id, user_id).await?;
+
+        let comment = self
+            .comments_collection()
+            .find_one(doc! {"_id": comment_id, "task_id": task_id, "deleted": {"$ne": true}})
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to fetch comment: {}", e)))?
+            .ok_or(AppError::NotFound("Comentario no encontrado".to_string()))?;
+
+        if comment.moderation.is_some() && !PermissionService::is_project_admin(&project, user_id) {
+            return Err(AppError::Unauthorized(
+                "El comentario está oculto por moderación".to_string(),
+            ));
+        }
+
+        Ok(CommentRevisions {
+            comment_id: comment_id.to_hex(),
+            content: comment.content,
+            updated_at: comment.updated_at,
+            revisions: comment.revisions,
+        })
+    }
+
+    // //* Ocultar un comentario (o volver a mostrarlo con `schema` a None). Solo administradores.
+    pub async fn moderate_comment(
+        &self,
+        task_id: ObjectId,
+        comment_id: ObjectId,
+        moderator_id: ObjectId,
+        schema: Option<HideCommentSchema>,
+    ) -> Result<CommentData, AppError> {
+        if let Some(schema) = &schema {
+            schema
+                .validate()
+                .map_err(|e| AppError::ValidationError(e.to_string()))?;
+        }
+
+        let (_, project) = self.task_and_project(task_id, moderator_id).await?;
+        if !PermissionService::is_project_admin(&project, moderator_id) {
+            return Err(AppError::Unauthorized(
+                "Solo los administradores del proyecto pueden moderar comentarios".to_string(),
+            ));
+        }
+
+        let (update, action) = match schema {
+            Some(schema) => {
+                let moderation = CommentModeration {
+                    moderator_id,
+                    reason: schema.reason,
+                    hidden_at: Utc::now(),
+                };
+                let moderation = to_bson(&moderation).map_err(|_| AppError::InternalServerError)?;
+                (
+                    doc! {
+                        "$set": {"moderation": moderation, "changed_at": Utc::now()},
+                        "$inc": {"version": 1},
+                    },
+                    HistoryAction::CommentHidden,
+                )
+            }
+            None => (
+                doc! {
+                    "$unset": {"moderation": ""},
+                    "$set": {"changed_at": Utc::now()},
+                    "$inc": {"version": 1},
+                },
+                HistoryAction::CommentRestored,
+            ),
+        };
+
+        let result = self
+            .comments_collection()
+            .update_one(
+                doc! {"_id": comment_id, "task_id": task_id, "deleted": {"$ne": true}},
+                update,
+            )
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to update comment: {}", e)))?;
+        if result.matched_count == 0 {
+            return Err(AppError::NotFound("Comentario no encontrado".to_string()));
+        }
+
+        // //? El motivo no va al historial: la actividad de la tarea la ve cualquier miembro
+        self.record_comment_event(task_id, moderator_id, action, comment_id)
+            .await;
+
+        self.get_comments_for_task(task_id, moderator_id, Some(comment_id))
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(AppError::InternalServerError)
+    }
 }
@@ -0,0 +1,272 @@
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::{
+    Collection,
+    bson::{Bson, Document, doc, oid::ObjectId},
+};
+use serde_json::Value;
+use std::{collections::HashMap, sync::Arc};
+use validator::Validate;
+
+use crate::{
+    db::DatabaseState,
+    errors::AppError,
+    models::{
+        custom_field_model::{
+            CreateCustomFieldSchema, CustomFieldDefinition, UpdateCustomFieldSchema,
+        },
+        project_models::Project,
+        task_model::Task,
+    },
+    services::permission_service::PermissionService,
+};
+
+// Valores de campos personalizados ya validados, listos para `$set` y `$unset`
+#[derive(Debug, Default)]
+pub struct CustomFieldChanges {
+    pub set: Document,
+    pub unset: Vec<String>,
+}
+
+pub struct CustomFieldService {
+    db_state: Arc<DatabaseState>,
+}
+
+impl CustomFieldService {
+    pub fn new(db_state: Arc<DatabaseState>) -> Self {
+        Self { db_state }
+    }
+
+    fn fields_collection(&self) -> Collection<CustomFieldDefinition> {
+        self.db_state
+            .get_db()
+            .collection::<CustomFieldDefinition>("custom_fields")
+    }
+
+    // //* Definiciones de un proyecto, sin comprobar permisos (uso interno)
+    pub async fn definitions_for_project(
+        &self,
+        project_id: ObjectId,
+    ) -> Result<Vec<CustomFieldDefinition>, AppError> {
+        self.fields_collection()
+            .find(doc! {"project_id": project_id})
+            .sort(doc! {"created_at": 1})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    async fn find_field(
+        &self,
+        project_id: ObjectId,
+        key: &str,
+    ) -> Result<CustomFieldDefinition, AppError> {
+        self.fields_collection()
+            .find_one(doc! {"project_id": project_id, "key": key})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .ok_or_else(|| AppError::NotFound("Campo personalizado no encontrado".to_string()))
+    }
+
+    pub async fn list_fields(
+        &self,
+        project_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<Vec<CustomFieldDefinition>, AppError> {
+        PermissionService::new(self.db_state.get_db())
+            .can_access_project(project_id, user_id)
+            .await?;
+
+        self.definitions_for_project(project_id).await
+    }
+
+    // //* Solo el dueño del proyecto define sus campos
+    pub async fn create_field(
+        &self,
+        project_id: ObjectId,
+        user_id: ObjectId,
+        schema: CreateCustomFieldSchema,
+    ) -> Result<CustomFieldDefinition, AppError> {
+        schema
+            .validate()
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+        PermissionService::new(self.db_state.get_db())
+            .is_project_owner(project_id, user_id)
+            .await?;
+
+        let now = Utc::now();
+        let mut field = CustomFieldDefinition {
+            id: None,
+            project_id,
+            key: schema.key,
+            name: schema.name,
+            field_type: schema.field_type,
+            required: schema.required.unwrap_or(false),
+            options: schema.options.unwrap_or_default(),
+            min: schema.min,
+            max: schema.max,
+            max_length: schema.max_length,
+            created_at: now,
+            updated_at: now,
+        };
+        field.check_settings().map_err(AppError::ValidationError)?;
+
+        let existing = self
+            .fields_collection()
+            .find_one(doc! {"project_id": project_id, "key": &field.key})
+            .await
+            .map_err(|_| AppError::InternalServerError)?;
+        if existing.is_some() {
+            return Err(AppError::Conflict(format!(
+                "Ya existe un campo con la clave '{}'",
+                field.key
+            )));
+        }
+
+        let result = self
+            .fields_collection()
+            .insert_one(&field)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        field.id = result.inserted_id.as_object_id();
+
+        Ok(field)
+    }
+
+    pub async fn update_field(
+        &self,
+        project_id: ObjectId,
+        key: &str,
+        user_id: ObjectId,
+        schema: UpdateCustomFieldSchema,
+    ) -> Result<CustomFieldDefinition, AppError> {
+        schema
+            .validate()
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+        PermissionService::new(self.db_state.get_db())
+            .is_project_owner(project_id, user_id)
+            .await?;
+
+        let mut field = self.find_field(project_id, key).await?;
+        if let Some(name) = schema.name {
+            field.name = name;
+        }
+        if let Some(required) = schema.required {
+            field.required = required;
+        }
+        if let Some(options) = schema.options {
+            field.options = options;
+        }
+        if let Some(min) = schema.min {
+            field.min = min;
+        }
+        if let Some(max) = schema.max {
+            field.max = max;
+        }
+        if let Some(max_length) = schema.max_length {
+            field.max_length = max_length;
+        }
+        field.updated_at = Utc::now();
+        field.check_settings().map_err(AppError::ValidationError)?;
+
+        self.fields_collection()
+            .replace_one(doc! {"project_id": project_id, "key": key}, &field)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(field)
+    }
+
+    // //* Eliminar la definición y los valores guardados en las tareas del proyecto
+    pub async fn delete_field(
+        &self,
+        project_id: ObjectId,
+        key: &str,
+        user_id: ObjectId,
+    ) -> Result<(), AppError> {
+        PermissionService::new(self.db_state.get_db())
+            .is_project_owner(project_id, user_id)
+            .await?;
+
+        let field = self.find_field(project_id, key).await?;
+
+        self.db_state
+            .get_db()
+            .collection::<Task>("tasks")
+            .update_many(
+                doc! {"project_id": project_id},
+                doc! {"$unset": {format!("custom_fields.{}", field.key): ""}},
+            )
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        self.fields_collection()
+            .delete_one(doc! {"project_id": project_id, "key": key})
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    // //* Validar los valores enviados para una tarea. Al crear se exigen todos los
+    // //* campos obligatorios; al editar solo se impide vaciarlos, para no bloquear
+    // //* tareas creadas antes de que el campo fuera obligatorio.
+    pub async fn validate_values(
+        &self,
+        project: &Project,
+        values: &HashMap<String, Value>,
+        creating: bool,
+    ) -> Result<CustomFieldChanges, AppError> {
+        let project_id = project.id.ok_or(AppError::InternalServerError)?;
+        let definitions = self.definitions_for_project(project_id).await?;
+
+        if let Some(unknown) = values
+            .keys()
+            .find(|key| !definitions.iter().any(|field| &field.key == *key))
+        {
+            return Err(AppError::ValidationError(format!(
+                "El proyecto no tiene un campo personalizado '{}'",
+                unknown
+            )));
+        }
+
+        let mut changes = CustomFieldChanges::default();
+        for field in &definitions {
+            let value = match values.get(&field.key) {
+                Some(value) => field
+                    .coerce_value(value)
+                    .map_err(AppError::ValidationError)?,
+                None if creating => None,
+                None => continue,
+            };
+
+            match value {
+                Some(value) => {
+                    if let Bson::ObjectId(member_id) = &value
+                        && project.owner_id != *member_id
+                        && !project.members.contains(member_id)
+                    {
+                        return Err(AppError::ValidationError(format!(
+                            "El usuario del campo '{}' no pertenece al proyecto",
+                            field.key
+                        )));
+                    }
+                    changes.set.insert(field.key.clone(), value);
+                }
+                None if field.required => {
+                    return Err(AppError::ValidationError(format!(
+                        "El campo '{}' es obligatorio",
+                        field.name
+                    )));
+                }
+                None => changes.unset.push(field.key.clone()),
+            }
+        }
+
+        Ok(changes)
+    }
+}
@@ -1,20 +1,66 @@
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
 use mongodb::{
-    Collection,
-    bson::{doc, oid::ObjectId},
+    ClientSession, Collection,
+    bson::{Bson, Document, doc, oid::ObjectId, to_bson},
+    error::TRANSIENT_TRANSACTION_ERROR,
+    options::{FindOneAndUpdateOptions, ReturnDocument},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
 };
-use std::sync::Arc;
 use validator::Validate;
 
-use crate::models::task_model::Task;
+use crate::models::{
+    dependency_model::{ReschedulePreview, ScheduleShift, TaskDependency},
+    history_model::{HistoryAction, TaskHistoryEntry},
+    project_models::Project,
+    task_model::{
+        CreateDateRangeSchema, DateRangeProblem, InvalidDateRange, OverdueTask, Task, TaskStatus,
+        UpdateDateRangeSchema,
+    },
+};
 use crate::{
-    db::DatabaseState, errors::AppError, models::task_model::DateRange,
-    services::permission_service::PermissionService,
+    db::DatabaseState,
+    errors::AppError,
+    models::task_model::DateRange,
+    services::{
+        calendar_service::CalendarService,
+        history_service::{HistoryService, history_value},
+        permission_service::PermissionService,
+    },
+    utils::{
+        calendar::{WorkCalendar, as_working_days},
+        critical_path::{PlannedTask, propagate_dates},
+    },
 };
 
 pub struct DateRangeService {
     db_state: Arc<DatabaseState>,
 }
 
+// Cambio de fechas de una tarea dependiente al replanificar
+struct RangeShift {
+    task: Task,
+    before: DateRange,
+    after: DateRange,
+}
+
+impl RangeShift {
+    fn summary(&self) -> ScheduleShift {
+        ScheduleShift {
+            task_id: self.before.task_id.to_hex(),
+            key: self.task.key.clone(),
+            title: self.task.title.clone(),
+            old_start_date: self.before.start_date.unwrap_or_default(),
+            old_end_date: self.before.end_date.unwrap_or_default(),
+            new_start_date: self.after.start_date.unwrap_or_default(),
+            new_end_date: self.after.end_date.unwrap_or_default(),
+        }
+    }
+}
+
 impl DateRangeService {
     pub fn new(db_state: Arc<DatabaseState>) -> Self {
         Self { db_state }
@@ -26,14 +72,108 @@ impl DateRangeService {
             .collection::<DateRange>("task_date_ranges")
     }
 
+    /// Fechas del rango tal como se guardan, con el mismo formato que al insertarlo
+    fn dates_document(date_range: &DateRange) -> Result<Document, AppError> {
+        Ok(doc! {
+            "start_date": to_bson(&date_range.start_date)
+                .map_err(|_| AppError::InternalServerError)?,
+            "end_date": to_bson(&date_range.end_date).map_err(|_| AppError::InternalServerError)?,
+        })
+    }
+
+    /// Registrar en el historial los cambios de inicio y fin de una tarea
+    async fn record_range_change(
+        &self,
+        task: &Task,
+        user_id: ObjectId,
+        before: Option<&DateRange>,
+        after: Option<&DateRange>,
+    ) {
+        let Some(task_id) = task.id else {
+            return;
+        };
+        let start = |range: Option<&DateRange>| history_value(&range.and_then(|r| r.start_date));
+        let end = |range: Option<&DateRange>| history_value(&range.and_then(|r| r.end_date));
+
+        let entries = [
+            ("start_date", start(before), start(after)),
+            ("end_date", end(before), end(after)),
+        ]
+        .into_iter()
+        .filter(|(_, old_value, new_value)| old_value != new_value)
+        .map(|(field, old_value, new_value)| {
+            TaskHistoryEntry::new(
+                task_id,
+                task.project_id,
+                user_id,
+                HistoryAction::DateRangeChanged,
+            )
+            .with_change(field, old_value, new_value)
+        })
+        .collect();
+
+        HistoryService::new(self.db_state.clone())
+            .record(entries)
+            .await;
+    }
+
+    /// Calendario laboral del proyecto de la tarea
+    async fn work_calendar(&self, project_id: ObjectId) -> Result<WorkCalendar, AppError> {
+        CalendarService::new(self.db_state.clone())
+            .work_calendar(project_id)
+            .await
+    }
+
+    /// Fecha de fin del rango: la indicada o, si se da una duración en días laborables, la que
+    /// resulta de sumarla al inicio según el calendario del proyecto
+    fn due_date(
+        start_date: Option<DateTime<Utc>>,
+        end_date: Option<DateTime<Utc>>,
+        end_given: bool,
+        working_days: Option<u32>,
+        calendar: &WorkCalendar,
+    ) -> Result<Option<DateTime<Utc>>, AppError> {
+        let Some(days) = working_days else {
+            return Ok(end_date);
+        };
+        if end_given {
+            return Err(AppError::ValidationError(
+                "Indica la fecha de fin o la duración en días laborables, no ambas".to_string(),
+            ));
+        }
+        let start = start_date.ok_or_else(|| {
+            AppError::ValidationError(
+                "Para calcular la fecha de fin hace falta la de inicio".to_string(),
+            )
+        })?;
+        Ok(Some(calendar.add_working_days(start, days)))
+    }
+
+    /// Momento en el que vence una tarea: su fin o, si cae en un día no laborable, el
+    /// comienzo del siguiente día laborable
+    pub fn due_at(calendar: &WorkCalendar, end_date: DateTime<Utc>) -> DateTime<Utc> {
+        calendar.next_working_time(end_date)
+    }
+
+    /// Si una tarea sin terminar ni cancelar ha pasado su vencimiento
+    pub fn is_overdue(
+        calendar: &WorkCalendar,
+        status: &TaskStatus,
+        end_date: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> bool {
+        !matches!(status, TaskStatus::Done | TaskStatus::Cancelled)
+            && end_date.is_some_and(|end_date| Self::due_at(calendar, end_date) < now)
+    }
+
     /// Crear o actualizar el rango de fechas para una tarea
     pub async fn set_task_date_range(
         &self,
         task_id: ObjectId,
-        date_range: DateRange,
+        schema: CreateDateRangeSchema,
         user_id: ObjectId,
     ) -> Result<DateRange, AppError> {
-        date_range
+        schema
             .validate()
             .map_err(|e| AppError::ValidationError(e.to_string()))?;
 
@@ -51,33 +191,41 @@ impl DateRangeService {
             .can_access_project(task.project_id, user_id)
             .await?;
 
-        // Verificar si ya existe un rango de fechas para esta tarea
+        let calendar = self.work_calendar(task.project_id).await?;
+        let date_range = DateRange {
+            task_id,
+            start_date: schema.start_date,
+            end_date: Self::due_date(
+                schema.start_date,
+                schema.end_date,
+                schema.end_date.is_some(),
+                schema.working_days,
+                &calendar,
+            )?,
+        };
+        date_range
+            .validate()
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+        // Crear o sustituir el rango en una sola operación; junto con el índice único sobre
+        // `task_id`, dos peticiones simultáneas no pueden dejar dos rangos para la misma tarea
         let existing_range = self
             .date_range_collection()
-            .find_one(doc! {"task_id": task_id})
+            .find_one_and_update(
+                doc! {"task_id": task_id},
+                doc! {"$set": Self::dates_document(&date_range)?},
+            )
+            .with_options(
+                FindOneAndUpdateOptions::builder()
+                    .upsert(true)
+                    .return_document(ReturnDocument::Before)
+                    .build(),
+            )
             .await
-            .map_err(|_| AppError::InternalServerError)?;
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
 
-        if existing_range.is_some() {
-            // Actualizar el rango existente
-            let update_doc = doc! {
-                "$set": {
-                    "start_date": date_range.start_date,
-                    "end_date": date_range.end_date,
-                }
-            };
-
-            self.date_range_collection()
-                .update_one(doc! {"task_id": task_id}, update_doc)
-                .await
-                .map_err(|_| AppError::InternalServerError)?;
-        } else {
-            // Crear nuevo rango
-            self.date_range_collection()
-                .insert_one(&date_range)
-                .await
-                .map_err(|_| AppError::InternalServerError)?;
-        }
+        self.record_range_change(&task, user_id, existing_range.as_ref(), Some(&date_range))
+            .await;
 
         Ok(date_range)
     }
@@ -181,79 +329,504 @@ impl DateRangeService {
             .await?;
 
         // Eliminar el rango de fechas
-        self.date_range_collection()
-            .delete_one(doc! {"task_id": task_id})
+        let existing_range = self
+            .date_range_collection()
+            .find_one_and_delete(doc! {"task_id": task_id})
             .await
             .map_err(|_| AppError::InternalServerError)?;
 
+        self.record_range_change(&task, user_id, existing_range.as_ref(), None)
+            .await;
+
         Ok(())
     }
 
-    /// Actualizar parcialmente el rango de fechas para una tarea
-    pub async fn update_task_date_range(
+    /// Rango que resulta de aplicar un cambio parcial: el lado que no cambia se conserva, y el
+    /// rango resultante debe seguir siendo válido
+    fn merge_update(
+        existing: &DateRange,
+        update_data: &UpdateDateRangeSchema,
+        calendar: &WorkCalendar,
+    ) -> Result<DateRange, AppError> {
+        update_data
+            .validate()
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+        if update_data.start_date.is_none()
+            && update_data.end_date.is_none()
+            && update_data.working_days.is_none()
+        {
+            return Err(AppError::ValidationError(
+                "No se proporcionaron campos para actualizar".to_string(),
+            ));
+        }
+
+        let start_date = update_data.start_date.unwrap_or(existing.start_date);
+        let updated_range = DateRange {
+            task_id: existing.task_id,
+            start_date,
+            end_date: Self::due_date(
+                start_date,
+                update_data.end_date.unwrap_or(existing.end_date),
+                update_data.end_date.is_some(),
+                update_data.working_days,
+                calendar,
+            )?,
+        };
+        updated_range
+            .validate()
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+        Ok(updated_range)
+    }
+
+    /// Sustituir las fechas de un rango solo si siguen siendo las de `from`
+    async fn replace_range(
+        &self,
+        from: &DateRange,
+        to: &DateRange,
+        session: Option<&mut ClientSession>,
+    ) -> Result<bool, AppError> {
+        let mut filter = doc! {"task_id": from.task_id};
+        filter.extend(Self::dates_document(from)?);
+        let collection = self.date_range_collection();
+        let update = collection.update_one(filter, doc! {"$set": Self::dates_document(to)?});
+        let result = match session {
+            Some(session) => update.session(session).await,
+            None => update.await,
+        };
+        match result {
+            Ok(result) => Ok(result.matched_count > 0),
+            // //? Otra transacción ha escrito el mismo rango: es un cambio concurrente más
+            Err(e) if e.contains_label(TRANSIENT_TRANSACTION_ERROR) => Ok(false),
+            Err(e) => Err(AppError::DatabaseError(e.to_string())),
+        }
+    }
+
+    // Conflicto al aplicar el cambio `index` de una lista cuyo primer cambio es el pedido
+    fn range_conflict(index: usize) -> AppError {
+        AppError::Conflict(if index == 0 {
+            "El rango de fechas ha cambiado mientras se actualizaba; vuelve a intentarlo"
+                .to_string()
+        } else {
+            "Una tarea dependiente ha cambiado de fechas; vuelve a intentarlo".to_string()
+        })
+    }
+
+    /// Aplicar varios cambios de rango (fechas leídas, fechas nuevas) a la vez: o se aplican
+    /// todos o ninguno. Cada rango se cambia solo si conserva las fechas leídas.
+    async fn apply_range_changes(
+        &self,
+        changes: &[(&DateRange, &DateRange)],
+    ) -> Result<(), AppError> {
+        if self.db_state.supports_transactions {
+            self.apply_in_transaction(changes).await
+        } else {
+            self.apply_with_rollback(changes).await
+        }
+    }
+
+    async fn apply_in_transaction(
+        &self,
+        changes: &[(&DateRange, &DateRange)],
+    ) -> Result<(), AppError> {
+        let mut session = self
+            .db_state
+            .client
+            .start_session()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        session
+            .start_transaction()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        for (index, (from, to)) in changes.iter().enumerate() {
+            let error = match self.replace_range(from, to, Some(&mut session)).await {
+                Ok(true) => continue,
+                Ok(false) => Self::range_conflict(index),
+                Err(e) => e,
+            };
+            if let Err(e) = session.abort_transaction().await {
+                tracing::warn!("Error abortando la transacción de fechas: {}", e);
+            }
+            return Err(error);
+        }
+
+        session.commit_transaction().await.map_err(|e| {
+            if e.contains_label(TRANSIENT_TRANSACTION_ERROR) {
+                Self::range_conflict(changes.len())
+            } else {
+                AppError::DatabaseError(e.to_string())
+            }
+        })
+    }
+
+    // //! Sin transacciones se deshace a mano lo aplicado ante cualquier error. Si no se puede
+    // //! deshacer, las fechas quedan a medias y se responde con un error interno
+    async fn apply_with_rollback(
+        &self,
+        changes: &[(&DateRange, &DateRange)],
+    ) -> Result<(), AppError> {
+        for (applied, (from, to)) in changes.iter().enumerate() {
+            let error = match self.replace_range(from, to, None).await {
+                Ok(true) => continue,
+                Ok(false) => Self::range_conflict(applied),
+                Err(e) => e,
+            };
+
+            let mut restored = true;
+            for (from, to) in changes[..applied].iter().rev() {
+                match self.replace_range(to, from, None).await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        tracing::error!(
+                            "No se pudo deshacer el cambio de fechas de {}: ha vuelto a cambiar",
+                            from.task_id
+                        );
+                        restored = false;
+                    }
+                    Err(e) => {
+                        tracing::error!(
+                            "Error deshaciendo el cambio de fechas de {}: {}",
+                            from.task_id,
+                            e
+                        );
+                        restored = false;
+                    }
+                }
+            }
+            return Err(if restored {
+                error
+            } else {
+                AppError::InternalServerError
+            });
+        }
+        Ok(())
+    }
+
+    /// Tarea a la que el usuario tiene acceso, junto con su proyecto y su rango de fechas
+    async fn find_task_range(
         &self,
         task_id: ObjectId,
-        update_data: crate::models::task_model::UpdateDateRangeSchema,
         user_id: ObjectId,
-    ) -> Result<DateRange, AppError> {
-        // Verificar que el usuario tiene permisos para acceder a la tarea
-        let task_collection = self.db_state.get_db().collection::<Task>("tasks");
-
-        let task = task_collection
+    ) -> Result<(Task, Project, DateRange), AppError> {
+        let task = self
+            .db_state
+            .get_db()
+            .collection::<Task>("tasks")
             .find_one(doc! {"_id": task_id})
             .await
             .map_err(|_| AppError::InternalServerError)?
             .ok_or_else(|| AppError::NotFound("Tarea no encontrada".to_string()))?;
 
-        // Verificar permisos del proyecto
-        PermissionService::new(self.db_state.get_db())
+        let project = PermissionService::new(self.db_state.get_db())
             .can_access_project(task.project_id, user_id)
             .await?;
 
-        // Verificar si existe un rango de fechas para esta tarea
         let existing_range = self
             .date_range_collection()
             .find_one(doc! {"task_id": task_id})
             .await
-            .map_err(|_| AppError::InternalServerError)?;
-
-        if existing_range.is_none() {
-            return Err(AppError::NotFound(
-                "Rango de fechas no encontrado para esta tarea".to_string(),
-            ));
-        }
+            .map_err(|_| AppError::InternalServerError)?
+            .ok_or_else(|| {
+                AppError::NotFound("Rango de fechas no encontrado para esta tarea".to_string())
+            })?;
 
-        // Construir el documento de actualización
-        let mut update_doc = doc! {};
+        Ok((task, project, existing_range))
+    }
 
-        if let Some(start_date) = update_data.start_date {
-            update_doc.insert("start_date", start_date);
+    /// Nuevas fechas de las tareas que dependen de `task` si pasa a tener `range`
+    async fn schedule_shifts(
+        &self,
+        task: &Task,
+        range: &DateRange,
+        calendar: &WorkCalendar,
+    ) -> Result<Vec<RangeShift>, AppError> {
+        let db = self.db_state.get_db();
+        let links: Vec<(ObjectId, ObjectId)> = db
+            .collection::<TaskDependency>("task_dependencies")
+            .find(doc! {"project_id": task.project_id})
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .try_collect::<Vec<TaskDependency>>()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .into_iter()
+            .map(|link| (link.predecessor_id, link.successor_id))
+            .collect();
+        if links.is_empty() {
+            return Ok(Vec::new());
         }
 
-        if let Some(end_date) = update_data.end_date {
-            update_doc.insert("end_date", end_date);
+        // //? Solo se propaga entre tareas con inicio y fin; las demás cortan la cadena
+        let linked_ids: HashSet<ObjectId> = links.iter().flat_map(|(a, b)| [*a, *b]).collect();
+        let mut ranges: HashMap<ObjectId, DateRange> = self
+            .date_range_collection()
+            .find(doc! {"task_id": {"$in": linked_ids.into_iter().collect::<Vec<_>>()}})
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .try_collect::<Vec<DateRange>>()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .into_iter()
+            .map(|range| (range.task_id, range))
+            .collect();
+        ranges.insert(range.task_id, range.clone());
+
+        let mut planned: Vec<PlannedTask> = ranges
+            .values()
+            .filter_map(|range| {
+                Some(PlannedTask {
+                    id: range.task_id,
+                    start: range.start_date?,
+                    end: range.end_date?,
+                })
+            })
+            .collect();
+        planned.sort_by_key(|task| (task.start, task.id));
+        let shifted = propagate_dates(&planned, &links, range.task_id, calendar);
+        if shifted.is_empty() {
+            return Ok(Vec::new());
         }
 
-        if update_doc.is_empty() {
-            return Err(AppError::ValidationError(
-                "No se proporcionaron campos para actualizar".to_string(),
-            ));
+        let shifted_ids: Vec<ObjectId> = shifted.iter().map(|task| task.id).collect();
+        let mut tasks: HashMap<ObjectId, Task> = db
+            .collection::<Task>("tasks")
+            .find(doc! {"_id": {"$in": shifted_ids}})
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .try_collect::<Vec<Task>>()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .into_iter()
+            .filter_map(|task| task.id.map(|id| (id, task)))
+            .collect();
+
+        Ok(shifted
+            .into_iter()
+            .filter_map(|moved| {
+                Some(RangeShift {
+                    task: tasks.remove(&moved.id)?,
+                    before: ranges.remove(&moved.id)?,
+                    after: DateRange {
+                        task_id: moved.id,
+                        start_date: Some(moved.start),
+                        end_date: Some(moved.end),
+                    },
+                })
+            })
+            .collect())
+    }
+
+    /// Vista previa de un cambio parcial de fechas, sin guardar nada: el rango resultante y
+    /// cómo se moverían las tareas dependientes
+    pub async fn preview_task_date_range(
+        &self,
+        task_id: ObjectId,
+        update_data: UpdateDateRangeSchema,
+        user_id: ObjectId,
+    ) -> Result<ReschedulePreview, AppError> {
+        let (task, project, existing) = self.find_task_range(task_id, user_id).await?;
+        let calendar = self.work_calendar(task.project_id).await?;
+        let updated_range = Self::merge_update(&existing, &update_data, &calendar)?;
+        let shifts = self
+            .schedule_shifts(&task, &updated_range, &calendar)
+            .await?;
+
+        Ok(ReschedulePreview {
+            task_id: task_id.to_hex(),
+            start_date: updated_range.start_date,
+            end_date: updated_range.end_date,
+            auto_schedule: project.auto_schedule,
+            shifts: shifts.iter().map(RangeShift::summary).collect(),
+        })
+    }
+
+    /// Actualizar parcialmente el rango de fechas para una tarea. Si el proyecto tiene la
+    /// planificación automática activada, también se retrasan las tareas dependientes: se
+    /// aplican todos los cambios o ninguno.
+    pub async fn update_task_date_range(
+        &self,
+        task_id: ObjectId,
+        update_data: UpdateDateRangeSchema,
+        user_id: ObjectId,
+    ) -> Result<DateRange, AppError> {
+        let (task, project, existing) = self.find_task_range(task_id, user_id).await?;
+        let calendar = self.work_calendar(task.project_id).await?;
+        let updated_range = Self::merge_update(&existing, &update_data, &calendar)?;
+        let shifts = if project.auto_schedule {
+            self.schedule_shifts(&task, &updated_range, &calendar)
+                .await?
+        } else {
+            Vec::new()
+        };
+
+        // Solo se aplica si nadie ha cambiado los rangos desde que se leyeron; si alguno ha
+        // cambiado entretanto no se aplica nada y se pide repetir la operación
+        let mut changes = vec![(&existing, &updated_range)];
+        changes.extend(shifts.iter().map(|shift| (&shift.before, &shift.after)));
+        self.apply_range_changes(&changes).await?;
+
+        self.record_range_change(&task, user_id, Some(&existing), Some(&updated_range))
+            .await;
+        for shift in &shifts {
+            self.record_range_change(
+                &shift.task,
+                user_id,
+                Some(&shift.before),
+                Some(&shift.after),
+            )
+            .await;
         }
 
-        // Actualizar el documento
-        self.date_range_collection()
-            .update_one(doc! {"task_id": task_id}, doc! {"$set": update_doc})
+        Ok(updated_range)
+    }
+
+    /// Tareas del proyecto que han pasado su vencimiento sin terminarse, de la más retrasada a
+    /// la menos. El retraso se cuenta en días laborables del calendario del proyecto.
+    pub async fn overdue_tasks(
+        &self,
+        project_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<Vec<OverdueTask>, AppError> {
+        PermissionService::new(self.db_state.get_db())
+            .can_access_project(project_id, user_id)
+            .await?;
+
+        let tasks: HashMap<ObjectId, Task> = self
+            .db_state
+            .get_db()
+            .collection::<Task>("tasks")
+            .find(doc! {"project_id": project_id})
             .await
-            .map_err(|_| AppError::InternalServerError)?;
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .try_collect::<Vec<Task>>()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .into_iter()
+            .filter_map(|task| task.id.map(|id| (id, task)))
+            .collect();
+        let task_ids: Vec<ObjectId> = tasks.keys().copied().collect();
 
-        // Obtener y retornar el documento actualizado
-        let updated_range = self
+        let ranges: Vec<DateRange> = self
             .date_range_collection()
-            .find_one(doc! {"task_id": task_id})
+            .find(doc! {"task_id": {"$in": task_ids}, "end_date": {"$ne": null}})
             .await
-            .map_err(|_| AppError::InternalServerError)?
-            .ok_or_else(|| AppError::InternalServerError)?;
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let calendar = self.work_calendar(project_id).await?;
+        let now = Utc::now();
+        let mut overdue: Vec<OverdueTask> = ranges
+            .into_iter()
+            .filter_map(|range| {
+                let task = tasks.get(&range.task_id)?;
+                let end_date = range.end_date?;
+                if !Self::is_overdue(&calendar, &task.status, Some(end_date), now) {
+                    return None;
+                }
+                let due_at = Self::due_at(&calendar, end_date);
+                Some(OverdueTask {
+                    task_id: range.task_id.to_hex(),
+                    key: task.key.clone(),
+                    title: task.title.clone(),
+                    status: task.status.clone(),
+                    assignee_id: task.assignee_id.map(|id| id.to_hex()),
+                    end_date,
+                    due_at,
+                    overdue_working_days: as_working_days(calendar.working_time(due_at, now)),
+                })
+            })
+            .collect();
+        overdue.sort_by_key(|task| task.due_at);
+
+        Ok(overdue)
+    }
 
-        Ok(updated_range)
+    /// Informe de migración: rangos guardados antes de validar las fechas que tienen el fin
+    /// antes del inicio o que están repetidos para la misma tarea. No corrige nada.
+    pub async fn migration_report(&self) -> Result<Vec<InvalidDateRange>, AppError> {
+        let documents: Vec<Document> = self
+            .db_state
+            .get_db()
+            .collection::<Document>("task_date_ranges")
+            .find(doc! {})
+            .sort(doc! {"task_id": 1, "_id": 1})
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        // //? Los rangos antiguos pueden tener las fechas como texto o como fecha de BSON
+        let date = |document: &Document, field: &str| match document.get(field) {
+            Some(Bson::DateTime(date)) => Some(date.to_chrono()),
+            Some(Bson::String(date)) => DateTime::parse_from_rfc3339(date)
+                .ok()
+                .map(|date| date.with_timezone(&Utc)),
+            _ => None,
+        };
+
+        let mut ranges_per_task: HashMap<ObjectId, usize> = HashMap::new();
+        for document in &documents {
+            if let Ok(task_id) = document.get_object_id("task_id") {
+                *ranges_per_task.entry(task_id).or_default() += 1;
+            }
+        }
+
+        let report = documents
+            .iter()
+            .filter_map(|document| {
+                let range_id = document.get_object_id("_id").ok()?;
+                let task_id = document.get_object_id("task_id").ok()?;
+                let start_date = date(document, "start_date");
+                let end_date = date(document, "end_date");
+
+                let mut problems = Vec::new();
+                if let (Some(start), Some(end)) = (start_date, end_date)
+                    && end < start
+                {
+                    problems.push(DateRangeProblem::EndBeforeStart);
+                }
+                if ranges_per_task.get(&task_id).copied().unwrap_or(0) > 1 {
+                    problems.push(DateRangeProblem::Duplicate);
+                }
+
+                (!problems.is_empty()).then_some(InvalidDateRange {
+                    range_id,
+                    task_id,
+                    start_date,
+                    end_date,
+                    problems,
+                })
+            })
+            .collect();
+
+        Ok(report)
+    }
+
+    /// Escribir el informe de migración en el registro al arrancar
+    pub async fn log_migration_report(&self) {
+        match self.migration_report().await {
+            Ok(report) if report.is_empty() => {}
+            Ok(report) => {
+                tracing::warn!(
+                    "Hay {} rangos de fechas que incumplen las reglas y deben corregirse:",
+                    report.len()
+                );
+                for range in report {
+                    tracing::warn!(
+                        "  rango {} de la tarea {}: inicio {:?}, fin {:?}, problemas {:?}",
+                        range.range_id,
+                        range.task_id,
+                        range.start_date,
+                        range.end_date,
+                        range.problems
+                    );
+                }
+            }
+            Err(e) => tracing::error!("Error generando el informe de rangos de fechas: {}", e),
+        }
     }
 }
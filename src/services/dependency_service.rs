@@ -0,0 +1,326 @@
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::{
+    Collection,
+    bson::{doc, oid::ObjectId},
+    error::{ErrorKind, WriteFailure},
+};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Arc,
+};
+use tokio::sync::broadcast;
+
+use crate::{
+    db::DatabaseState,
+    errors::AppError,
+    models::{
+        dependency_model::{
+            CreateDependencySchema, DependencyTask, TaskDependencies, TaskDependency,
+        },
+        history_model::{HistoryAction, TaskHistoryEntry},
+        task_model::Task,
+    },
+    services::{
+        history_service::{HistoryService, history_value},
+        permission_service::PermissionService,
+    },
+};
+
+pub struct DependencyService {
+    db_state: Arc<DatabaseState>,
+    ws_tx: broadcast::Sender<String>,
+}
+
+impl DependencyService {
+    pub fn new(db_state: Arc<DatabaseState>, ws_tx: broadcast::Sender<String>) -> Self {
+        Self { db_state, ws_tx }
+    }
+
+    fn task_collection(&self) -> Collection<Task> {
+        self.db_state.get_db().collection::<Task>("tasks")
+    }
+
+    fn dependency_collection(&self) -> Collection<TaskDependency> {
+        self.db_state
+            .get_db()
+            .collection::<TaskDependency>("task_dependencies")
+    }
+
+    // //* Tarea a la que el usuario tiene acceso a través de su proyecto
+    async fn find_task(&self, task_id: ObjectId, user_id: ObjectId) -> Result<Task, AppError> {
+        let task = self
+            .task_collection()
+            .find_one(doc! {"_id": task_id})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .ok_or_else(|| AppError::NotFound("Tarea no encontrada".to_string()))?;
+
+        PermissionService::new(self.db_state.get_db())
+            .can_access_project(task.project_id, user_id)
+            .await?;
+
+        Ok(task)
+    }
+
+    // //* Todas las dependencias de un proyecto, sin comprobar permisos
+    pub async fn project_dependencies(
+        &self,
+        project_id: ObjectId,
+    ) -> Result<Vec<TaskDependency>, AppError> {
+        self.dependency_collection()
+            .find(doc! {"project_id": project_id})
+            .sort(doc! {"created_at": 1, "_id": 1})
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    // //* Predecesoras y sucesoras de una tarea
+    pub async fn get_dependencies(
+        &self,
+        task_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<TaskDependencies, AppError> {
+        self.find_task(task_id, user_id).await?;
+        self.task_dependencies(task_id).await
+    }
+
+    async fn task_dependencies(&self, task_id: ObjectId) -> Result<TaskDependencies, AppError> {
+        let links: Vec<TaskDependency> = self
+            .dependency_collection()
+            .find(doc! {"$or": [{"predecessor_id": task_id}, {"successor_id": task_id}]})
+            .sort(doc! {"created_at": 1, "_id": 1})
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let related_ids: Vec<ObjectId> = links
+            .iter()
+            .map(|link| {
+                if link.successor_id == task_id {
+                    link.predecessor_id
+                } else {
+                    link.successor_id
+                }
+            })
+            .collect();
+        let tasks: HashMap<ObjectId, Task> = self
+            .task_collection()
+            .find(doc! {"_id": {"$in": &related_ids}})
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .try_collect::<Vec<Task>>()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .into_iter()
+            .filter_map(|task| task.id.map(|id| (id, task)))
+            .collect();
+
+        let summary = |id: &ObjectId| {
+            tasks.get(id).map(|task| DependencyTask {
+                task_id: id.to_hex(),
+                key: task.key.clone(),
+                title: task.title.clone(),
+                status: task.status.clone(),
+            })
+        };
+        Ok(TaskDependencies {
+            task_id: task_id.to_hex(),
+            predecessors: links
+                .iter()
+                .filter(|link| link.successor_id == task_id)
+                .filter_map(|link| summary(&link.predecessor_id))
+                .collect(),
+            successors: links
+                .iter()
+                .filter(|link| link.predecessor_id == task_id)
+                .filter_map(|link| summary(&link.successor_id))
+                .collect(),
+        })
+    }
+
+    // //* Añadir una predecesora a la tarea
+    pub async fn add_dependency(
+        &self,
+        task_id: ObjectId,
+        user_id: ObjectId,
+        schema: CreateDependencySchema,
+    ) -> Result<TaskDependencies, AppError> {
+        let predecessor_id = ObjectId::parse_str(&schema.predecessor_id).map_err(|_| {
+            AppError::ValidationError("ID de tarea predecesora inválido".to_string())
+        })?;
+        if predecessor_id == task_id {
+            return Err(AppError::ValidationError(
+                "Una tarea no puede depender de sí misma".to_string(),
+            ));
+        }
+
+        let task = self.find_task(task_id, user_id).await?;
+        let predecessor = self
+            .task_collection()
+            .find_one(doc! {"_id": predecessor_id})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .filter(|predecessor| predecessor.project_id == task.project_id)
+            .ok_or_else(|| {
+                AppError::NotFound("Tarea predecesora no encontrada en el proyecto".to_string())
+            })?;
+
+        let links = self.project_dependencies(task.project_id).await?;
+        if links
+            .iter()
+            .any(|link| link.predecessor_id == predecessor_id && link.successor_id == task_id)
+        {
+            return Err(AppError::Conflict("La dependencia ya existe".to_string()));
+        }
+        // //! Si la predecesora ya depende (directa o indirectamente) de la tarea, se cerraría
+        // //! un ciclo y ninguna de las dos podría empezar
+        if depends_on(&links, predecessor_id, task_id) {
+            return Err(AppError::ValidationError(
+                "La dependencia crearía un ciclo entre tareas".to_string(),
+            ));
+        }
+
+        // El índice único también rechaza la dependencia si otra petición la creó a la vez
+        let inserted = self
+            .dependency_collection()
+            .insert_one(TaskDependency {
+                id: None,
+                project_id: task.project_id,
+                predecessor_id,
+                successor_id: task_id,
+                created_by: user_id,
+                created_at: Utc::now(),
+            })
+            .await
+            .map_err(|e| match *e.kind {
+                ErrorKind::Write(WriteFailure::WriteError(ref error)) if error.code == 11000 => {
+                    AppError::Conflict("La dependencia ya existe".to_string())
+                }
+                _ => AppError::DatabaseError(e.to_string()),
+            })?;
+
+        // //! Dos peticiones simultáneas (A→B y B→A) pasan la comprobación anterior por
+        // //! separado. Se repite con la dependencia ya guardada y, si cierra un ciclo, se borra
+        let links = self.project_dependencies(task.project_id).await?;
+        if depends_on(&links, predecessor_id, task_id) {
+            self.dependency_collection()
+                .delete_one(doc! {"_id": inserted.inserted_id})
+                .await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            return Err(AppError::Conflict(
+                "Otra dependencia creada a la vez cerraría un ciclo; vuelve a intentarlo"
+                    .to_string(),
+            ));
+        }
+
+        self.record_change(&task, &predecessor, user_id, HistoryAction::DependencyAdded)
+            .await;
+        self.broadcast(&task);
+        self.task_dependencies(task_id).await
+    }
+
+    // //* Quitar una predecesora de la tarea
+    pub async fn remove_dependency(
+        &self,
+        task_id: ObjectId,
+        predecessor_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<TaskDependencies, AppError> {
+        let task = self.find_task(task_id, user_id).await?;
+
+        let removed = self
+            .dependency_collection()
+            .find_one_and_delete(doc! {"predecessor_id": predecessor_id, "successor_id": task_id})
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if removed.is_none() {
+            return Err(AppError::NotFound("Dependencia no encontrada".to_string()));
+        }
+
+        if let Some(predecessor) = self
+            .task_collection()
+            .find_one(doc! {"_id": predecessor_id})
+            .await
+            .ok()
+            .flatten()
+        {
+            self.record_change(
+                &task,
+                &predecessor,
+                user_id,
+                HistoryAction::DependencyRemoved,
+            )
+            .await;
+        }
+        self.broadcast(&task);
+        self.task_dependencies(task_id).await
+    }
+
+    // El cambio se anota en la sucesora, que es la que queda condicionada
+    async fn record_change(
+        &self,
+        task: &Task,
+        predecessor: &Task,
+        user_id: ObjectId,
+        action: HistoryAction,
+    ) {
+        let Some(task_id) = task.id else {
+            return;
+        };
+        let value = history_value(predecessor.key.as_ref().unwrap_or(&predecessor.title));
+        let (old_value, new_value) = match action {
+            HistoryAction::DependencyRemoved => (value, None),
+            _ => (None, value),
+        };
+
+        HistoryService::new(self.db_state.clone())
+            .record(vec![
+                TaskHistoryEntry::new(task_id, task.project_id, user_id, action)
+                    .with_change("predecessor", old_value, new_value)
+                    .with_related(predecessor.id),
+            ])
+            .await;
+    }
+
+    fn broadcast(&self, task: &Task) {
+        let broadcast_message = serde_json::json!({
+            "event_type": "DEPENDENCIES_UPDATED",
+            "task_id": task.id.map(|id| id.to_hex()),
+            "project_id": task.project_id.to_hex(),
+        })
+        .to_string();
+
+        if let Err(e) = self.ws_tx.send(broadcast_message) {
+            tracing::warn!("Error enviando mensaje WebSocket para dependencias: {}", e);
+        }
+    }
+}
+
+// Si `from` depende, directa o indirectamente, de `task_id`
+fn depends_on(links: &[TaskDependency], from: ObjectId, task_id: ObjectId) -> bool {
+    let mut predecessors: HashMap<ObjectId, Vec<ObjectId>> = HashMap::new();
+    for link in links {
+        predecessors
+            .entry(link.successor_id)
+            .or_default()
+            .push(link.predecessor_id);
+    }
+
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::from([from]);
+    while let Some(current) = queue.pop_front() {
+        if current == task_id {
+            return true;
+        }
+        if visited.insert(current) {
+            queue.extend(predecessors.get(&current).into_iter().flatten());
+        }
+    }
+    false
+}
@@ -0,0 +1,281 @@
+use futures::TryStreamExt;
+use mongodb::{
+    Collection,
+    bson::{doc, oid::ObjectId},
+};
+use serde::de::DeserializeOwned;
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use crate::{
+    db::DatabaseState,
+    errors::AppError,
+    models::{
+        notification_model::{EmailDelivery, Notification, NotificationPreferences},
+        project_models::Project,
+        task_model::Task,
+        user_model::User,
+    },
+    services::{
+        mail_service::{EmailMessage, Mailer},
+        notification_service::task_label,
+    },
+    utils::email_templates::{
+        NotificationLine, digest_email, immediate_email, notification_line, status_name,
+    },
+};
+
+// Usuarios, tareas y proyectos citados en un lote de avisos
+struct Context {
+    users: HashMap<ObjectId, User>,
+    tasks: HashMap<ObjectId, Task>,
+    projects: HashMap<ObjectId, Project>,
+    preferences: HashMap<ObjectId, NotificationPreferences>,
+}
+
+pub struct EmailNotificationService {
+    db_state: Arc<DatabaseState>,
+    mailer: Arc<dyn Mailer>,
+}
+
+impl EmailNotificationService {
+    pub fn new(db_state: Arc<DatabaseState>, mailer: Arc<dyn Mailer>) -> Self {
+        Self { db_state, mailer }
+    }
+
+    fn notification_collection(&self) -> Collection<Notification> {
+        self.db_state
+            .get_db()
+            .collection::<Notification>("notifications")
+    }
+
+    // Documentos de una colección cuyo `field` es uno de `ids`
+    async fn find_in<T>(
+        &self,
+        name: &str,
+        field: &str,
+        ids: Vec<ObjectId>,
+    ) -> Result<Vec<T>, AppError>
+    where
+        T: DeserializeOwned + Send + Sync,
+    {
+        self.db_state
+            .get_db()
+            .collection::<T>(name)
+            .find(doc! {field: {"$in": ids}})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    async fn load_context(&self, notifications: &[Notification]) -> Result<Context, AppError> {
+        let mut user_ids: Vec<ObjectId> = notifications
+            .iter()
+            .flat_map(|n| [n.user_id, n.actor_id])
+            .collect();
+        user_ids.sort();
+        user_ids.dedup();
+        let recipients: Vec<ObjectId> = notifications.iter().map(|n| n.user_id).collect();
+        let task_ids = notifications.iter().filter_map(|n| n.task_id).collect();
+        let project_ids = notifications.iter().map(|n| n.project_id).collect();
+
+        let users: Vec<User> = self.find_in("users", "_id", user_ids).await?;
+        let tasks: Vec<Task> = self.find_in("tasks", "_id", task_ids).await?;
+        let projects: Vec<Project> = self.find_in("projects", "_id", project_ids).await?;
+        let preferences: Vec<NotificationPreferences> = self
+            .find_in("notification_preferences", "user_id", recipients)
+            .await?;
+
+        Ok(Context {
+            users: users
+                .into_iter()
+                .filter_map(|user| Some((user.id?, user)))
+                .collect(),
+            tasks: tasks
+                .into_iter()
+                .filter_map(|task| Some((task.id?, task)))
+                .collect(),
+            projects: projects
+                .into_iter()
+                .filter_map(|project| Some((project.id?, project)))
+                .collect(),
+            preferences: preferences
+                .into_iter()
+                .map(|preferences| (preferences.user_id, preferences))
+                .collect(),
+        })
+    }
+
+    fn line(context: &Context, locale: &str, notification: &Notification) -> String {
+        let actor = context
+            .users
+            .get(&notification.actor_id)
+            .map_or("?", |user| user.username.as_str());
+        let project = context
+            .projects
+            .get(&notification.project_id)
+            .map_or("?", |project| project.name.as_str());
+        let task = notification
+            .task_id
+            .and_then(|task_id| context.tasks.get(&task_id))
+            .map(task_label)
+            .unwrap_or_default();
+        let status = notification
+            .status
+            .as_ref()
+            .map(|status| status_name(locale, status))
+            .unwrap_or_default();
+
+        notification_line(
+            locale,
+            &NotificationLine {
+                kind: notification.kind,
+                actor,
+                project,
+                task: &task,
+                status,
+            },
+        )
+    }
+
+    // //* Enviar los correos pendientes de un tipo de entrega: uno por aviso si son
+    // //* inmediatos y uno por usuario si van al resumen. Devuelve los correos enviados.
+    pub async fn send_pending(&self, delivery: EmailDelivery) -> Result<usize, AppError> {
+        let filter = match delivery {
+            EmailDelivery::Immediate => doc! {"email": "immediate"},
+            EmailDelivery::Digest => doc! {"email": "digest"},
+            EmailDelivery::Off => return Ok(0),
+        };
+        let pending: Vec<Notification> = self
+            .notification_collection()
+            .find(filter)
+            .sort(doc! {"created_at": 1, "_id": 1})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        let context = self.load_context(&pending).await?;
+
+        // Agrupar por destinatario conservando el orden de llegada
+        let mut by_user: Vec<(ObjectId, Vec<Notification>)> = Vec::new();
+        for notification in pending {
+            match by_user
+                .iter_mut()
+                .find(|(user_id, _)| *user_id == notification.user_id)
+            {
+                Some((_, notifications)) => notifications.push(notification),
+                None => by_user.push((notification.user_id, vec![notification])),
+            }
+        }
+
+        let mut sent = 0;
+        for (user_id, notifications) in by_user {
+            let preferences = context
+                .preferences
+                .get(&user_id)
+                .cloned()
+                .unwrap_or_else(|| NotificationPreferences::default_for(user_id));
+
+            // Lo que el usuario ha desactivado después, o ya ha leído en la aplicación
+            // antes del resumen, no se envía
+            let (to_send, discarded): (Vec<Notification>, Vec<Notification>) =
+                notifications.into_iter().partition(|notification| {
+                    preferences.delivery_for(notification.kind) != EmailDelivery::Off
+                        && !(delivery == EmailDelivery::Digest && notification.read)
+                });
+            self.clear_pending(&discarded).await?;
+
+            let Some(user) = context.users.get(&user_id) else {
+                self.clear_pending(&to_send).await?;
+                continue;
+            };
+            if to_send.is_empty() {
+                continue;
+            }
+
+            let locale = preferences.locale.as_str();
+            let batches: Vec<Vec<Notification>> = match delivery {
+                EmailDelivery::Digest => vec![to_send],
+                _ => to_send.into_iter().map(|n| vec![n]).collect(),
+            };
+            for batch in batches {
+                let lines: Vec<String> = batch
+                    .iter()
+                    .map(|notification| Self::line(&context, locale, notification))
+                    .collect();
+                let (subject, body) = match delivery {
+                    EmailDelivery::Digest => digest_email(locale, &user.username, &lines),
+                    _ => {
+                        let project = context
+                            .projects
+                            .get(&batch[0].project_id)
+                            .map_or("?", |project| project.name.as_str());
+                        immediate_email(locale, &user.username, project, &lines[0])
+                    }
+                };
+                let message = EmailMessage {
+                    to: user.email.clone(),
+                    subject,
+                    body,
+                };
+
+                // Si el envío falla los avisos siguen pendientes para el siguiente intento
+                match self.mailer.send(&message).await {
+                    Ok(()) => {
+                        self.clear_pending(&batch).await?;
+                        sent += 1;
+                    }
+                    Err(e) => tracing::warn!("Error enviando correo a {}: {}", user.email, e),
+                }
+            }
+        }
+
+        Ok(sent)
+    }
+
+    async fn clear_pending(&self, notifications: &[Notification]) -> Result<(), AppError> {
+        let ids: Vec<ObjectId> = notifications.iter().filter_map(|n| n.id).collect();
+        if ids.is_empty() {
+            return Ok(());
+        }
+        self.notification_collection()
+            .update_many(doc! {"_id": {"$in": ids}}, doc! {"$unset": {"email": ""}})
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        Ok(())
+    }
+
+    // Tarea de fondo: correos inmediatos cada `immediate_interval` y resumen cada
+    // `digest_interval`
+    pub fn spawn_email_jobs(
+        db_state: Arc<DatabaseState>,
+        mailer: Arc<dyn Mailer>,
+        immediate_interval: Duration,
+        digest_interval: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let email_service = EmailNotificationService::new(db_state, mailer);
+            let mut immediate_ticker = tokio::time::interval(immediate_interval);
+            let mut digest_ticker = tokio::time::interval(digest_interval);
+            // El primer tic de un intervalo es inmediato; el resumen espera un periodo completo
+            digest_ticker.tick().await;
+            loop {
+                let delivery = tokio::select! {
+                    _ = immediate_ticker.tick() => EmailDelivery::Immediate,
+                    _ = digest_ticker.tick() => EmailDelivery::Digest,
+                };
+                match email_service.send_pending(delivery).await {
+                    Ok(0) => {}
+                    Ok(count) => tracing::info!("Enviados {} correos de notificación", count),
+                    Err(e) => tracing::error!("Error enviando correos de notificación: {}", e),
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,274 @@
+use futures::TryStreamExt;
+use mongodb::{
+    Collection,
+    bson::{doc, oid::ObjectId},
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::Arc;
+use validator::Validate;
+
+use crate::{
+    db::DatabaseState,
+    errors::AppError,
+    models::{
+        history_model::{ActivityPage, ActivityQuery, HistoryAction, TaskHistoryEntry},
+        task_model::Task,
+    },
+    services::permission_service::PermissionService,
+    utils::cursor,
+};
+
+pub struct HistoryService {
+    db_state: Arc<DatabaseState>,
+}
+
+// Valor JSON para el historial; los ObjectId se guardan en hex
+pub fn history_value<T: Serialize>(value: &T) -> Option<Value> {
+    match serde_json::to_value(value).ok()? {
+        Value::Null => None,
+        Value::Object(map) if map.len() == 1 && map.contains_key("$oid") => {
+            map.get("$oid").cloned()
+        }
+        other => Some(other),
+    }
+}
+
+impl HistoryService {
+    pub fn new(db_state: Arc<DatabaseState>) -> Self {
+        Self { db_state }
+    }
+
+    fn history_collection(&self) -> Collection<TaskHistoryEntry> {
+        self.db_state
+            .get_db()
+            .collection::<TaskHistoryEntry>("task_history")
+    }
+
+    // //* Guardar entradas de historial. Un fallo aquí no debe deshacer el cambio
+    // //* ya aplicado, así que solo se registra en el log.
+    pub async fn record(&self, entries: Vec<TaskHistoryEntry>) {
+        if entries.is_empty() {
+            return;
+        }
+        if let Err(e) = self.history_collection().insert_many(entries).await {
+            tracing::warn!("Error guardando el historial de la tarea: {}", e);
+        }
+    }
+
+    // //* Comparar dos versiones de una tarea y generar una entrada por campo cambiado
+    pub fn task_changes(before: &Task, after: &Task, actor_id: ObjectId) -> Vec<TaskHistoryEntry> {
+        let Some(task_id) = before.id else {
+            return Vec::new();
+        };
+
+        let fields: [(&str, Option<Value>, Option<Value>); 13] = [
+            (
+                "title",
+                history_value(&before.title),
+                history_value(&after.title),
+            ),
+            (
+                "description",
+                history_value(&before.description),
+                history_value(&after.description),
+            ),
+            (
+                "status",
+                history_value(&before.status),
+                history_value(&after.status),
+            ),
+            (
+                "priority",
+                history_value(&before.priority),
+                history_value(&after.priority),
+            ),
+            (
+                "assignee_id",
+                history_value(&before.assignee_id),
+                history_value(&after.assignee_id),
+            ),
+            (
+                "original_estimate_minutes",
+                history_value(&before.original_estimate_minutes),
+                history_value(&after.original_estimate_minutes),
+            ),
+            (
+                "remaining_estimate_minutes",
+                history_value(&before.remaining_estimate_minutes),
+                history_value(&after.remaining_estimate_minutes),
+            ),
+            (
+                "story_points",
+                history_value(&before.story_points),
+                history_value(&after.story_points),
+            ),
+            (
+                "sprint_id",
+                history_value(&before.sprint_id),
+                history_value(&after.sprint_id),
+            ),
+            (
+                "epic_id",
+                history_value(&before.epic_id),
+                history_value(&after.epic_id),
+            ),
+            (
+                "project_id",
+                history_value(&before.project_id),
+                history_value(&after.project_id),
+            ),
+            ("key", history_value(&before.key), history_value(&after.key)),
+            (
+                "labels",
+                history_value(&before.labels),
+                history_value(&after.labels),
+            ),
+        ];
+
+        // Los campos personalizados se registran como `cf.<clave>`
+        let mut custom_keys: Vec<&String> = before
+            .custom_fields
+            .keys()
+            .chain(after.custom_fields.keys())
+            .collect();
+        custom_keys.sort();
+        custom_keys.dedup();
+        let custom_fields = custom_keys.into_iter().map(|key| {
+            (
+                format!("cf.{}", key),
+                before.custom_fields.get(key).and_then(history_value),
+                after.custom_fields.get(key).and_then(history_value),
+            )
+        });
+
+        fields
+            .into_iter()
+            .map(|(field, old_value, new_value)| (field.to_string(), old_value, new_value))
+            .chain(custom_fields)
+            .filter(|(_, old_value, new_value)| old_value != new_value)
+            .map(|(field, old_value, new_value)| {
+                TaskHistoryEntry::new(task_id, after.project_id, actor_id, HistoryAction::Updated)
+                    .with_change(&field, old_value, new_value)
+            })
+            .collect()
+    }
+
+    // //* Proyecto de una tarea, aunque ya se haya eliminado (se busca en su historial)
+    async fn project_for_task(&self, task_id: ObjectId) -> Result<ObjectId, AppError> {
+        let task = self
+            .db_state
+            .get_db()
+            .collection::<Task>("tasks")
+            .find_one(doc! {"_id": task_id})
+            .await
+            .map_err(|_| AppError::InternalServerError)?;
+        if let Some(task) = task {
+            return Ok(task.project_id);
+        }
+
+        self.history_collection()
+            .find_one(doc! {"task_id": task_id})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .map(|entry| entry.project_id)
+            .ok_or_else(|| AppError::NotFound("Tarea no encontrada".to_string()))
+    }
+
+    // //* Historial completo de una tarea en orden cronológico
+    pub async fn get_task_history(
+        &self,
+        task_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<Vec<TaskHistoryEntry>, AppError> {
+        let project_id = self.project_for_task(task_id).await?;
+        PermissionService::new(self.db_state.get_db())
+            .can_access_project(project_id, user_id)
+            .await?;
+
+        let entries: Vec<TaskHistoryEntry> = self
+            .history_collection()
+            .find(doc! {"task_id": task_id})
+            .sort(doc! {"created_at": 1, "_id": 1})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        Ok(entries.into_iter().map(without_comment_content).collect())
+    }
+
+    // //* Actividad reciente de un proyecto, de la más nueva a la más antigua
+    pub async fn get_project_activity(
+        &self,
+        project_id: ObjectId,
+        user_id: ObjectId,
+        query: ActivityQuery,
+    ) -> Result<ActivityPage, AppError> {
+        query
+            .validate()
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+        PermissionService::new(self.db_state.get_db())
+            .can_access_project(project_id, user_id)
+            .await?;
+
+        let mut filter = doc! {"project_id": project_id};
+        if let Some(cursor) = &query.cursor {
+            let cursor = cursor::decode(cursor)?;
+            let invalid = || AppError::ValidationError("Cursor de paginación inválido".to_string());
+            let created_at = cursor.get_datetime("created_at").map_err(|_| invalid())?;
+            let last_id = cursor.get_object_id("id").map_err(|_| invalid())?;
+            filter.insert(
+                "$or",
+                vec![
+                    doc! {"created_at": {"$lt": created_at}},
+                    doc! {"created_at": created_at, "_id": {"$lt": last_id}},
+                ],
+            );
+        }
+
+        let limit = query.limit.unwrap_or(50) as i64;
+        let mut items: Vec<TaskHistoryEntry> = self
+            .history_collection()
+            .find(filter)
+            .sort(doc! {"created_at": -1, "_id": -1})
+            .limit(limit + 1)
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let has_more = items.len() as i64 > limit;
+        items.truncate(limit as usize);
+        let items: Vec<TaskHistoryEntry> = items.into_iter().map(without_comment_content).collect();
+        let next_cursor = match items.last() {
+            Some(last) if has_more => Some(cursor::encode(&doc! {
+                "created_at": bson::DateTime::from_chrono(last.created_at),
+                "id": last.id,
+            })),
+            _ => None,
+        };
+
+        Ok(ActivityPage { items, next_cursor })
+    }
+}
+
+// //! Las entradas de comentarios guardadas antes de registrar solo su id llevan el texto, que
+// //! podría estar oculto por moderación o borrado: nunca se devuelve desde el historial
+fn without_comment_content(mut entry: TaskHistoryEntry) -> TaskHistoryEntry {
+    if matches!(
+        entry.action,
+        HistoryAction::CommentAdded
+            | HistoryAction::CommentUpdated
+            | HistoryAction::CommentDeleted
+            | HistoryAction::CommentHidden
+            | HistoryAction::CommentRestored
+    ) {
+        entry.field = None;
+        entry.old_value = None;
+        entry.new_value = None;
+    }
+    entry
+}
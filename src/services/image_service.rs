@@ -19,11 +19,17 @@ use crate::{
     config::Config,
     db::DatabaseState,
     errors::AppError,
-    models::image_model::{Image, ImageResponse, UpdateImageSchema},
+    models::{
+        history_model::{HistoryAction, TaskHistoryEntry},
+        image_model::{Image, ImageResponse, UpdateImageSchema},
+        task_model::Task,
+    },
+    services::history_service::{HistoryService, history_value},
 };
 
 #[derive(Clone)]
 pub struct ImageService {
+    db: Arc<DatabaseState>,
     collection: Collection<Image>,
     gcs_client: Client,
     config: Arc<Config>,
@@ -49,6 +55,7 @@ impl ImageService {
         };
 
         Ok(Self {
+            db,
             collection,
             gcs_client,
             config,
@@ -61,12 +68,44 @@ impl ImageService {
         // Para tests, creamos un cliente con configuración por defecto que no se usará
         let gcs_client = Client::new(ClientConfig::default());
         Self {
+            db,
             collection,
             gcs_client,
             config,
         }
     }
 
+    // Registrar en el historial de la tarea que se adjuntó o quitó un archivo
+    async fn record_attachment(
+        &self,
+        task_id: ObjectId,
+        actor_id: ObjectId,
+        action: HistoryAction,
+        image_id: ObjectId,
+        filename: &str,
+    ) {
+        let task = self
+            .db
+            .db
+            .collection::<Task>("tasks")
+            .find_one(doc! { "_id": task_id })
+            .await;
+        let Ok(Some(task)) = task else {
+            return;
+        };
+
+        let (old_value, new_value) = match action {
+            HistoryAction::AttachmentRemoved => (history_value(&filename), None),
+            _ => (None, history_value(&filename)),
+        };
+        let entry = TaskHistoryEntry::new(task_id, task.project_id, actor_id, action)
+            .with_change("attachment", old_value, new_value)
+            .with_related(Some(image_id));
+        HistoryService::new(self.db.clone())
+            .record(vec![entry])
+            .await;
+    }
+
     async fn create_gcs_client() -> Result<Client, Box<dyn std::error::Error + Send + Sync>> {
         let client_config = ClientConfig::default()
             .with_auth()
@@ -160,6 +199,17 @@ impl ImageService {
                 AppError::InternalServerError
             })?;
 
+        if let Some(task_id) = image.task_id {
+            self.record_attachment(
+                task_id,
+                user_id,
+                HistoryAction::AttachmentAdded,
+                inserted_id,
+                &image.original_filename,
+            )
+            .await;
+        }
+
         let mut saved_image = image;
         saved_image.id = Some(inserted_id);
 
@@ -241,13 +291,16 @@ impl ImageService {
             }
         }
 
+        let mut new_task_id = image.task_id;
         if let Some(task_id) = update_data.task_id {
             if !task_id.is_empty() {
                 let task_oid = ObjectId::parse_str(&task_id)
                     .map_err(|_| AppError::ValidationError("ID de tarea inválido".to_string()))?;
                 update_doc.insert("task_id", task_oid);
+                new_task_id = Some(task_oid);
             } else {
                 update_doc.insert("task_id", mongodb::bson::Bson::Null);
+                new_task_id = None;
             }
         }
 
@@ -256,6 +309,30 @@ impl ImageService {
             .await
             .map_err(|e| AppError::DatabaseError(format!("Error actualizando imagen: {}", e)))?;
 
+        // Si el archivo cambió de tarea, ambas tareas lo reflejan en su historial
+        if new_task_id != image.task_id {
+            if let Some(old_task_id) = image.task_id {
+                self.record_attachment(
+                    old_task_id,
+                    user_id,
+                    HistoryAction::AttachmentRemoved,
+                    image_id,
+                    &image.original_filename,
+                )
+                .await;
+            }
+            if let Some(task_id) = new_task_id {
+                self.record_attachment(
+                    task_id,
+                    user_id,
+                    HistoryAction::AttachmentAdded,
+                    image_id,
+                    &image.original_filename,
+                )
+                .await;
+            }
+        }
+
         self.get_image(image_id).await
     }
 
@@ -274,8 +351,8 @@ impl ImageService {
         // Eliminar el archivo de Google Cloud Storage
         if !cfg!(test) {
             let delete_request = DeleteObjectRequest {
-                bucket: image.gcs_bucket,
-                object: image.gcs_object_name,
+                bucket: image.gcs_bucket.clone(),
+                object: image.gcs_object_name.clone(),
                 ..Default::default()
             };
 
@@ -294,6 +371,17 @@ impl ImageService {
             .await
             .map_err(|e| AppError::DatabaseError(format!("Error eliminando imagen de la base de datos: {}", e)))?;
 
+        if let Some(task_id) = image.task_id {
+            self.record_attachment(
+                task_id,
+                user_id,
+                HistoryAction::AttachmentRemoved,
+                image_id,
+                &image.original_filename,
+            )
+            .await;
+        }
+
         Ok(())
     }
 
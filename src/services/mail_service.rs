@@ -0,0 +1,138 @@
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::{Mailbox, header::ContentType},
+    transport::smtp::authentication::Credentials,
+};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, sync::Arc};
+
+use crate::config::Config;
+
+// Correo ya renderizado, listo para enviar
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+// Transporte de correo; la aplicación solo conoce este trait
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, message: &EmailMessage) -> Result<()>;
+}
+
+// Envío real a través de un servidor SMTP con STARTTLS
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub fn new(
+        host: &str,
+        port: u16,
+        username: Option<String>,
+        password: Option<String>,
+        from: &str,
+    ) -> Result<Self> {
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+            .map_err(|e| anyhow!("Error configurando SMTP: {}", e))?
+            .port(port);
+        if let Some(username) = username {
+            builder = builder.credentials(Credentials::new(username, password.unwrap_or_default()));
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from: from
+                .parse()
+                .map_err(|e| anyhow!("Remitente de correo inválido: {}", e))?,
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, message: &EmailMessage) -> Result<()> {
+        let email = Message::builder()
+            .from(self.from.clone())
+            .to(message
+                .to
+                .parse()
+                .map_err(|e| anyhow!("Destinatario inválido: {}", e))?)
+            .subject(&message.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(message.body.clone())
+            .map_err(|e| anyhow!("Error construyendo el correo: {}", e))?;
+
+        self.transport
+            .send(email)
+            .await
+            .map_err(|e| anyhow!("Error enviando el correo: {}", e))?;
+        Ok(())
+    }
+}
+
+// Deja cada correo como un fichero JSON en un directorio; pensado para desarrollo y tests
+pub struct FileMailer {
+    dir: PathBuf,
+}
+
+impl FileMailer {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    // Correos escritos hasta ahora, del más antiguo al más reciente
+    pub fn sent(&self) -> Result<Vec<EmailMessage>> {
+        let mut paths: Vec<PathBuf> = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        paths.sort();
+
+        paths
+            .iter()
+            .map(|path| Ok(serde_json::from_slice(&std::fs::read(path)?)?))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, message: &EmailMessage) -> Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        // Los ObjectId crecen con el tiempo, así el nombre conserva el orden de envío
+        let path = self.dir.join(format!("{}.json", ObjectId::new().to_hex()));
+        tokio::fs::write(path, serde_json::to_vec_pretty(message)?).await?;
+        Ok(())
+    }
+}
+
+// Transporte según la configuración: SMTP si hay servidor, si no un directorio local
+pub fn mailer_from_config(config: &Config) -> Result<Arc<dyn Mailer>> {
+    match &config.smtp_host {
+        Some(host) => Ok(Arc::new(SmtpMailer::new(
+            host,
+            config.smtp_port,
+            config.smtp_username.clone(),
+            config.smtp_password.clone(),
+            &config.mail_from,
+        )?)),
+        None => {
+            tracing::warn!(
+                "SMTP_HOST no configurado; los correos se guardarán en {}",
+                config.mail_outbox_dir
+            );
+            Ok(Arc::new(FileMailer::new(&config.mail_outbox_dir)))
+        }
+    }
+}
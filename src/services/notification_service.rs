@@ -0,0 +1,331 @@
+use futures::TryStreamExt;
+use mongodb::{
+    Collection,
+    bson::{doc, oid::ObjectId},
+};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::broadcast;
+use validator::Validate;
+
+use crate::{
+    db::DatabaseState,
+    errors::AppError,
+    models::{
+        notification_model::{
+            EmailDelivery, Notification, NotificationCount, NotificationKind, NotificationPage,
+            NotificationPreferences, NotificationQuery, UpdateNotificationPreferencesSchema,
+        },
+        task_model::Task,
+    },
+    utils::cursor,
+};
+
+// Nombre con el que se cita una tarea en los avisos
+pub fn task_label(task: &Task) -> String {
+    match &task.key {
+        Some(key) => format!("{} «{}»", key, task.title),
+        None => format!("«{}»", task.title),
+    }
+}
+
+pub struct NotificationService {
+    db_state: Arc<DatabaseState>,
+    ws_tx: broadcast::Sender<String>,
+}
+
+impl NotificationService {
+    pub fn new(db_state: Arc<DatabaseState>, ws_tx: broadcast::Sender<String>) -> Self {
+        Self { db_state, ws_tx }
+    }
+
+    fn notification_collection(&self) -> Collection<Notification> {
+        self.db_state
+            .get_db()
+            .collection::<Notification>("notifications")
+    }
+
+    fn preferences_collection(&self) -> Collection<NotificationPreferences> {
+        self.db_state
+            .get_db()
+            .collection::<NotificationPreferences>("notification_preferences")
+    }
+
+    // //* Avisos al crear o editar una tarea: al nuevo asignado y, si cambia el estado,
+    // //* a sus observadores. `before` es None al crear la tarea.
+    pub fn task_notifications(
+        before: Option<&Task>,
+        after: &Task,
+        actor_id: ObjectId,
+    ) -> Vec<Notification> {
+        let mut notifications = Vec::new();
+
+        if let Some(assignee_id) = after.assignee_id
+            && before.is_none_or(|before| before.assignee_id != Some(assignee_id))
+        {
+            notifications.push(
+                Notification::new(
+                    assignee_id,
+                    NotificationKind::TaskAssigned,
+                    actor_id,
+                    after.project_id,
+                    format!("Te han asignado la tarea {}", task_label(after)),
+                )
+                .with_task(after.id),
+            );
+        }
+
+        if let Some(before) = before
+            && before.status != after.status
+        {
+            let status = serde_json::to_value(&after.status)
+                .ok()
+                .and_then(|status| status.as_str().map(str::to_string))
+                .unwrap_or_default();
+            notifications.extend(after.watchers.iter().map(|watcher_id| {
+                let mut notification = Notification::new(
+                    *watcher_id,
+                    NotificationKind::StatusChanged,
+                    actor_id,
+                    after.project_id,
+                    format!("La tarea {} ha pasado a {}", task_label(after), status),
+                )
+                .with_task(after.id);
+                notification.status = Some(after.status.clone());
+                notification
+            }));
+        }
+
+        notifications
+    }
+
+    // //* Guardar los avisos y enviarlos en vivo a su destinatario. Nadie recibe avisos de
+    // //* sus propias acciones. Un fallo se registra pero no interrumpe la operación.
+    pub async fn notify(&self, notifications: Vec<Notification>) {
+        let mut notifications: Vec<Notification> = notifications
+            .into_iter()
+            .filter(|notification| notification.user_id != notification.actor_id)
+            .collect();
+        if notifications.is_empty() {
+            return;
+        }
+
+        // Cada aviso queda pendiente de correo según las preferencias de su destinatario
+        let recipients: Vec<ObjectId> = notifications.iter().map(|n| n.user_id).collect();
+        let preferences = match self.preferences_for(&recipients).await {
+            Ok(preferences) => preferences,
+            Err(e) => {
+                tracing::warn!("Error leyendo preferencias de notificación: {}", e);
+                HashMap::new()
+            }
+        };
+        for notification in notifications.iter_mut() {
+            let delivery = preferences
+                .get(&notification.user_id)
+                .map_or_else(
+                    || NotificationPreferences::default_for(notification.user_id),
+                    Clone::clone,
+                )
+                .delivery_for(notification.kind);
+            notification.email = (delivery != EmailDelivery::Off).then_some(delivery);
+        }
+
+        let result = match self
+            .notification_collection()
+            .insert_many(&notifications)
+            .await
+        {
+            Ok(result) => result,
+            Err(e) => {
+                tracing::warn!("Error guardando notificaciones: {}", e);
+                return;
+            }
+        };
+
+        for (index, notification) in notifications.iter_mut().enumerate() {
+            notification.id = result
+                .inserted_ids
+                .get(&index)
+                .and_then(|id| id.as_object_id());
+
+            // Solo la conexión WebSocket del destinatario recibe el mensaje
+            let message = serde_json::json!({
+                "event_type": "NOTIFICATION_CREATED",
+                "target_user_id": notification.user_id.to_hex(),
+                "notification": notification,
+            })
+            .to_string();
+            if let Err(e) = self.ws_tx.send(message) {
+                tracing::warn!("Error enviando notificación por WebSocket: {}", e);
+            }
+        }
+    }
+
+    // //* Preferencias guardadas de varios usuarios, indexadas por usuario
+    pub async fn preferences_for(
+        &self,
+        user_ids: &[ObjectId],
+    ) -> Result<HashMap<ObjectId, NotificationPreferences>, AppError> {
+        let preferences: Vec<NotificationPreferences> = self
+            .preferences_collection()
+            .find(doc! {"user_id": {"$in": user_ids}})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(preferences
+            .into_iter()
+            .map(|preferences| (preferences.user_id, preferences))
+            .collect())
+    }
+
+    // //* Preferencias de correo del usuario, o las de por defecto si nunca las ha cambiado
+    pub async fn get_preferences(
+        &self,
+        user_id: ObjectId,
+    ) -> Result<NotificationPreferences, AppError> {
+        Ok(self
+            .preferences_collection()
+            .find_one(doc! {"user_id": user_id})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .unwrap_or_else(|| NotificationPreferences::default_for(user_id)))
+    }
+
+    // //* Cambiar solo los campos enviados; la primera vez se crean las preferencias
+    pub async fn update_preferences(
+        &self,
+        user_id: ObjectId,
+        schema: UpdateNotificationPreferencesSchema,
+    ) -> Result<NotificationPreferences, AppError> {
+        schema
+            .validate()
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+        let mut preferences = self.get_preferences(user_id).await?;
+        if let Some(locale) = schema.locale {
+            preferences.locale = locale;
+        }
+        if let Some(delivery) = schema.task_assigned {
+            preferences.task_assigned = delivery;
+        }
+        if let Some(delivery) = schema.mentioned {
+            preferences.mentioned = delivery;
+        }
+        if let Some(delivery) = schema.status_changed {
+            preferences.status_changed = delivery;
+        }
+        if let Some(delivery) = schema.added_to_project {
+            preferences.added_to_project = delivery;
+        }
+
+        self.preferences_collection()
+            .replace_one(doc! {"user_id": user_id}, &preferences)
+            .upsert(true)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        self.get_preferences(user_id).await
+    }
+
+    async fn unread_count(&self, user_id: ObjectId) -> Result<u64, AppError> {
+        self.notification_collection()
+            .count_documents(doc! {"user_id": user_id, "read": false})
+            .await
+            .map_err(|_| AppError::InternalServerError)
+    }
+
+    // //* Bandeja del usuario, de la más reciente a la más antigua
+    pub async fn list_notifications(
+        &self,
+        user_id: ObjectId,
+        query: NotificationQuery,
+    ) -> Result<NotificationPage, AppError> {
+        query
+            .validate()
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+        let mut filter = doc! {"user_id": user_id};
+        if query.unread == Some(true) {
+            filter.insert("read", false);
+        }
+        if let Some(cursor) = &query.cursor {
+            let cursor = cursor::decode(cursor)?;
+            let invalid = || AppError::ValidationError("Cursor de paginación inválido".to_string());
+            let created_at = cursor.get_datetime("created_at").map_err(|_| invalid())?;
+            let last_id = cursor.get_object_id("id").map_err(|_| invalid())?;
+            filter.insert(
+                "$or",
+                vec![
+                    doc! {"created_at": {"$lt": created_at}},
+                    doc! {"created_at": created_at, "_id": {"$lt": last_id}},
+                ],
+            );
+        }
+
+        let limit = query.limit.unwrap_or(50) as i64;
+        let mut items: Vec<Notification> = self
+            .notification_collection()
+            .find(filter)
+            .sort(doc! {"created_at": -1, "_id": -1})
+            .limit(limit + 1)
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let has_more = items.len() as i64 > limit;
+        items.truncate(limit as usize);
+        let next_cursor = match items.last() {
+            Some(last) if has_more => Some(cursor::encode(&doc! {
+                "created_at": bson::DateTime::from_chrono(last.created_at),
+                "id": last.id,
+            })),
+            _ => None,
+        };
+
+        Ok(NotificationPage {
+            items,
+            unread_count: self.unread_count(user_id).await?,
+            next_cursor,
+        })
+    }
+
+    // //* Marcar como leída una notificación propia
+    pub async fn mark_read(
+        &self,
+        notification_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<Notification, AppError> {
+        self.notification_collection()
+            .find_one_and_update(
+                doc! {"_id": notification_id, "user_id": user_id},
+                doc! {"$set": {"read": true}},
+            )
+            .with_options(
+                mongodb::options::FindOneAndUpdateOptions::builder()
+                    .return_document(mongodb::options::ReturnDocument::After)
+                    .build(),
+            )
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .ok_or_else(|| AppError::NotFound("Notificación no encontrada".to_string()))
+    }
+
+    // //* Marcar como leídas todas las notificaciones del usuario
+    pub async fn mark_all_read(&self, user_id: ObjectId) -> Result<NotificationCount, AppError> {
+        self.notification_collection()
+            .update_many(
+                doc! {"user_id": user_id, "read": false},
+                doc! {"$set": {"read": true}},
+            )
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(NotificationCount {
+            unread_count: self.unread_count(user_id).await?,
+        })
+    }
+}
@@ -1,4 +1,5 @@
 use crate::{errors::AppError, models::project_models::Project};
+use futures::TryStreamExt;
 use mongodb::{
     Collection, Database,
     bson::{doc, oid::ObjectId},
@@ -56,4 +57,20 @@ impl<'a> PermissionService<'a> {
         }
         Ok(project)
     }
+
+    // El dueño es quien administra el proyecto (p. ej. modera sus comentarios)
+    pub fn is_project_admin(project: &Project, user_id: ObjectId) -> bool {
+        project.owner_id == user_id
+    }
+
+    // Proyectos a los que el usuario tiene acceso (dueño o miembro)
+    pub async fn accessible_projects(&self, user_id: ObjectId) -> Result<Vec<Project>, AppError> {
+        self.projects_collection()
+            .find(doc! {"$or": [{"owner_id": user_id}, {"members": user_id}]})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
 }
@@ -3,29 +3,33 @@ use chrono::Utc;
 use futures::{/*stream::StreamExt*/ StreamExt, TryStreamExt};
 use mongodb::{
     Collection,
-    bson::{doc, oid::ObjectId},
+    bson::{Document, doc, oid::ObjectId},
     // options::FindOptions,
 };
 use std::sync::Arc;
+use tokio::sync::broadcast;
 use validator::Validate;
 
 use crate::{
     db::DatabaseState,
     errors::AppError,
     models::{
+        notification_model::{Notification, NotificationKind},
         project_models::{AddMemberSchema, CreateProjectSchema, Project, UpdateProjectSchema, ProjectWithRole},
         user_model::{User, UserData},
     },
-    services::permission_service::PermissionService,
+    services::{notification_service::NotificationService, permission_service::PermissionService},
+    utils::mentions::extract_mentions,
 };
 
 pub struct ProjectService {
     db_state: Arc<DatabaseState>,
+    ws_tx: broadcast::Sender<String>,
 }
 
 impl ProjectService {
-    pub fn new(db_state: Arc<DatabaseState>) -> Self {
-        Self { db_state }
+    pub fn new(db_state: Arc<DatabaseState>, ws_tx: broadcast::Sender<String>) -> Self {
+        Self { db_state, ws_tx }
     }
 
     fn projects_collection(&self) -> Collection<Project> {
@@ -64,6 +68,9 @@ impl ProjectService {
             description: schema.description,
             owner_id,
             members: vec![], // El creador es el primer miembro
+            task_counter: 0,
+            version: 0,
+            auto_schedule: false,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -122,6 +129,7 @@ impl ProjectService {
         project_id: ObjectId,
         owner_id: ObjectId,
         schema: UpdateProjectSchema,
+        expected_version: Option<i64>,
     ) -> Result<Project, AppError> {
         // 1. Validar el esquema de entrada
         schema
@@ -143,7 +151,14 @@ impl ProjectService {
             ));
         }
 
-        // 4. Construir el documento de actualización solo con los campos presentes
+        // 4. Si el cliente envió If-Match, su versión debe ser la actual
+        if let Some(expected) = expected_version
+            && expected != project.version
+        {
+            return Err(AppError::precondition_failed(&project));
+        }
+
+        // 5. Construir el documento de actualización solo con los campos presentes
         let mut update_doc = doc! {};
         if let Some(name) = schema.name {
             update_doc.insert("name", name);
@@ -151,6 +166,9 @@ impl ProjectService {
         if let Some(description) = schema.description {
             update_doc.insert("description", description);
         }
+        if let Some(auto_schedule) = schema.auto_schedule {
+            update_doc.insert("auto_schedule", auto_schedule);
+        }
 
         // Si no hay nada que actualizar, devolvemos el proyecto tal cual
         if update_doc.is_empty() {
@@ -159,10 +177,17 @@ impl ProjectService {
 
         update_doc.insert("updated_at", Utc::now());
 
-        // 5. Realizar la actualización
+        // 6. Realizar la actualización, exigiendo la versión leída si hubo If-Match
+        let mut filter = doc! { "_id": project_id };
+        if expected_version.is_some() {
+            filter.insert("version", project.version);
+        }
         let update_result = self
             .projects_collection()
-            .find_one_and_update(doc! { "_id": project_id }, doc! { "$set": update_doc })
+            .find_one_and_update(
+                filter,
+                doc! { "$set": update_doc, "$inc": { "version": 1 } },
+            )
             .with_options(
                 mongodb::options::FindOneAndUpdateOptions::builder()
                     .return_document(mongodb::options::ReturnDocument::After)
@@ -171,12 +196,19 @@ impl ProjectService {
             .await
             .map_err(|_| AppError::InternalServerError)?;
 
-        // Devolver el documento actualizado
-        update_result.ok_or_else(|| {
-            AppError::NotFound(
-                "No se pudo encontrar el proyecto después de actualizar.".to_string(),
-            )
-        })
+        // Devolver el documento actualizado; si no hubo coincidencia otro usuario lo modificó
+        match update_result {
+            Some(project) => Ok(project),
+            None => {
+                let current = self
+                    .projects_collection()
+                    .find_one(doc! { "_id": project_id })
+                    .await
+                    .map_err(|_| AppError::InternalServerError)?
+                    .ok_or_else(|| AppError::NotFound("Proyecto no encontrado.".to_string()))?;
+                Err(AppError::precondition_failed(&current))
+            }
+        }
     }
 
     pub async fn delete_project(
@@ -219,7 +251,7 @@ impl ProjectService {
             .validate()
             .map_err(|e| AppError::ValidationError(e.to_string()))?;
 
-        PermissionService::new(self.db_state.get_db())
+        let project = PermissionService::new(self.db_state.get_db())
             .is_project_owner(project_id, owner_id)
             .await?;
 
@@ -232,14 +264,29 @@ impl ProjectService {
             .map_err(|_| AppError::InternalServerError)?
             .ok_or_else(|| AppError::NotFound("Usuario no encontrado.".to_string()))?;
 
-        self.projects_collection()
+        let member_id = user_to_add.id.unwrap();
+        let update_result = self
+            .projects_collection()
             .update_one(
                 doc! { "_id": project_id },
-                doc! { "$addToSet": { "members": user_to_add.id.unwrap() } },
+                doc! { "$addToSet": { "members": member_id } },
             )
             .await
             .map_err(|_| AppError::InternalServerError)?;
 
+        // Solo se avisa si no era ya miembro
+        if update_result.modified_count > 0 {
+            NotificationService::new(self.db_state.clone(), self.ws_tx.clone())
+                .notify(vec![Notification::new(
+                    member_id,
+                    NotificationKind::AddedToProject,
+                    owner_id,
+                    project_id,
+                    format!("Te han añadido al proyecto {}", project.name),
+                )])
+                .await;
+        }
+
         Ok(())
     }
 
@@ -275,6 +322,34 @@ impl ProjectService {
         Ok(members_data)
     }
 
+    // //* Miembros del proyecto (dueño incluido) mencionados con `@usuario` en el texto,
+    // //* en el orden en que aparecen. Los nombres que no son de miembros se ignoran.
+    pub async fn mentioned_members(
+        &self,
+        project_id: ObjectId,
+        user_id: ObjectId,
+        text: &str,
+    ) -> Result<Vec<UserData>, AppError> {
+        let usernames = extract_mentions(text);
+        if usernames.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut members = self.list_members(project_id, user_id).await?;
+        members.retain(|member| {
+            usernames
+                .iter()
+                .any(|username| member.username.eq_ignore_ascii_case(username))
+        });
+        members.sort_by_key(|member| {
+            usernames
+                .iter()
+                .position(|username| member.username.eq_ignore_ascii_case(username))
+        });
+
+        Ok(members)
+    }
+
     pub async fn remove_member(
         &self,
         project_id: ObjectId,
@@ -305,6 +380,17 @@ impl ProjectService {
                 "Miembro no encontrado en el proyecto.".to_string(),
             ));
         }
+
+        // Sin acceso al proyecto deja de observar sus tareas
+        self.db_state
+            .get_db()
+            .collection::<Document>("tasks")
+            .update_many(
+                doc! { "project_id": project_id },
+                doc! { "$pull": { "watchers": member_id_to_remove } },
+            )
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
         Ok(())
     }
 }
@@ -0,0 +1,304 @@
+use chrono::Utc;
+use mongodb::{
+    Collection,
+    bson::{Bson, Document, doc, oid::ObjectId},
+};
+use std::{sync::Arc, time::Duration};
+use tokio::sync::broadcast;
+
+use crate::{
+    db::DatabaseState,
+    errors::AppError,
+    models::{
+        history_model::{HistoryAction, TaskHistoryEntry},
+        task_model::{RankTaskSchema, Task},
+    },
+    services::{
+        history_service::{HistoryService, history_value},
+        permission_service::PermissionService,
+    },
+    utils::lexorank::{self, MAX_RANK_LENGTH},
+};
+
+pub struct RankService {
+    db_state: Arc<DatabaseState>,
+    ws_tx: broadcast::Sender<String>,
+}
+
+impl RankService {
+    pub fn new(db_state: Arc<DatabaseState>, ws_tx: broadcast::Sender<String>) -> Self {
+        Self { db_state, ws_tx }
+    }
+
+    fn task_collection(&self) -> Collection<Task> {
+        self.db_state.get_db().collection::<Task>("tasks")
+    }
+
+    async fn find_task(&self, task_id: ObjectId) -> Result<Task, AppError> {
+        self.task_collection()
+            .find_one(doc! {"_id": task_id})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .ok_or_else(|| AppError::NotFound("Tarea no encontrada".to_string()))
+    }
+
+    // //* Rango para una tarea nueva: al final de la lista del proyecto
+    pub async fn next_rank_for_project(&self, project_id: ObjectId) -> Result<String, AppError> {
+        let last = self
+            .task_collection()
+            .find_one(doc! {"project_id": project_id, "rank": {"$gt": ""}})
+            .sort(doc! {"rank": -1})
+            .await
+            .map_err(|_| AppError::InternalServerError)?;
+
+        lexorank::between(last.as_ref().map(|task| task.rank.as_str()), None)
+    }
+
+    // //* Buscar el vecino inmediato de un rango en la dirección indicada
+    async fn adjacent_rank(
+        &self,
+        project_id: ObjectId,
+        rank: &str,
+        exclude: ObjectId,
+        forward: bool,
+    ) -> Result<Option<String>, AppError> {
+        // Las tareas sin rango ("") nunca cuentan como vecinas
+        let (rank_filter, direction) = if forward {
+            (doc! {"$gt": rank}, 1)
+        } else {
+            (doc! {"$lt": rank, "$gt": ""}, -1)
+        };
+
+        let neighbour = self
+            .task_collection()
+            .find_one(doc! {
+                "project_id": project_id,
+                "_id": {"$ne": exclude},
+                "rank": rank_filter,
+            })
+            .sort(doc! {"rank": direction})
+            .await
+            .map_err(|_| AppError::InternalServerError)?;
+
+        Ok(neighbour.map(|task| task.rank))
+    }
+
+    async fn neighbour(
+        &self,
+        neighbour_id: Option<String>,
+        project_id: ObjectId,
+    ) -> Result<Option<Task>, AppError> {
+        let Some(neighbour_id) = neighbour_id else {
+            return Ok(None);
+        };
+        let neighbour_id = ObjectId::parse_str(&neighbour_id)
+            .map_err(|_| AppError::ValidationError("ID de tarea vecina inválido".to_string()))?;
+        let neighbour = self.find_task(neighbour_id).await?;
+        if neighbour.project_id != project_id {
+            return Err(AppError::ValidationError(
+                "Las tareas vecinas deben pertenecer al mismo proyecto".to_string(),
+            ));
+        }
+        Ok(Some(neighbour))
+    }
+
+    // //* Mover una tarea entre sus nuevos vecinos (drag & drop en el tablero)
+    pub async fn rank_task(
+        &self,
+        task_id: ObjectId,
+        user_id: ObjectId,
+        schema: RankTaskSchema,
+    ) -> Result<Task, AppError> {
+        if schema.before_task_id.is_none() && schema.after_task_id.is_none() {
+            return Err(AppError::ValidationError(
+                "Debes indicar al menos una tarea vecina".to_string(),
+            ));
+        }
+
+        let task = self.find_task(task_id).await?;
+        PermissionService::new(self.db_state.get_db())
+            .can_access_project(task.project_id, user_id)
+            .await?;
+
+        // Las tareas anteriores al ranking no tienen rango todavía
+        let has_unranked = self
+            .task_collection()
+            .find_one(doc! {"project_id": task.project_id, "rank": {"$in": ["", Bson::Null]}})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .is_some();
+        if has_unranked {
+            self.rebalance_project(task.project_id).await?;
+        }
+
+        let before = self
+            .neighbour(schema.before_task_id, task.project_id)
+            .await?;
+        let after = self
+            .neighbour(schema.after_task_id, task.project_id)
+            .await?;
+        if before.as_ref().and_then(|t| t.id) == Some(task_id)
+            || after.as_ref().and_then(|t| t.id) == Some(task_id)
+        {
+            return Err(AppError::ValidationError(
+                "Una tarea no puede ser vecina de sí misma".to_string(),
+            ));
+        }
+
+        // Si solo llega un vecino, el otro es el que hoy está a su lado
+        let prev_rank = match (&before, &after) {
+            (Some(before), _) => Some(before.rank.clone()),
+            (None, Some(after)) => {
+                self.adjacent_rank(task.project_id, &after.rank, task_id, false)
+                    .await?
+            }
+            (None, None) => None,
+        };
+        let next_rank = match (&after, &before) {
+            (Some(after), _) => Some(after.rank.clone()),
+            (None, Some(before)) => {
+                self.adjacent_rank(task.project_id, &before.rank, task_id, true)
+                    .await?
+            }
+            (None, None) => None,
+        };
+
+        let rank = lexorank::between(prev_rank.as_deref(), next_rank.as_deref())?;
+
+        // //? El rango anterior se toma del documento sobrescrito: el rebalanceo puede haberlo cambiado
+        let previous = self
+            .task_collection()
+            .find_one_and_update(
+                doc! {"_id": task_id},
+                doc! {
+                    "$set": {"rank": &rank, "updated_at": Utc::now()},
+                    "$inc": {"version": 1},
+                },
+            )
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .ok_or_else(|| AppError::NotFound("Tarea no encontrada".to_string()))?;
+        let entry =
+            TaskHistoryEntry::new(task_id, task.project_id, user_id, HistoryAction::Updated)
+                .with_change("rank", history_value(&previous.rank), history_value(&rank));
+        HistoryService::new(self.db_state.clone())
+            .record(vec![entry])
+            .await;
+
+        let broadcast_message = serde_json::json!({
+            "event_type": "TASK_RANKED",
+            "task_id": task_id.to_hex(),
+            "project_id": task.project_id.to_hex(),
+            "rank": rank,
+        })
+        .to_string();
+        if let Err(e) = self.ws_tx.send(broadcast_message) {
+            tracing::warn!(
+                "Error enviando mensaje WebSocket para tarea reordenada: {}",
+                e
+            );
+        }
+
+        self.find_task(task_id).await
+    }
+
+    // //* Redistribuir los rangos de un proyecto conservando el orden actual. Las tareas sin
+    // //* rango quedan al final, en orden de creación.
+    pub async fn rebalance_project(&self, project_id: ObjectId) -> Result<usize, AppError> {
+        let count = self
+            .task_collection()
+            .count_documents(doc! {"project_id": project_id})
+            .await
+            .map_err(|_| AppError::InternalServerError)? as usize;
+        if count == 0 {
+            return Ok(0);
+        }
+
+        // //! Una sola pasada en el servidor: $setWindowFields numera las tareas en orden y
+        // //! $merge escribe el rango de cada posición, sin buscar cada tarea en una lista
+        let ranks = lexorank::evenly_spaced(count);
+        let pipeline = vec![
+            doc! {"$match": {"project_id": project_id}},
+            doc! {"$set": {"unranked": {"$eq": [{"$ifNull": ["$rank", ""]}, ""]}}},
+            doc! {"$setWindowFields": {
+                "sortBy": {"unranked": 1, "rank": 1, "created_at": 1, "_id": 1},
+                "output": {"position": {"$documentNumber": {}}},
+            }},
+            // //? Una tarea creada tras el recuento conserva su rango hasta el próximo rebalanceo
+            doc! {"$project": {
+                "rank": {"$ifNull": [
+                    {"$arrayElemAt": [ranks, {"$subtract": ["$position", 1]}]},
+                    "$rank",
+                ]},
+            }},
+            doc! {"$merge": {
+                "into": "tasks",
+                "on": "_id",
+                "whenMatched": [{"$set": {
+                    "rank": "$$new.rank",
+                    "version": {"$add": [{"$ifNull": ["$version", 0]}, 1]},
+                }}],
+                "whenNotMatched": "discard",
+            }},
+        ];
+        self.task_collection()
+            .aggregate(pipeline)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let broadcast_message = serde_json::json!({
+            "event_type": "TASKS_REBALANCED",
+            "project_id": project_id.to_hex(),
+        })
+        .to_string();
+        if let Err(e) = self.ws_tx.send(broadcast_message) {
+            tracing::debug!("Sin receptores WebSocket para el rebalanceo: {}", e);
+        }
+
+        Ok(count)
+    }
+
+    // //* Rebalancear todos los proyectos con rangos vacíos o demasiado largos
+    pub async fn rebalance_long_ranks(&self) -> Result<usize, AppError> {
+        let filter: Document = doc! {
+            "$or": [
+                {"rank": {"$in": ["", Bson::Null]}},
+                {"$expr": {"$gt": [{"$strLenCP": {"$ifNull": ["$rank", ""]}}, MAX_RANK_LENGTH as i64]}},
+            ]
+        };
+
+        let project_ids = self
+            .task_collection()
+            .distinct("project_id", filter)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let mut rebalanced = 0;
+        for project_id in project_ids.into_iter().filter_map(|id| id.as_object_id()) {
+            self.rebalance_project(project_id).await?;
+            rebalanced += 1;
+        }
+
+        Ok(rebalanced)
+    }
+
+    // //* Tarea en segundo plano que rebalancea periódicamente los rangos
+    pub fn spawn_rebalance_job(
+        db_state: Arc<DatabaseState>,
+        ws_tx: broadcast::Sender<String>,
+        interval: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let rank_service = RankService::new(db_state, ws_tx);
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match rank_service.rebalance_long_ranks().await {
+                    Ok(0) => {}
+                    Ok(count) => tracing::info!("Rangos rebalanceados en {} proyectos", count),
+                    Err(e) => tracing::error!("Error rebalanceando rangos: {}", e),
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,193 @@
+use mongodb::{
+    Collection,
+    bson::{Document, doc, oid::ObjectId},
+};
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use validator::Validate;
+
+use crate::{
+    db::DatabaseState,
+    errors::AppError,
+    models::{
+        comment_model::Comment,
+        reaction_model::{ReactionSummary, ToggleReactionSchema, summarize_reactions},
+        task_model::Task,
+    },
+    services::permission_service::PermissionService,
+};
+
+pub struct ReactionService {
+    db_state: Arc<DatabaseState>,
+    ws_tx: broadcast::Sender<String>,
+}
+
+impl ReactionService {
+    pub fn new(db_state: Arc<DatabaseState>, ws_tx: broadcast::Sender<String>) -> Self {
+        Self { db_state, ws_tx }
+    }
+
+    fn task_collection(&self) -> Collection<Task> {
+        self.db_state.get_db().collection::<Task>("tasks")
+    }
+
+    // //* Tarea a la que el usuario tiene acceso a través de su proyecto
+    async fn find_task(&self, task_id: ObjectId, user_id: ObjectId) -> Result<Task, AppError> {
+        let task = self
+            .task_collection()
+            .find_one(doc! {"_id": task_id})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .ok_or_else(|| AppError::NotFound("Tarea no encontrada".to_string()))?;
+
+        PermissionService::new(self.db_state.get_db())
+            .can_access_project(task.project_id, user_id)
+            .await?;
+
+        Ok(task)
+    }
+
+    // //? Quitar al usuario si ya había reaccionado con ese emoji o añadirlo si no, en una
+    // //? sola operación; los emojis que se quedan sin usuarios desaparecen
+    async fn toggle<T>(
+        &self,
+        collection: Collection<T>,
+        filter: Document,
+        user_id: ObjectId,
+        emoji: &str,
+        track_changes: bool,
+    ) -> Result<Option<T>, AppError>
+    where
+        T: DeserializeOwned + Send + Sync,
+    {
+        let users = doc! {"$ifNull": [format!("$reactions.{}", emoji), []]};
+        let mut pipeline = vec![
+            doc! {
+                "$set": {
+                    format!("reactions.{}", emoji): {
+                        "$cond": [
+                            {"$in": [user_id, &users]},
+                            {"$setDifference": [&users, [user_id]]},
+                            {"$concatArrays": [&users, [user_id]]},
+                        ]
+                    }
+                }
+            },
+            doc! {
+                "$set": {
+                    "reactions": {
+                        "$arrayToObject": {
+                            "$filter": {
+                                "input": {"$objectToArray": "$reactions"},
+                                "as": "reaction",
+                                "cond": {"$gt": [{"$size": "$$reaction.v"}, 0]},
+                            }
+                        }
+                    }
+                }
+            },
+        ];
+        // Los comentarios anotan el cambio para la sincronización incremental
+        if track_changes {
+            pipeline.push(doc! {"$set": {"changed_at": "$$NOW"}});
+        }
+
+        collection
+            .find_one_and_update(filter, pipeline)
+            .with_options(
+                mongodb::options::FindOneAndUpdateOptions::builder()
+                    .return_document(mongodb::options::ReturnDocument::After)
+                    .build(),
+            )
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    fn broadcast(&self, task: &Task, comment_id: Option<ObjectId>, reactions: &[ReactionSummary]) {
+        // //? El `reacted_by_me` solo tiene sentido para quien reaccionó; se envían los recuentos
+        let counts: Vec<_> = reactions
+            .iter()
+            .map(|reaction| serde_json::json!({"emoji": reaction.emoji, "count": reaction.count}))
+            .collect();
+        let broadcast_message = serde_json::json!({
+            "event_type": "REACTIONS_UPDATED",
+            "task_id": task.id.map(|id| id.to_hex()),
+            "comment_id": comment_id.map(|id| id.to_hex()),
+            "project_id": task.project_id.to_hex(),
+            "reactions": counts,
+        })
+        .to_string();
+
+        if let Err(e) = self.ws_tx.send(broadcast_message) {
+            tracing::warn!("Error enviando mensaje WebSocket para reacciones: {}", e);
+        }
+    }
+
+    // //* Reacciones de una tarea
+    pub async fn get_task_reactions(
+        &self,
+        task_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<Vec<ReactionSummary>, AppError> {
+        let task = self.find_task(task_id, user_id).await?;
+        Ok(summarize_reactions(&task.reactions, user_id))
+    }
+
+    // //* Poner o quitar una reacción en una tarea
+    pub async fn toggle_task_reaction(
+        &self,
+        task_id: ObjectId,
+        user_id: ObjectId,
+        schema: ToggleReactionSchema,
+    ) -> Result<Vec<ReactionSummary>, AppError> {
+        schema
+            .validate()
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+        self.find_task(task_id, user_id).await?;
+
+        let task = self
+            .toggle(
+                self.task_collection(),
+                doc! {"_id": task_id},
+                user_id,
+                &schema.emoji,
+                false,
+            )
+            .await?
+            .ok_or_else(|| AppError::NotFound("Tarea no encontrada".to_string()))?;
+
+        let reactions = summarize_reactions(&task.reactions, user_id);
+        self.broadcast(&task, None, &reactions);
+        Ok(reactions)
+    }
+
+    // //* Poner o quitar una reacción en un comentario de la tarea
+    pub async fn toggle_comment_reaction(
+        &self,
+        task_id: ObjectId,
+        comment_id: ObjectId,
+        user_id: ObjectId,
+        schema: ToggleReactionSchema,
+    ) -> Result<Vec<ReactionSummary>, AppError> {
+        schema
+            .validate()
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+        let task = self.find_task(task_id, user_id).await?;
+
+        let comment: Comment = self
+            .toggle(
+                self.db_state.get_db().collection::<Comment>("comments"),
+                doc! {"_id": comment_id, "task_id": task_id, "deleted": {"$ne": true}},
+                user_id,
+                &schema.emoji,
+                true,
+            )
+            .await?
+            .ok_or_else(|| AppError::NotFound("Comentario no encontrado".to_string()))?;
+
+        let reactions = summarize_reactions(&comment.reactions, user_id);
+        self.broadcast(&task, Some(comment_id), &reactions);
+        Ok(reactions)
+    }
+}
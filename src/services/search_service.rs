@@ -0,0 +1,371 @@
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::{
+    Collection,
+    bson::{Bson, Document, doc, oid::ObjectId, to_bson},
+};
+use std::{collections::HashMap, sync::Arc};
+use validator::Validate;
+
+use crate::{
+    db::DatabaseState,
+    errors::AppError,
+    models::{
+        project_models::Project,
+        search_model::{
+            JqlSearchQuery, SearchResult, TextSearchHit, TextSearchHitKind, TextSearchQuery,
+            TextSearchResult,
+        },
+        sprint_model::{Sprint, SprintState},
+        task_model::Task,
+        user_model::User,
+    },
+    services::permission_service::PermissionService,
+    utils::{
+        highlight,
+        jql::{self, Field, JqlContext},
+    },
+};
+
+pub struct SearchService {
+    db_state: Arc<DatabaseState>,
+}
+
+impl SearchService {
+    pub fn new(db_state: Arc<DatabaseState>) -> Self {
+        Self { db_state }
+    }
+
+    fn task_collection(&self) -> Collection<Task> {
+        self.db_state.get_db().collection::<Task>("tasks")
+    }
+
+    // //* Buscar tareas con JQL en los proyectos a los que el usuario tiene acceso
+    pub async fn search_jql(
+        &self,
+        user_id: ObjectId,
+        query: JqlSearchQuery,
+    ) -> Result<SearchResult, AppError> {
+        query
+            .validate()
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+        let parsed = jql::parse(&query.jql)?;
+
+        let projects = PermissionService::new(self.db_state.get_db())
+            .accessible_projects(user_id)
+            .await?;
+        let project_ids: Vec<ObjectId> = projects.iter().filter_map(|p| p.id).collect();
+
+        let mut project_map = HashMap::new();
+        for project in &projects {
+            if let Some(id) = project.id {
+                project_map.insert(project.project_key.to_lowercase(), id);
+                project_map.insert(id.to_hex(), id);
+            }
+        }
+
+        let context = JqlContext {
+            current_user: user_id,
+            now: Utc::now(),
+            projects: project_map,
+            users: self.resolve_users(&parsed).await?,
+            open_sprints: self.open_sprints(&project_ids).await?,
+        };
+        let compiled = jql::compile(&parsed, &context)?;
+
+        // El filtro del usuario siempre queda limitado a sus proyectos
+        let filter = doc! {"$and": [
+            {"project_id": {"$in": project_ids}},
+            compiled.filter,
+        ]};
+
+        let total = self
+            .task_collection()
+            .count_documents(filter.clone())
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let max_results = query.max_results.unwrap_or(50);
+        let mut pipeline = vec![doc! {"$match": filter}];
+        let sort_keys: Vec<String> = compiled.sort_fields.keys().cloned().collect();
+        if !compiled.sort_fields.is_empty() {
+            pipeline.push(doc! {"$addFields": compiled.sort_fields});
+        }
+        pipeline.push(doc! {"$sort": compiled.sort});
+        pipeline.push(doc! {"$skip": query.start_at as i64});
+        pipeline.push(doc! {"$limit": max_results as i64});
+
+        let documents: Vec<Document> = self
+            .task_collection()
+            .aggregate(pipeline)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let items = documents
+            .into_iter()
+            .map(|mut document| {
+                for key in &sort_keys {
+                    document.remove(key);
+                }
+                bson::from_document::<Task>(document)
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))
+            })
+            .collect::<Result<Vec<Task>, AppError>>()?;
+
+        Ok(SearchResult {
+            total,
+            start_at: query.start_at,
+            max_results,
+            items,
+        })
+    }
+
+    // //* Búsqueda de texto completo en tareas y comentarios, ordenada por relevancia
+    pub async fn search_text(
+        &self,
+        user_id: ObjectId,
+        query: TextSearchQuery,
+    ) -> Result<TextSearchResult, AppError> {
+        query
+            .validate()
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+        let projects = PermissionService::new(self.db_state.get_db())
+            .accessible_projects(user_id)
+            .await?;
+        let project_ids: Vec<ObjectId> = projects.iter().filter_map(|project| project.id).collect();
+        // Los comentarios ocultos por moderación solo los encuentran los administradores
+        let (admin_projects, member_projects): (Vec<_>, Vec<_>) = projects
+            .iter()
+            .partition(|project| PermissionService::is_project_admin(project, user_id));
+
+        let max_results = query.max_results.unwrap_or(20);
+        // Cada colección aporta como mucho los primeros start_at + max_results resultados
+        let window = query.start_at as i64 + max_results as i64;
+        let terms = highlight::search_terms(&query.q);
+
+        let task_pipeline = vec![
+            doc! {"$match": {"$text": {"$search": &query.q}, "project_id": {"$in": project_ids.clone()}}},
+            doc! {"$addFields": {"score": {"$meta": "textScore"}}},
+            doc! {"$sort": {"score": -1, "_id": 1}},
+        ];
+        let (task_docs, task_total) = self.text_facet("tasks", task_pipeline, window).await?;
+
+        // //? Los comentarios no guardan el proyecto: se limitan a las tareas accesibles desde el
+        // //? primer $match para no recorrer los de toda la base de datos
+        let admin_tasks = self.project_task_ids(&admin_projects).await?;
+        let member_tasks = self.project_task_ids(&member_projects).await?;
+        let comment_pipeline = vec![
+            doc! {"$match": {
+                "$text": {"$search": &query.q},
+                "deleted": {"$ne": true},
+                "$or": [
+                    {"task_id": {"$in": admin_tasks}},
+                    {"task_id": {"$in": member_tasks}, "moderation": null},
+                ],
+            }},
+            doc! {"$addFields": {"score": {"$meta": "textScore"}}},
+            doc! {"$lookup": {
+                "from": "tasks",
+                "localField": "task_id",
+                "foreignField": "_id",
+                "as": "task",
+            }},
+            doc! {"$unwind": "$task"},
+            doc! {"$sort": {"score": -1, "_id": 1}},
+        ];
+        let (comment_docs, comment_total) = self
+            .text_facet("comments", comment_pipeline, window)
+            .await?;
+
+        let mut hits = Vec::with_capacity(task_docs.len() + comment_docs.len());
+        for document in task_docs {
+            let title = document.get_str("title").unwrap_or_default().to_string();
+            let description = document.get_str("description").unwrap_or_default();
+            // Se muestra la descripción si es donde aparece el término
+            let source = if Self::contains_term(description, &terms) {
+                description
+            } else {
+                &title
+            };
+            hits.push(TextSearchHit {
+                kind: TextSearchHitKind::Task,
+                score: document.get_f64("score").unwrap_or_default(),
+                project_id: Self::object_id(&document, "project_id")?,
+                task_id: Self::object_id(&document, "_id")?,
+                snippet: highlight::snippet(source, &terms, 160),
+                task_title: title,
+                comment_id: None,
+            });
+        }
+        for document in comment_docs {
+            let task = document
+                .get_document("task")
+                .map_err(|_| AppError::InternalServerError)?;
+            hits.push(TextSearchHit {
+                kind: TextSearchHitKind::Comment,
+                score: document.get_f64("score").unwrap_or_default(),
+                project_id: Self::object_id(task, "project_id")?,
+                task_id: Self::object_id(task, "_id")?,
+                task_title: task.get_str("title").unwrap_or_default().to_string(),
+                comment_id: Some(Self::object_id(&document, "_id")?),
+                snippet: highlight::snippet(
+                    document.get_str("content").unwrap_or_default(),
+                    &terms,
+                    160,
+                ),
+            });
+        }
+
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        let items = hits
+            .into_iter()
+            .skip(query.start_at as usize)
+            .take(max_results as usize)
+            .collect();
+
+        Ok(TextSearchResult {
+            total: task_total + comment_total,
+            start_at: query.start_at,
+            max_results,
+            items,
+        })
+    }
+
+    // Ids de las tareas de los proyectos indicados
+    async fn project_task_ids(&self, projects: &[&Project]) -> Result<Vec<Bson>, AppError> {
+        let project_ids: Vec<ObjectId> = projects.iter().filter_map(|project| project.id).collect();
+        if project_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.task_collection()
+            .distinct("_id", doc! {"project_id": {"$in": project_ids}})
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    // Ejecuta el pipeline devolviendo los primeros `window` documentos y el total
+    async fn text_facet(
+        &self,
+        collection: &str,
+        mut pipeline: Vec<Document>,
+        window: i64,
+    ) -> Result<(Vec<Document>, u64), AppError> {
+        pipeline.push(doc! {"$facet": {
+            "items": [{"$limit": window}],
+            "total": [{"$count": "count"}],
+        }});
+
+        let facet: Option<Document> = self
+            .db_state
+            .get_db()
+            .collection::<Document>(collection)
+            .aggregate(pipeline)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .try_next()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let Some(facet) = facet else {
+            return Ok((Vec::new(), 0));
+        };
+        let items = facet
+            .get_array("items")
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| item.as_document().cloned())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let total = facet
+            .get_array("total")
+            .ok()
+            .and_then(|total| total.first())
+            .and_then(|count| count.as_document())
+            .and_then(|count| count.get("count"))
+            .and_then(|count| count.as_i32().map(i64::from).or(count.as_i64()))
+            .unwrap_or(0);
+
+        Ok((items, total as u64))
+    }
+
+    fn contains_term(text: &str, terms: &[String]) -> bool {
+        let text = text.to_lowercase();
+        terms.iter().any(|term| text.contains(term.as_str()))
+    }
+
+    fn object_id(document: &Document, key: &str) -> Result<ObjectId, AppError> {
+        document
+            .get_object_id(key)
+            .map_err(|_| AppError::InternalServerError)
+    }
+
+    // //* Resolver por nombre de usuario o email los usuarios citados en la consulta
+    async fn resolve_users(
+        &self,
+        parsed: &jql::JqlQuery,
+    ) -> Result<HashMap<String, ObjectId>, AppError> {
+        let mut names = parsed.literals_for(Field::Assignee);
+        names.extend(parsed.literals_for(Field::Reporter));
+        if names.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let patterns: Vec<Bson> = names
+            .iter()
+            .map(|name| {
+                Bson::RegularExpression(bson::Regex {
+                    pattern: format!("^{}$", regex::escape(name)),
+                    options: "i".to_string(),
+                })
+            })
+            .collect();
+
+        let users: Vec<User> = self
+            .db_state
+            .get_db()
+            .collection::<User>("users")
+            .find(doc! {"$or": [
+                {"username": {"$in": patterns.clone()}},
+                {"email": {"$in": patterns}},
+            ]})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let mut map = HashMap::new();
+        for user in users {
+            if let Some(id) = user.id {
+                map.insert(user.username.to_lowercase(), id);
+                map.insert(user.email.to_lowercase(), id);
+            }
+        }
+        Ok(map)
+    }
+
+    async fn open_sprints(&self, project_ids: &[ObjectId]) -> Result<Vec<ObjectId>, AppError> {
+        let sprints: Vec<Sprint> = self
+            .db_state
+            .get_db()
+            .collection::<Sprint>("sprints")
+            .find(doc! {
+                "project_id": {"$in": project_ids.to_vec()},
+                "state": to_bson(&SprintState::Active).unwrap(),
+            })
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(sprints.into_iter().filter_map(|sprint| sprint.id).collect())
+    }
+}
@@ -0,0 +1,604 @@
+use bson::to_bson;
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::{
+    Collection,
+    bson::{Bson, doc, oid::ObjectId},
+};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use validator::Validate;
+
+use crate::{
+    db::DatabaseState,
+    errors::AppError,
+    models::{
+        history_model::{HistoryAction, TaskHistoryEntry},
+        sprint_model::{
+            CloseSprintResult, CloseSprintSchema, CreateSprintSchema, IncompleteTasksTarget,
+            Sprint, SprintState, SprintTasksSchema, UpdateSprintSchema,
+        },
+        task_model::{Task, TaskStatus},
+    },
+    services::{
+        history_service::{HistoryService, history_value},
+        permission_service::PermissionService,
+    },
+};
+
+pub struct SprintService {
+    db_state: Arc<DatabaseState>,
+    ws_tx: broadcast::Sender<String>,
+}
+
+impl SprintService {
+    pub fn new(db_state: Arc<DatabaseState>, ws_tx: broadcast::Sender<String>) -> Self {
+        Self { db_state, ws_tx }
+    }
+
+    fn sprint_collection(&self) -> Collection<Sprint> {
+        self.db_state.get_db().collection::<Sprint>("sprints")
+    }
+
+    fn task_collection(&self) -> Collection<Task> {
+        self.db_state.get_db().collection::<Task>("tasks")
+    }
+
+    fn broadcast(&self, event_type: &str, sprint: &Sprint) {
+        let broadcast_message = serde_json::json!({
+            "event_type": event_type,
+            "sprint": sprint,
+        })
+        .to_string();
+
+        if let Err(e) = self.ws_tx.send(broadcast_message) {
+            tracing::warn!("Error enviando mensaje WebSocket de sprint: {}", e);
+        }
+    }
+
+    // //* Buscar un sprint y verificar que el usuario puede acceder a su proyecto
+    async fn find_sprint(
+        &self,
+        sprint_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<Sprint, AppError> {
+        let sprint = self
+            .sprint_collection()
+            .find_one(doc! {"_id": sprint_id})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .ok_or_else(|| AppError::NotFound("Sprint no encontrado".to_string()))?;
+
+        PermissionService::new(self.db_state.get_db())
+            .can_access_project(sprint.project_id, user_id)
+            .await?;
+
+        Ok(sprint)
+    }
+
+    fn parse_task_ids(task_ids: &[String]) -> Result<Vec<ObjectId>, AppError> {
+        task_ids
+            .iter()
+            .map(ObjectId::parse_str)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| AppError::ValidationError("ID de tarea inválido".to_string()))
+    }
+
+    // //* Registrar en el historial el cambio de sprint de cada tarea movida
+    async fn record_sprint_changes(
+        &self,
+        tasks: &[Task],
+        sprint_id: Option<ObjectId>,
+        user_id: ObjectId,
+    ) {
+        let entries = tasks
+            .iter()
+            .filter(|task| task.sprint_id != sprint_id)
+            .filter_map(|task| {
+                let entry = TaskHistoryEntry::new(
+                    task.id?,
+                    task.project_id,
+                    user_id,
+                    HistoryAction::Updated,
+                );
+                Some(entry.with_change(
+                    "sprint_id",
+                    history_value(&task.sprint_id),
+                    history_value(&sprint_id),
+                ))
+            })
+            .collect();
+        HistoryService::new(self.db_state.clone())
+            .record(entries)
+            .await;
+    }
+
+    fn validate_dates(
+        start_date: Option<chrono::DateTime<Utc>>,
+        end_date: Option<chrono::DateTime<Utc>>,
+    ) -> Result<(), AppError> {
+        if let (Some(start), Some(end)) = (start_date, end_date)
+            && end < start
+        {
+            return Err(AppError::ValidationError(
+                "La fecha de fin del sprint no puede ser anterior a la de inicio".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub async fn create_sprint(
+        &self,
+        project_id: ObjectId,
+        user_id: ObjectId,
+        schema: CreateSprintSchema,
+    ) -> Result<Sprint, AppError> {
+        schema
+            .validate()
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+        Self::validate_dates(schema.start_date, schema.end_date)?;
+
+        PermissionService::new(self.db_state.get_db())
+            .can_access_project(project_id, user_id)
+            .await?;
+
+        let now = Utc::now();
+        let mut sprint = Sprint {
+            id: None,
+            project_id,
+            name: schema.name,
+            goal: schema.goal,
+            start_date: schema.start_date,
+            end_date: schema.end_date,
+            state: SprintState::Planned,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let result = self
+            .sprint_collection()
+            .insert_one(&sprint)
+            .await
+            .map_err(|_| AppError::InternalServerError)?;
+        sprint.id = result.inserted_id.as_object_id();
+
+        self.broadcast("SPRINT_CREATED", &sprint);
+
+        Ok(sprint)
+    }
+
+    pub async fn get_sprints_for_project(
+        &self,
+        project_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<Vec<Sprint>, AppError> {
+        PermissionService::new(self.db_state.get_db())
+            .can_access_project(project_id, user_id)
+            .await?;
+
+        self.sprint_collection()
+            .find(doc! {"project_id": project_id})
+            .sort(doc! {"created_at": 1})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    pub async fn update_sprint(
+        &self,
+        sprint_id: ObjectId,
+        user_id: ObjectId,
+        schema: UpdateSprintSchema,
+    ) -> Result<Sprint, AppError> {
+        schema
+            .validate()
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+        let sprint = self.find_sprint(sprint_id, user_id).await?;
+        if sprint.state == SprintState::Closed {
+            return Err(AppError::ValidationError(
+                "Un sprint cerrado no se puede modificar".to_string(),
+            ));
+        }
+
+        Self::validate_dates(
+            schema.start_date.or(sprint.start_date),
+            schema.end_date.or(sprint.end_date),
+        )?;
+
+        let mut update_doc = doc! {};
+        if let Some(name) = schema.name {
+            update_doc.insert("name", name);
+        }
+        if let Some(goal) = schema.goal {
+            update_doc.insert("goal", goal);
+        }
+        if let Some(start_date) = schema.start_date {
+            update_doc.insert("start_date", to_bson(&start_date).unwrap());
+        }
+        if let Some(end_date) = schema.end_date {
+            update_doc.insert("end_date", to_bson(&end_date).unwrap());
+        }
+
+        if update_doc.is_empty() {
+            return Ok(sprint);
+        }
+        update_doc.insert("updated_at", Utc::now());
+
+        self.set_fields(sprint_id, update_doc).await
+    }
+
+    async fn set_fields(
+        &self,
+        sprint_id: ObjectId,
+        update_doc: bson::Document,
+    ) -> Result<Sprint, AppError> {
+        self.sprint_collection()
+            .find_one_and_update(doc! {"_id": sprint_id}, doc! {"$set": update_doc})
+            .with_options(
+                mongodb::options::FindOneAndUpdateOptions::builder()
+                    .return_document(mongodb::options::ReturnDocument::After)
+                    .build(),
+            )
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .ok_or_else(|| AppError::NotFound("Sprint no encontrado".to_string()))
+    }
+
+    // //* Eliminar un sprint que no esté activo; sus tareas vuelven al backlog
+    pub async fn delete_sprint(
+        &self,
+        sprint_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<(), AppError> {
+        let sprint = self.find_sprint(sprint_id, user_id).await?;
+        if sprint.state == SprintState::Active {
+            return Err(AppError::ValidationError(
+                "No se puede eliminar un sprint activo, ciérralo primero".to_string(),
+            ));
+        }
+
+        self.task_collection()
+            .update_many(
+                doc! {"sprint_id": sprint_id},
+                doc! {
+                    "$unset": {"sprint_id": ""},
+                    "$set": {"updated_at": Utc::now()},
+                    "$inc": {"version": 1},
+                },
+            )
+            .await
+            .map_err(|_| AppError::InternalServerError)?;
+
+        self.sprint_collection()
+            .delete_one(doc! {"_id": sprint_id})
+            .await
+            .map_err(|_| AppError::InternalServerError)?;
+
+        self.broadcast("SPRINT_DELETED", &sprint);
+
+        Ok(())
+    }
+
+    // //* Iniciar un sprint planificado. Solo puede haber un sprint activo por proyecto.
+    pub async fn start_sprint(
+        &self,
+        sprint_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<Sprint, AppError> {
+        let sprint = self.find_sprint(sprint_id, user_id).await?;
+        if sprint.state != SprintState::Planned {
+            return Err(AppError::ValidationError(
+                "Solo se pueden iniciar sprints planificados".to_string(),
+            ));
+        }
+
+        let active_exists = self
+            .sprint_collection()
+            .find_one(doc! {
+                "project_id": sprint.project_id,
+                "state": to_bson(&SprintState::Active).unwrap(),
+            })
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .is_some();
+        if active_exists {
+            return Err(AppError::ValidationError(
+                "El proyecto ya tiene un sprint activo".to_string(),
+            ));
+        }
+
+        let now = Utc::now();
+        let mut update_doc = doc! {
+            "state": to_bson(&SprintState::Active).unwrap(),
+            "updated_at": now,
+        };
+        if sprint.start_date.is_none() {
+            update_doc.insert("start_date", to_bson(&now).unwrap());
+        }
+
+        let sprint = self.set_fields(sprint_id, update_doc).await?;
+        self.broadcast("SPRINT_STARTED", &sprint);
+        Ok(sprint)
+    }
+
+    // //* Cerrar el sprint activo moviendo las tareas incompletas al backlog o al siguiente sprint
+    pub async fn close_sprint(
+        &self,
+        sprint_id: ObjectId,
+        user_id: ObjectId,
+        schema: CloseSprintSchema,
+    ) -> Result<CloseSprintResult, AppError> {
+        let sprint = self.find_sprint(sprint_id, user_id).await?;
+        if sprint.state != SprintState::Active {
+            return Err(AppError::ValidationError(
+                "Solo se pueden cerrar sprints activos".to_string(),
+            ));
+        }
+
+        let next_sprint_id = match schema.move_to {
+            IncompleteTasksTarget::Backlog => None,
+            IncompleteTasksTarget::NextSprint => Some(
+                self.resolve_next_sprint(&sprint, schema.next_sprint_id)
+                    .await?,
+            ),
+        };
+
+        let completed_statuses = vec![
+            to_bson(&TaskStatus::Done).unwrap(),
+            to_bson(&TaskStatus::Cancelled).unwrap(),
+        ];
+        let incomplete_tasks: Vec<Task> = self
+            .task_collection()
+            .find(doc! {
+                "sprint_id": sprint_id,
+                "status": {"$nin": completed_statuses.clone()},
+            })
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        let incomplete_ids: Vec<ObjectId> =
+            incomplete_tasks.iter().filter_map(|task| task.id).collect();
+        // //? Solo se mueven las tareas leídas, para que el historial refleje lo que cambió
+        let incomplete_filter = doc! {
+            "_id": {"$in": incomplete_ids},
+            "sprint_id": sprint_id,
+            "status": {"$nin": completed_statuses.clone()},
+        };
+
+        let now = Utc::now();
+        let move_update = match next_sprint_id {
+            Some(next_id) => doc! {
+                "$set": {"sprint_id": next_id, "updated_at": now},
+                "$inc": {"version": 1},
+            },
+            None => doc! {
+                "$unset": {"sprint_id": ""},
+                "$set": {"updated_at": now},
+                "$inc": {"version": 1},
+            },
+        };
+
+        let moved = self
+            .task_collection()
+            .update_many(incomplete_filter, move_update)
+            .await
+            .map_err(|_| AppError::InternalServerError)?;
+        self.record_sprint_changes(&incomplete_tasks, next_sprint_id, user_id)
+            .await;
+
+        let completed_tasks = self
+            .task_collection()
+            .count_documents(doc! {"sprint_id": sprint_id, "status": {"$in": completed_statuses}})
+            .await
+            .map_err(|_| AppError::InternalServerError)?;
+
+        let mut update_doc = doc! {
+            "state": to_bson(&SprintState::Closed).unwrap(),
+            "updated_at": now,
+        };
+        if sprint.end_date.is_none() {
+            update_doc.insert("end_date", to_bson(&now).unwrap());
+        }
+        let sprint = self.set_fields(sprint_id, update_doc).await?;
+
+        self.broadcast("SPRINT_CLOSED", &sprint);
+
+        Ok(CloseSprintResult {
+            sprint,
+            completed_tasks,
+            moved_tasks: moved.modified_count,
+            moved_to_sprint_id: next_sprint_id,
+        })
+    }
+
+    async fn resolve_next_sprint(
+        &self,
+        sprint: &Sprint,
+        next_sprint_id: Option<String>,
+    ) -> Result<ObjectId, AppError> {
+        let next_sprint = match next_sprint_id {
+            Some(id) => {
+                let id = ObjectId::parse_str(&id)
+                    .map_err(|_| AppError::ValidationError("ID de sprint inválido".to_string()))?;
+                self.sprint_collection()
+                    .find_one(doc! {"_id": id, "project_id": sprint.project_id})
+                    .await
+                    .map_err(|_| AppError::InternalServerError)?
+                    .ok_or_else(|| AppError::NotFound("Sprint destino no encontrado".to_string()))?
+            }
+            None => self
+                .sprint_collection()
+                .find_one(doc! {
+                    "project_id": sprint.project_id,
+                    "state": to_bson(&SprintState::Planned).unwrap(),
+                })
+                .sort(doc! {"start_date": 1, "created_at": 1})
+                .await
+                .map_err(|_| AppError::InternalServerError)?
+                .ok_or_else(|| {
+                    AppError::ValidationError(
+                        "No hay un sprint planificado al que mover las tareas".to_string(),
+                    )
+                })?,
+        };
+
+        if next_sprint.state != SprintState::Planned {
+            return Err(AppError::ValidationError(
+                "El sprint destino debe estar planificado".to_string(),
+            ));
+        }
+
+        next_sprint.id.ok_or(AppError::InternalServerError)
+    }
+
+    // //* Añadir tareas del mismo proyecto a un sprint
+    pub async fn add_tasks(
+        &self,
+        sprint_id: ObjectId,
+        user_id: ObjectId,
+        schema: SprintTasksSchema,
+    ) -> Result<Vec<Task>, AppError> {
+        schema
+            .validate()
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+        let sprint = self.find_sprint(sprint_id, user_id).await?;
+        if sprint.state == SprintState::Closed {
+            return Err(AppError::ValidationError(
+                "No se pueden añadir tareas a un sprint cerrado".to_string(),
+            ));
+        }
+
+        // //? Un ID repetido no debe descuadrar el recuento de tareas encontradas
+        let mut task_ids = Self::parse_task_ids(&schema.task_ids)?;
+        task_ids.sort();
+        task_ids.dedup();
+        let filter = doc! {"_id": {"$in": &task_ids}, "project_id": sprint.project_id};
+
+        let tasks: Vec<Task> = self
+            .task_collection()
+            .find(filter.clone())
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if tasks.len() != task_ids.len() {
+            return Err(AppError::ValidationError(
+                "Todas las tareas deben existir y pertenecer al proyecto del sprint".to_string(),
+            ));
+        }
+
+        self.task_collection()
+            .update_many(
+                filter,
+                doc! {
+                    "$set": {"sprint_id": sprint_id, "updated_at": Utc::now()},
+                    "$inc": {"version": 1},
+                },
+            )
+            .await
+            .map_err(|_| AppError::InternalServerError)?;
+        self.record_sprint_changes(&tasks, Some(sprint_id), user_id)
+            .await;
+
+        self.broadcast("SPRINT_TASKS_CHANGED", &sprint);
+
+        self.get_sprint_tasks(sprint_id, user_id).await
+    }
+
+    // //* Quitar una tarea del sprint, devolviéndola al backlog
+    pub async fn remove_task(
+        &self,
+        sprint_id: ObjectId,
+        task_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<(), AppError> {
+        let sprint = self.find_sprint(sprint_id, user_id).await?;
+        if sprint.state == SprintState::Closed {
+            return Err(AppError::ValidationError(
+                "No se pueden quitar tareas de un sprint cerrado".to_string(),
+            ));
+        }
+
+        let task = self
+            .task_collection()
+            .find_one_and_update(
+                doc! {"_id": task_id, "sprint_id": sprint_id},
+                doc! {
+                    "$unset": {"sprint_id": ""},
+                    "$set": {"updated_at": Utc::now()},
+                    "$inc": {"version": 1},
+                },
+            )
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .ok_or_else(|| AppError::NotFound("La tarea no pertenece a este sprint".to_string()))?;
+        self.record_sprint_changes(&[task], None, user_id).await;
+
+        self.broadcast("SPRINT_TASKS_CHANGED", &sprint);
+
+        Ok(())
+    }
+
+    pub async fn get_sprint_tasks(
+        &self,
+        sprint_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<Vec<Task>, AppError> {
+        self.find_sprint(sprint_id, user_id).await?;
+
+        self.task_collection()
+            .find(doc! {"sprint_id": sprint_id})
+            .sort(doc! {"rank": 1, "created_at": 1})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    // //* Backlog del proyecto: tareas fuera del sprint activo. Las tareas de sprints
+    // //* cerrados tampoco se incluyen, ya que al cerrar solo quedan las completadas.
+    pub async fn get_backlog(
+        &self,
+        project_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<Vec<Task>, AppError> {
+        PermissionService::new(self.db_state.get_db())
+            .can_access_project(project_id, user_id)
+            .await?;
+
+        let excluded_sprints: Vec<Bson> = self
+            .sprint_collection()
+            .find(doc! {
+                "project_id": project_id,
+                "state": {"$in": [
+                    to_bson(&SprintState::Active).unwrap(),
+                    to_bson(&SprintState::Closed).unwrap(),
+                ]},
+            })
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .try_collect::<Vec<Sprint>>()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .into_iter()
+            .filter_map(|sprint| sprint.id.map(Bson::ObjectId))
+            .collect();
+
+        self.task_collection()
+            .find(doc! {"project_id": project_id, "sprint_id": {"$nin": excluded_sprints}})
+            .sort(doc! {"rank": 1, "created_at": 1})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+}
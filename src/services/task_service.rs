@@ -1,11 +1,12 @@
 use bson::to_bson;
 use chrono::Utc;
-use futures::StreamExt;
+use futures::TryStreamExt;
 use mongodb::{
-    Collection,
-    bson::{DateTime, doc, oid::ObjectId},
+    ClientSession, Collection,
+    bson::{Bson, DateTime, Document, doc, oid::ObjectId},
+    error::TRANSIENT_TRANSACTION_ERROR,
 };
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 use tokio::sync::broadcast;
 use validator::Validate;
 
@@ -13,10 +14,25 @@ use crate::{
     db::DatabaseState,
     errors::AppError,
     models::{
+        custom_field_model::{CustomFieldDefinition, CustomFieldType, parse_date},
+        history_model::{HistoryAction, TaskHistoryEntry},
         project_models::Project,
-        task_model::{CreateTaskSchema, Task, TaskPriority, TaskStatus, UpdateTaskSchema},
+        reaction_model::Reactions,
+        task_model::{
+            CreateTaskSchema, MoveTaskSchema, Task, TaskListQuery, TaskPage, TaskPriority,
+            TaskStatus, UpdateTaskSchema, normalize_labels,
+        },
     },
-    services::permission_service::PermissionService,
+    services::{
+        board_service::BoardService,
+        custom_field_service::CustomFieldService,
+        history_service::{HistoryService, history_value},
+        notification_service::NotificationService,
+        permission_service::PermissionService,
+        project_service::ProjectService,
+        rank_service::RankService,
+    },
+    utils::{cursor, markdown::render_markdown},
 };
 
 pub struct TaskService {
@@ -37,6 +53,70 @@ impl TaskService {
         self.db_state.get_db().collection::<Project>("projects")
     }
 
+    // //* HTML saneado de una descripción, con las menciones a miembros del proyecto enlazadas
+    async fn render_description(
+        &self,
+        project_id: ObjectId,
+        user_id: ObjectId,
+        description: &str,
+    ) -> Result<String, AppError> {
+        let members = ProjectService::new(self.db_state.clone(), self.ws_tx.clone())
+            .mentioned_members(project_id, user_id, description)
+            .await?;
+        Ok(render_markdown(description, &members))
+    }
+
+    // //* Validar que la épica indicada es otra tarea del mismo proyecto
+    async fn resolve_epic(
+        &self,
+        project_id: ObjectId,
+        task_id: Option<ObjectId>,
+        epic_id: &str,
+    ) -> Result<ObjectId, AppError> {
+        let epic_id = ObjectId::parse_str(epic_id)
+            .map_err(|_| AppError::ValidationError("El ID de la épica no es válido".to_string()))?;
+        if Some(epic_id) == task_id {
+            return Err(AppError::ValidationError(
+                "Una tarea no puede ser su propia épica".to_string(),
+            ));
+        }
+
+        self.task_collection()
+            .find_one(doc! {"_id": epic_id, "project_id": project_id})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .ok_or_else(|| {
+                AppError::ValidationError("La épica no existe en este proyecto".to_string())
+            })?;
+
+        Ok(epic_id)
+    }
+
+    // //* Reservar la siguiente clave `PROYECTO-n`. El contador se incrementa de forma
+    // //* atómica para que dos tareas creadas a la vez no compartan número.
+    async fn next_task_key(
+        &self,
+        project_id: ObjectId,
+        session: Option<&mut ClientSession>,
+    ) -> Result<String, AppError> {
+        let projects = self.projects_collection();
+        let update = projects
+            .find_one_and_update(doc! {"_id": project_id}, doc! {"$inc": {"task_counter": 1}})
+            .with_options(
+                mongodb::options::FindOneAndUpdateOptions::builder()
+                    .return_document(mongodb::options::ReturnDocument::After)
+                    .build(),
+            );
+        let project = match session {
+            Some(session) => update.session(session).await,
+            None => update.await,
+        }
+        .map_err(|_| AppError::InternalServerError)?
+        .ok_or_else(|| AppError::NotFound("Proyecto no encontrado".to_string()))?;
+
+        Ok(format!("{}-{}", project.project_key, project.task_counter))
+    }
+
     // //* Create a new task
     // //* Creates a new task in a project, ensuring the user has permission to do so.
     pub async fn create_task(
@@ -51,10 +131,18 @@ impl TaskService {
 
         // Validar fechas
 
-        PermissionService::new(self.db_state.get_db())
+        let project = PermissionService::new(self.db_state.get_db())
             .can_access_project(project_id, reporter_id)
             .await?;
 
+        let custom_fields = CustomFieldService::new(self.db_state.clone())
+            .validate_values(&project, &schema.custom_fields.unwrap_or_default(), true)
+            .await?
+            .set;
+
+        let labels = normalize_labels(&schema.labels.unwrap_or_default())
+            .map_err(AppError::ValidationError)?;
+
         let assignee_id = schema
             .assignee_id
             .map(|id_str| ObjectId::parse_str(&id_str))
@@ -63,20 +151,75 @@ impl TaskService {
                 AppError::ValidationError("El ID del asignado no es válido".to_string())
             })?;
 
+        let epic_id = match schema.epic_id {
+            Some(epic_id) => Some(self.resolve_epic(project_id, None, &epic_id).await?),
+            None => None,
+        };
+
+        // Las tareas nuevas entran en la columna de su estado inicial
+        let status = schema.status.unwrap_or(TaskStatus::ToDo);
+        let wip_warning = BoardService::new(self.db_state.clone(), self.ws_tx.clone())
+            .check_wip_limit(project_id, None, None, &status)
+            .await?;
+
         // Usar fechas proporcionadas o generar automáticamente si no se proporcionan
         let now = Utc::now();
         let created_at = schema.created_at.unwrap_or(now);
         let updated_at = schema.updated_at.unwrap_or(now);
 
+        // Si no se indica la estimación restante, parte de la original
+        let remaining_estimate_minutes = schema
+            .remaining_estimate_minutes
+            .or(schema.original_estimate_minutes);
+
+        // Las tareas nuevas se colocan al final de la lista del proyecto
+        let rank = RankService::new(self.db_state.clone(), self.ws_tx.clone())
+            .next_rank_for_project(project_id)
+            .await?;
+
+        let description_html = match &schema.description {
+            Some(description) => Some(
+                self.render_description(project_id, reporter_id, description)
+                    .await?,
+            ),
+            None => None,
+        };
+
+        let key = self.next_task_key(project_id, None).await?;
+
+        // El informador y el asignado observan la tarea desde el principio
+        let mut watchers = vec![reporter_id];
+        if let Some(assignee_id) = assignee_id
+            && assignee_id != reporter_id
+        {
+            watchers.push(assignee_id);
+        }
+
         let mut new_task = Task {
             id: None,
             project_id,
+            key: Some(key),
+            key_aliases: Vec::new(),
             title: schema.title,
             description: schema.description,
-            status: schema.status.unwrap_or(TaskStatus::ToDo),
+            description_html,
+            status,
             priority: schema.priority.unwrap_or(TaskPriority::Medium),
             assignee_id,
             reporter_id,
+            original_estimate_minutes: schema.original_estimate_minutes,
+            remaining_estimate_minutes,
+            story_points: schema.story_points,
+            sprint_id: None,
+            epic_id,
+            rank,
+            custom_fields,
+            labels,
+            watchers,
+            reactions: Reactions::new(),
+            comment_count: None,
+            wip_warning: None,
+            version: 0,
             created_at,
             updated_at,
         };
@@ -97,6 +240,7 @@ impl TaskService {
         let broadcast_message = serde_json::json!({
             "event_type": "TASK_CREATED",
             "task": new_task,
+            "wip_warning": wip_warning,
         })
         .to_string();
 
@@ -109,39 +253,423 @@ impl TaskService {
             );
         }
 
-        Ok(new_task)
+        HistoryService::new(self.db_state.clone())
+            .record(vec![TaskHistoryEntry::new(
+                new_task.id.unwrap(),
+                project_id,
+                reporter_id,
+                HistoryAction::Created,
+            )])
+            .await;
+
+        NotificationService::new(self.db_state.clone(), self.ws_tx.clone())
+            .notify(NotificationService::task_notifications(
+                None,
+                &new_task,
+                reporter_id,
+            ))
+            .await;
+
+        Ok(Task {
+            wip_warning,
+            ..new_task
+        })
     }
 
     // //* Get all tasks for a project
-    // //* Retrieves all tasks associated with a project, ensuring the user has permission to access the project.
+    // //* Retrieves the tasks of a project filtered, sorted and paginated with an opaque cursor,
+    // //* ensuring the user has permission to access the project.
     pub async fn get_task_for_project(
         &self,
         project_id: ObjectId,
         user_id: ObjectId,
-    ) -> Result<Vec<Task>, AppError> {
-        // Verificacion de permisos
+        query: TaskListQuery,
+        custom_filters: HashMap<String, String>,
+    ) -> Result<TaskPage, AppError> {
+        query
+            .validate()
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
 
+        // Verificacion de permisos
         PermissionService::new(self.db_state.get_db())
             .can_access_project(project_id, user_id)
             .await?;
 
-        // Buscar todas las tareas que coincidan con el project_id
-        let filter = doc! {"project_id": project_id};
-        let mut cursor = self
+        let definitions = CustomFieldService::new(self.db_state.clone())
+            .definitions_for_project(project_id)
+            .await?;
+        let find_field = |key: &str| {
+            definitions
+                .iter()
+                .find(|field| field.key == key)
+                .ok_or_else(|| {
+                    AppError::ValidationError(format!("Campo personalizado desconocido: {}", key))
+                })
+        };
+
+        let mut filter = Self::task_list_filter(project_id, user_id, &query)?;
+        for (key, raw) in &custom_filters {
+            let field = find_field(key)?;
+            filter.insert(
+                format!("custom_fields.{}", field.key),
+                Self::custom_field_condition(field, raw, user_id)?,
+            );
+        }
+        let total = self
             .task_collection()
-            .find(filter)
+            .count_documents(filter.clone())
             .await
-            .map_err(|_| AppError::InternalServerError)?;
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
 
-        let mut tasks = Vec::new();
-        while let Some(result) = cursor.next().await {
-            if let Ok(task) = result {
-                tasks.push(task);
+        // Los campos simples se ordenan directamente (usan los índices compuestos);
+        // prioridad, estado y título necesitan una clave calculada
+        let sort = query.sort.as_deref().unwrap_or("rank");
+        let (sort_name, direction) = match sort.strip_prefix('-') {
+            Some(name) => (name, -1),
+            None => (sort, 1),
+        };
+        let (sort_field, sort_key): (&str, Option<Bson>) = match sort_name {
+            "rank" | "created_at" | "updated_at" => (sort_name, None),
+            "priority" => (
+                "_sort_key",
+                Some(Bson::Document(doc! {
+                    "$indexOfArray": [["Low", "Medium", "High", "Urgent"], "$priority"]
+                })),
+            ),
+            "status" => (
+                "_sort_key",
+                Some(Bson::Document(doc! {
+                    "$indexOfArray": [["ToDo", "InProgress", "Done", "Cancelled"], "$status"]
+                })),
+            ),
+            "title" => (
+                "_sort_key",
+                Some(Bson::Document(doc! {"$toLower": "$title"})),
+            ),
+            // Los campos personalizados vacíos quedan como null (primeros en orden ascendente)
+            name if name.starts_with("cf.") => {
+                let field = find_field(&name[3..])?;
+                let path = format!("$custom_fields.{}", field.key);
+                let value = match field.field_type {
+                    CustomFieldType::Text => Bson::Document(doc! {"$toLower": &path}),
+                    CustomFieldType::MultiSelect => {
+                        Bson::Document(doc! {"$arrayElemAt": [&path, 0]})
+                    }
+                    _ => Bson::String(path),
+                };
+                (
+                    "_sort_key",
+                    Some(Bson::Document(doc! {"$ifNull": [value, Bson::Null]})),
+                )
+            }
+            _ => {
+                return Err(AppError::ValidationError(format!(
+                    "Campo de orden no soportado: {}",
+                    sort_name
+                )));
+            }
+        };
+
+        let mut pipeline = vec![doc! {"$match": filter}];
+        if let Some(sort_key) = &sort_key {
+            pipeline.push(doc! {"$addFields": {"_sort_key": sort_key}});
+        }
+
+        // El cursor guarda el valor de orden y el _id del último elemento entregado
+        if let Some(cursor) = &query.cursor {
+            let cursor = cursor::decode(cursor)?;
+            if cursor.get_str("sort").ok() != Some(sort) {
+                return Err(AppError::ValidationError(
+                    "El cursor no corresponde al orden solicitado".to_string(),
+                ));
+            }
+            let value = cursor.get("value").cloned().unwrap_or(Bson::Null);
+            let last_id = cursor.get_object_id("id").map_err(|_| {
+                AppError::ValidationError("Cursor de paginación inválido".to_string())
+            })?;
+            let op = if direction == 1 { "$gt" } else { "$lt" };
+            if sort_key.is_some() {
+                // Las claves calculadas se comparan con $expr, que ordena valores de
+                // distinto tipo (p. ej. null) igual que $sort
+                let key = format!("${}", sort_field);
+                pipeline.push(doc! {"$match": {"$expr": {"$or": [
+                    {op: [&key, value.clone()]},
+                    {"$and": [{"$eq": [&key, value]}, {op: ["$_id", last_id]}]},
+                ]}}});
+            } else {
+                pipeline.push(doc! {"$match": {"$or": [
+                    {sort_field: {op: value.clone()}},
+                    {sort_field: value, "_id": {op: last_id}},
+                ]}});
             }
         }
+
+        let limit = query.limit.unwrap_or(50) as i64;
+        pipeline.push(doc! {"$sort": {sort_field: direction, "_id": direction}});
+        pipeline.push(doc! {"$limit": limit + 1});
+
+        let mut documents: Vec<Document> = self
+            .task_collection()
+            .aggregate(pipeline)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let has_more = documents.len() as i64 > limit;
+        documents.truncate(limit as usize);
+
+        let next_cursor = match documents.last() {
+            Some(last) if has_more => Some(cursor::encode(&doc! {
+                "sort": sort,
+                "value": last.get(sort_field).cloned().unwrap_or(Bson::Null),
+                "id": last.get_object_id("_id").map_err(|_| AppError::InternalServerError)?,
+            })),
+            _ => None,
+        };
+
+        // Un documento que no se puede leer es un error, no se descarta en silencio
+        let items = documents
+            .into_iter()
+            .map(|mut document| {
+                document.remove("_sort_key");
+                bson::from_document::<Task>(document).map_err(|e| {
+                    tracing::error!(
+                        "Tarea con formato inválido en el proyecto {}: {}",
+                        project_id,
+                        e
+                    );
+                    AppError::DatabaseError(e.to_string())
+                })
+            })
+            .collect::<Result<Vec<Task>, AppError>>()?;
+        let items = self.with_comment_counts(items).await?;
+
+        Ok(TaskPage {
+            items,
+            total,
+            next_cursor,
+        })
+    }
+
+    // //* Número de comentarios de cada tarea (sin lápidas), con una sola agregación por página
+    async fn with_comment_counts(&self, mut tasks: Vec<Task>) -> Result<Vec<Task>, AppError> {
+        let task_ids: Vec<ObjectId> = tasks.iter().filter_map(|task| task.id).collect();
+        let counts: Vec<Document> = self
+            .db_state
+            .get_db()
+            .collection::<Document>("comments")
+            .aggregate(vec![
+                doc! {"$match": {"task_id": {"$in": &task_ids}, "deleted": {"$ne": true}}},
+                doc! {"$group": {"_id": "$task_id", "count": {"$sum": 1}}},
+            ])
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let counts: HashMap<ObjectId, u64> = counts
+            .iter()
+            .filter_map(|count| {
+                Some((
+                    count.get_object_id("_id").ok()?,
+                    count.get_i32("count").ok()? as u64,
+                ))
+            })
+            .collect();
+        for task in tasks.iter_mut() {
+            task.comment_count = Some(task.id.and_then(|id| counts.get(&id).copied()).unwrap_or(0));
+        }
         Ok(tasks)
     }
 
+    // //* Traducir los parámetros de consulta a un filtro de MongoDB
+    fn task_list_filter(
+        project_id: ObjectId,
+        user_id: ObjectId,
+        query: &TaskListQuery,
+    ) -> Result<Document, AppError> {
+        let mut filter = doc! {"project_id": project_id};
+
+        if let Some(status) = &query.status {
+            let statuses = Self::parse_enum_list::<TaskStatus>(status, "Estado")?;
+            filter.insert("status", doc! {"$in": statuses});
+        }
+        if let Some(priority) = &query.priority {
+            let priorities = Self::parse_enum_list::<TaskPriority>(priority, "Prioridad")?;
+            filter.insert("priority", doc! {"$in": priorities});
+        }
+        if let Some(assignee) = &query.assignee {
+            filter.insert(
+                "assignee_id",
+                doc! {"$in": Self::parse_user_list(assignee, user_id, true)?},
+            );
+        }
+        if let Some(reporter) = &query.reporter {
+            filter.insert(
+                "reporter_id",
+                doc! {"$in": Self::parse_user_list(reporter, user_id, false)?},
+            );
+        }
+
+        for (field, from, to) in [
+            ("created_at", query.created_from, query.created_to),
+            ("updated_at", query.updated_from, query.updated_to),
+        ] {
+            let mut range = doc! {};
+            if let Some(from) = from {
+                range.insert("$gte", DateTime::from_chrono(from));
+            }
+            if let Some(to) = to {
+                range.insert("$lte", DateTime::from_chrono(to));
+            }
+            if !range.is_empty() {
+                filter.insert(field, range);
+            }
+        }
+
+        if let Some(labels) = &query.labels {
+            let labels: Vec<&str> = labels
+                .split(',')
+                .map(str::trim)
+                .filter(|label| !label.is_empty())
+                .collect();
+            filter.insert("labels", doc! {"$in": labels});
+        }
+
+        if let Some(text) = query
+            .q
+            .as_deref()
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+        {
+            filter.insert(
+                "title",
+                doc! {"$regex": regex::escape(text), "$options": "i"},
+            );
+        }
+
+        Ok(filter)
+    }
+
+    fn parse_enum_list<T: serde::de::DeserializeOwned + serde::Serialize>(
+        values: &str,
+        label: &str,
+    ) -> Result<Vec<Bson>, AppError> {
+        values
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| {
+                let parsed: T = serde_json::from_value(serde_json::Value::String(
+                    value.to_string(),
+                ))
+                .map_err(|_| AppError::ValidationError(format!("{} inválido: {}", label, value)))?;
+                to_bson(&parsed).map_err(|_| AppError::InternalServerError)
+            })
+            .collect()
+    }
+
+    fn parse_user_list(
+        values: &str,
+        user_id: ObjectId,
+        allow_unassigned: bool,
+    ) -> Result<Vec<Bson>, AppError> {
+        values
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| match value {
+                "me" => Ok(Bson::ObjectId(user_id)),
+                // null también coincide con los documentos sin el campo
+                "unassigned" if allow_unassigned => Ok(Bson::Null),
+                _ => ObjectId::parse_str(value).map(Bson::ObjectId).map_err(|_| {
+                    AppError::ValidationError(format!("ID de usuario inválido: {}", value))
+                }),
+            })
+            .collect()
+    }
+
+    // //* Condición de filtro para `cf.<clave>=valor` según el tipo del campo:
+    // //* texto contiene, número y fecha admiten rangos `desde..hasta`,
+    // //* selección y usuario admiten listas separadas por comas (`me` para el usuario actual)
+    fn custom_field_condition(
+        field: &CustomFieldDefinition,
+        raw: &str,
+        user_id: ObjectId,
+    ) -> Result<Bson, AppError> {
+        let raw = raw.trim();
+        let invalid = || {
+            AppError::ValidationError(format!(
+                "Filtro inválido para el campo '{}': {}",
+                field.key, raw
+            ))
+        };
+        let list = || {
+            raw.split(',')
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        };
+
+        let condition = match field.field_type {
+            CustomFieldType::Text => {
+                doc! {"$regex": regex::escape(raw), "$options": "i"}
+            }
+            CustomFieldType::Number | CustomFieldType::Date => {
+                let parse = |value: &str| -> Result<Bson, AppError> {
+                    match field.field_type {
+                        CustomFieldType::Number => value
+                            .parse::<f64>()
+                            .map(Bson::Double)
+                            .map_err(|_| invalid()),
+                        _ => parse_date(value).map(Bson::String).ok_or_else(invalid),
+                    }
+                };
+                match raw.split_once("..") {
+                    Some((from, to)) => {
+                        let mut range = doc! {};
+                        if !from.trim().is_empty() {
+                            range.insert("$gte", parse(from.trim())?);
+                        }
+                        if !to.trim().is_empty() {
+                            range.insert("$lte", parse(to.trim())?);
+                        }
+                        if range.is_empty() {
+                            return Err(invalid());
+                        }
+                        range
+                    }
+                    None => doc! {"$eq": parse(raw)?},
+                }
+            }
+            CustomFieldType::SingleSelect | CustomFieldType::MultiSelect => {
+                let options = list()
+                    .map(|option| {
+                        field
+                            .select_option(option)
+                            .map(Bson::String)
+                            .map_err(AppError::ValidationError)
+                    })
+                    .collect::<Result<Vec<Bson>, AppError>>()?;
+                doc! {"$in": options}
+            }
+            CustomFieldType::User => {
+                let users = list()
+                    .map(|value| match value {
+                        "me" => Ok(Bson::ObjectId(user_id)),
+                        _ => ObjectId::parse_str(value)
+                            .map(Bson::ObjectId)
+                            .map_err(|_| invalid()),
+                    })
+                    .collect::<Result<Vec<Bson>, AppError>>()?;
+                doc! {"$in": users}
+            }
+        };
+        Ok(Bson::Document(condition))
+    }
+
     // //* Get a task by ID
     // //* Retrieves a task by its ID, ensuring the user has permission to access it.
     pub async fn get_task_by_id(
@@ -164,6 +692,24 @@ impl TaskService {
         Ok(task)
     }
 
+    // //* Get a task by key
+    // //* Resolves a task by its current key or by any key it had before being moved.
+    pub async fn get_task_by_key(&self, key: &str, user_id: ObjectId) -> Result<Task, AppError> {
+        let key = key.trim().to_uppercase();
+        let task = self
+            .task_collection()
+            .find_one(doc! {"$or": [{"key": &key}, {"key_aliases": &key}]})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .ok_or_else(|| AppError::NotFound("Tarea no encontrada".to_string()))?;
+
+        PermissionService::new(self.db_state.get_db())
+            .can_access_project(task.project_id, user_id)
+            .await?;
+
+        Ok(task)
+    }
+
     // //* Update a task
     // //* Updates a task by its ID, ensuring the user has permission to update it.
     pub async fn update_task(
@@ -171,6 +717,7 @@ impl TaskService {
         task_id: ObjectId,
         user_id: ObjectId,
         schema: UpdateTaskSchema,
+        expected_version: Option<i64>,
     ) -> Result<Task, AppError> {
         schema
             .validate()
@@ -187,19 +734,32 @@ impl TaskService {
         // Validar fechas con el contexto de la tarea actual
 
         // Verificar si el usuario puede acceder al proyecto (es dueño o miembro)
-        PermissionService::new(self.db_state.get_db())
+        let project = PermissionService::new(self.db_state.get_db())
             .can_access_project(task.project_id, user_id)
             .await?;
 
         // No es necesario verificar si es dueño o asignado específicamente
         // Cualquier miembro del proyecto puede actualizar tareas
 
+        // Si el cliente envió If-Match, su versión debe ser la actual
+        if let Some(expected) = expected_version
+            && expected != task.version
+        {
+            return Err(AppError::precondition_failed(&task));
+        }
+
         // Capturar información sobre los cambios antes de mover los valores
         let title_changed = schema.title.is_some();
         let description_changed = schema.description.is_some();
         let status_changed = schema.status.is_some();
         let priority_changed = schema.priority.is_some();
         let assignee_changed = schema.assignee_id.is_some();
+        let epic_changed = schema.epic_id.is_some();
+        let custom_fields_changed = schema.custom_fields.is_some();
+        let labels_changed = schema.labels.is_some();
+        let estimates_changed = schema.original_estimate_minutes.is_some()
+            || schema.remaining_estimate_minutes.is_some()
+            || schema.story_points.is_some();
 
         let previous_status = if status_changed {
             Some(task.status.clone())
@@ -207,13 +767,28 @@ impl TaskService {
             None
         };
 
+        // Comprobar el límite WIP de la columna destino antes de aplicar el cambio
+        let wip_warning = match &schema.status {
+            Some(status) if *status != task.status => {
+                BoardService::new(self.db_state.clone(), self.ws_tx.clone())
+                    .check_wip_limit(task.project_id, task.sprint_id, Some(&task.status), status)
+                    .await?
+            }
+            _ => None,
+        };
+
         let mut update_doc = doc! {};
+        let mut new_assignee = None;
 
         if let Some(title) = schema.title {
             update_doc.insert("title", title);
         }
         if let Some(description) = schema.description {
+            let description_html = self
+                .render_description(task.project_id, user_id, &description)
+                .await?;
             update_doc.insert("description", description);
+            update_doc.insert("description_html", description_html);
         }
         if let Some(status) = schema.status {
             update_doc.insert("status", to_bson(&status).unwrap());
@@ -228,10 +803,48 @@ impl TaskService {
                 })?),
                 None => None,
             };
+            new_assignee = assignee_id;
             update_doc.insert("assignee_id", assignee_id);
         }
+        if let Some(original_estimate) = schema.original_estimate_minutes {
+            update_doc.insert("original_estimate_minutes", original_estimate);
+        }
+        if let Some(remaining_estimate) = schema.remaining_estimate_minutes {
+            update_doc.insert("remaining_estimate_minutes", remaining_estimate);
+        }
+        if let Some(story_points) = schema.story_points {
+            update_doc.insert("story_points", story_points);
+        }
+        if let Some(epic_opt) = schema.epic_id {
+            let epic_id = match epic_opt {
+                Some(epic_id) => Some(
+                    self.resolve_epic(task.project_id, Some(task_id), &epic_id)
+                        .await?,
+                ),
+                None => None,
+            };
+            update_doc.insert("epic_id", epic_id);
+        }
 
-        if update_doc.is_empty() {
+        if let Some(labels) = &schema.labels {
+            let labels = normalize_labels(labels).map_err(AppError::ValidationError)?;
+            update_doc.insert("labels", labels);
+        }
+
+        let mut unset_doc = doc! {};
+        if let Some(values) = &schema.custom_fields {
+            let changes = CustomFieldService::new(self.db_state.clone())
+                .validate_values(&project, values, false)
+                .await?;
+            for (key, value) in changes.set {
+                update_doc.insert(format!("custom_fields.{}", key), value);
+            }
+            for key in changes.unset {
+                unset_doc.insert(format!("custom_fields.{}", key), "");
+            }
+        }
+
+        if update_doc.is_empty() && unset_doc.is_empty() {
             return Ok(task);
         }
 
@@ -239,13 +852,45 @@ impl TaskService {
         let updated_at = schema.updated_at.unwrap_or_else(Utc::now);
         update_doc.insert("updated_at", DateTime::from_chrono(updated_at));
 
-        self.task_collection()
-            .update_one(doc! {"_id": task_id}, doc! {"$set": update_doc})
+        // La versión leída se exige también en el filtro para que dos ediciones
+        // simultáneas con el mismo If-Match no se pisen
+        let mut filter = doc! {"_id": task_id};
+        if expected_version.is_some() {
+            filter.insert("version", task.version);
+        }
+        let mut update = doc! {"$set": update_doc, "$inc": {"version": 1}};
+        if !unset_doc.is_empty() {
+            update.insert("$unset", unset_doc);
+        }
+        // El nuevo asignado pasa a observar la tarea
+        if let Some(assignee_id) = new_assignee {
+            update.insert("$addToSet", doc! {"watchers": assignee_id});
+        }
+        let result = self
+            .task_collection()
+            .update_one(filter, update)
             .await
             .map_err(|_| AppError::InternalServerError)?;
 
+        if result.matched_count == 0 {
+            let current = self.get_task_by_id(task_id, user_id).await?;
+            return Err(AppError::precondition_failed(&current));
+        }
+
         let updated_task = self.get_task_by_id(task_id, user_id).await?;
 
+        HistoryService::new(self.db_state.clone())
+            .record(HistoryService::task_changes(&task, &updated_task, user_id))
+            .await;
+
+        NotificationService::new(self.db_state.clone(), self.ws_tx.clone())
+            .notify(NotificationService::task_notifications(
+                Some(&task),
+                &updated_task,
+                user_id,
+            ))
+            .await;
+
         // nueva logica de broadcast con información detallada de cambios
         let broadcast_message = serde_json::json!({
             "event_type": "TASK_UPDATED",
@@ -259,8 +904,13 @@ impl TaskService {
                     "status": status_changed,
                     "priority": priority_changed,
                     "assignee_id": assignee_changed,
+                    "estimates": estimates_changed,
+                    "epic_id": epic_changed,
+                    "custom_fields": custom_fields_changed,
+                    "labels": labels_changed,
                 }
-            }
+            },
+            "wip_warning": wip_warning,
         })
         .to_string();
 
@@ -277,7 +927,409 @@ impl TaskService {
             );
         }
 
-        Ok(updated_task)
+        Ok(Task {
+            wip_warning,
+            ..updated_task
+        })
+    }
+
+    // //* Move a task to another project
+    // //* The user must be able to access both projects. The task gets a new key (the old one
+    // //* stays as an alias), a status that exists in the target board and loses whatever only
+    // //* made sense in the source project: sprint, epic, assignee, watchers without access
+    // //* and custom field values.
+    pub async fn move_task(
+        &self,
+        task_id: ObjectId,
+        user_id: ObjectId,
+        schema: MoveTaskSchema,
+        expected_version: Option<i64>,
+    ) -> Result<Task, AppError> {
+        let target_id = ObjectId::parse_str(&schema.project_id).map_err(|_| {
+            AppError::ValidationError("El ID del proyecto destino no es válido".to_string())
+        })?;
+
+        let task = self
+            .task_collection()
+            .find_one(doc! {"_id": task_id})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .ok_or_else(|| AppError::NotFound("Tarea no encontrada".to_string()))?;
+        let source_id = task.project_id;
+
+        let permissions = PermissionService::new(self.db_state.get_db());
+        permissions.can_access_project(source_id, user_id).await?;
+        let target = permissions.can_access_project(target_id, user_id).await?;
+
+        if target_id == source_id {
+            return Err(AppError::ValidationError(
+                "La tarea ya pertenece a ese proyecto".to_string(),
+            ));
+        }
+        if let Some(expected) = expected_version
+            && expected != task.version
+        {
+            return Err(AppError::precondition_failed(&task));
+        }
+
+        // El estado se adapta a las columnas del tablero destino
+        let boards = BoardService::new(self.db_state.clone(), self.ws_tx.clone());
+        let target_board = boards.load_board(target_id).await?;
+        let status = match schema.status {
+            Some(status) if target_board.column_for_status(&status).is_none() => {
+                return Err(AppError::ValidationError(
+                    "El estado no tiene columna en el tablero del proyecto destino".to_string(),
+                ));
+            }
+            Some(status) => status,
+            None => {
+                let source_board = boards.load_board(source_id).await?;
+                target_board.remap_status(&source_board, &task.status)
+            }
+        };
+        let wip_warning = boards
+            .check_wip_limit(target_id, None, None, &status)
+            .await?;
+
+        let assignee_id = task
+            .assignee_id
+            .filter(|id| target.owner_id == *id || target.members.contains(id));
+        let custom_fields = self.carry_custom_fields(&task, &target).await?;
+        let watchers: Vec<ObjectId> = task
+            .watchers
+            .iter()
+            .copied()
+            .filter(|id| target.owner_id == *id || target.members.contains(id))
+            .collect();
+
+        let mut key_aliases = task.key_aliases.clone();
+        if let Some(key) = &task.key
+            && !key_aliases.contains(key)
+        {
+            key_aliases.push(key.clone());
+        }
+        let rank = RankService::new(self.db_state.clone(), self.ws_tx.clone())
+            .next_rank_for_project(target_id)
+            .await?;
+
+        // El filtro incluye el proyecto de origen para que dos movimientos simultáneos
+        // no se apliquen ambos
+        let mut filter = doc! {"_id": task_id, "project_id": source_id};
+        if expected_version.is_some() {
+            filter.insert("version", task.version);
+        }
+        let fields = doc! {
+            "project_id": target_id,
+            "key_aliases": &key_aliases,
+            "status": to_bson(&status).unwrap(),
+            "assignee_id": assignee_id,
+            "custom_fields": custom_fields,
+            "watchers": watchers,
+            "rank": rank,
+            "updated_at": DateTime::from_chrono(Utc::now()),
+        };
+        let moved = if self.db_state.supports_transactions {
+            self.move_in_transaction(&task, target_id, filter, fields)
+                .await?
+        } else {
+            self.move_with_rollback(&task, target_id, filter, fields)
+                .await?
+        };
+
+        if !moved {
+            let current = self.get_task_by_id(task_id, user_id).await?;
+            return Err(AppError::precondition_failed(&current));
+        }
+
+        let moved_task = self.get_task_by_id(task_id, user_id).await?;
+
+        HistoryService::new(self.db_state.clone())
+            .record(HistoryService::task_changes(&task, &moved_task, user_id))
+            .await;
+
+        let broadcast_message = serde_json::json!({
+            "event_type": "TASK_MOVED",
+            "task": moved_task,
+            "from_project_id": source_id.to_hex(),
+            "previous_key": task.key,
+            "wip_warning": wip_warning,
+        })
+        .to_string();
+
+        if let Err(e) = self.ws_tx.send(broadcast_message) {
+            tracing::warn!("Error enviando mensaje WebSocket para tarea movida: {}", e);
+        }
+
+        Ok(Task {
+            wip_warning,
+            ..moved_task
+        })
+    }
+
+    // //* Escrituras de un movimiento entre proyectos: la clave nueva, la tarea, sus adjuntos y
+    // //* registros de trabajo, y las dependencias y épicas que no pueden cruzar proyectos.
+    // //* Devuelve false, sin tocar nada más, si la tarea ya no coincide con el filtro.
+    async fn write_move(
+        &self,
+        task: &Task,
+        target_id: ObjectId,
+        filter: Document,
+        mut fields: Document,
+        mut session: Option<&mut ClientSession>,
+    ) -> Result<bool, AppError> {
+        let task_id = task.id.ok_or(AppError::InternalServerError)?;
+        let key = self
+            .next_task_key(target_id, session.as_deref_mut())
+            .await?;
+        fields.insert("key", key);
+
+        let tasks = self.task_collection();
+        let update = tasks.update_one(
+            filter,
+            doc! {
+                "$set": fields,
+                "$unset": {"sprint_id": "", "epic_id": ""},
+                "$inc": {"version": 1},
+            },
+        );
+        let result = match session.as_deref_mut() {
+            Some(session) => update.session(session).await,
+            None => update.await,
+        };
+        match result {
+            Ok(result) if result.matched_count == 0 => return Ok(false),
+            Ok(_) => {}
+            // //? Otra transacción ha escrito la misma tarea: es un movimiento concurrente
+            Err(e) if e.contains_label(TRANSIENT_TRANSACTION_ERROR) => return Ok(false),
+            Err(e) => return Err(AppError::DatabaseError(e.to_string())),
+        }
+
+        // Comentarios y rangos de fechas solo guardan la tarea, así que la acompañan solos;
+        // adjuntos y registros de trabajo también guardan el proyecto. Las dependencias no
+        // pueden cruzar proyectos y se pierden.
+        let db = self.db_state.get_db();
+        for name in ["images", "worklogs"] {
+            let collection = db.collection::<Document>(name);
+            let update = collection.update_many(
+                doc! {"task_id": task_id},
+                doc! {"$set": {"project_id": target_id}},
+            );
+            match session.as_deref_mut() {
+                Some(session) => update.session(session).await,
+                None => update.await,
+            }
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        }
+
+        let dependencies = db.collection::<Document>("task_dependencies");
+        let delete = dependencies
+            .delete_many(doc! {"$or": [{"predecessor_id": task_id}, {"successor_id": task_id}]});
+        match session.as_deref_mut() {
+            Some(session) => delete.session(session).await,
+            None => delete.await,
+        }
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        // Las tareas del proyecto de origen no pueden tener una épica de otro proyecto
+        let update = tasks.update_many(
+            doc! {"project_id": task.project_id, "epic_id": task_id},
+            doc! {"$unset": {"epic_id": ""}, "$inc": {"version": 1}},
+        );
+        match session {
+            Some(session) => update.session(session).await,
+            None => update.await,
+        }
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(true)
+    }
+
+    async fn move_in_transaction(
+        &self,
+        task: &Task,
+        target_id: ObjectId,
+        filter: Document,
+        fields: Document,
+    ) -> Result<bool, AppError> {
+        let mut session = self
+            .db_state
+            .client
+            .start_session()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        session
+            .start_transaction()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let result = self
+            .write_move(task, target_id, filter, fields, Some(&mut session))
+            .await;
+        if let Ok(true) = result {
+            return match session.commit_transaction().await {
+                Ok(()) => Ok(true),
+                Err(e) if e.contains_label(TRANSIENT_TRANSACTION_ERROR) => Ok(false),
+                Err(e) => Err(AppError::DatabaseError(e.to_string())),
+            };
+        }
+
+        if let Err(e) = session.abort_transaction().await {
+            tracing::warn!("Error abortando la transacción del movimiento: {}", e);
+        }
+        result
+    }
+
+    // //! Sin transacciones se guarda lo que el movimiento va a borrar y, ante cualquier error,
+    // //! se deshace todo. Si no se puede deshacer se responde con un error interno
+    async fn move_with_rollback(
+        &self,
+        task: &Task,
+        target_id: ObjectId,
+        filter: Document,
+        fields: Document,
+    ) -> Result<bool, AppError> {
+        let task_id = task.id.ok_or(AppError::InternalServerError)?;
+        let dependencies: Vec<Document> = self
+            .db_state
+            .get_db()
+            .collection::<Document>("task_dependencies")
+            .find(doc! {"$or": [{"predecessor_id": task_id}, {"successor_id": task_id}]})
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        let epic_children: Vec<ObjectId> = self
+            .task_collection()
+            .distinct(
+                "_id",
+                doc! {"project_id": task.project_id, "epic_id": task_id},
+            )
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .iter()
+            .filter_map(Bson::as_object_id)
+            .collect();
+
+        let error = match self.write_move(task, target_id, filter, fields, None).await {
+            Ok(moved) => return Ok(moved),
+            Err(error) => error,
+        };
+        if let Err(e) = self
+            .undo_move(task, target_id, dependencies, &epic_children)
+            .await
+        {
+            tracing::error!(
+                "Error deshaciendo el movimiento de la tarea {}: {}",
+                task_id,
+                e
+            );
+            return Err(AppError::InternalServerError);
+        }
+        Err(error)
+    }
+
+    // Cada paso se puede repetir aunque el movimiento no llegara a aplicarlo. El número de
+    // clave consumido en el proyecto destino no se devuelve, como en una secuencia.
+    async fn undo_move(
+        &self,
+        task: &Task,
+        target_id: ObjectId,
+        dependencies: Vec<Document>,
+        epic_children: &[ObjectId],
+    ) -> Result<(), AppError> {
+        let task_id = task.id.ok_or(AppError::InternalServerError)?;
+        let restored = Task {
+            version: task.version + 2,
+            wip_warning: None,
+            ..task.clone()
+        };
+        self.task_collection()
+            .replace_one(doc! {"_id": task_id, "project_id": target_id}, restored)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let db = self.db_state.get_db();
+        for collection in ["images", "worklogs"] {
+            db.collection::<Document>(collection)
+                .update_many(
+                    doc! {"task_id": task_id},
+                    doc! {"$set": {"project_id": task.project_id}},
+                )
+                .await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        }
+
+        let collection = db.collection::<Document>("task_dependencies");
+        collection
+            .delete_many(doc! {"$or": [{"predecessor_id": task_id}, {"successor_id": task_id}]})
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if !dependencies.is_empty() {
+            collection
+                .insert_many(dependencies)
+                .await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        }
+
+        self.task_collection()
+            .update_many(
+                doc! {"_id": {"$in": epic_children}, "epic_id": {"$exists": false}},
+                doc! {"$set": {"epic_id": task_id}, "$inc": {"version": 1}},
+            )
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    // //* Valores de campos personalizados que siguen siendo válidos en el proyecto destino:
+    // //* una definición con la misma clave que acepte el valor. El resto se descarta.
+    async fn carry_custom_fields(
+        &self,
+        task: &Task,
+        target: &Project,
+    ) -> Result<Document, AppError> {
+        if task.custom_fields.is_empty() {
+            return Ok(Document::new());
+        }
+        let target_id = target.id.ok_or(AppError::InternalServerError)?;
+        let definitions = CustomFieldService::new(self.db_state.clone())
+            .definitions_for_project(target_id)
+            .await?;
+
+        let mut carried = Document::new();
+        for (key, value) in &task.custom_fields {
+            let Some(field) = definitions.iter().find(|field| &field.key == key) else {
+                continue;
+            };
+            let value = match value {
+                Bson::ObjectId(id) => serde_json::Value::String(id.to_hex()),
+                other => other.clone().into_relaxed_extjson(),
+            };
+            let Ok(Some(value)) = field.coerce_value(&value) else {
+                continue;
+            };
+            if let Bson::ObjectId(member_id) = &value
+                && target.owner_id != *member_id
+                && !target.members.contains(member_id)
+            {
+                continue;
+            }
+            carried.insert(key.clone(), value);
+        }
+        Ok(carried)
+    }
+
+    // Dependencias en las que participa la tarea, como predecesora o como sucesora
+    async fn delete_dependencies(&self, task_id: ObjectId) -> Result<(), AppError> {
+        self.db_state
+            .get_db()
+            .collection::<Document>("task_dependencies")
+            .delete_many(doc! {"$or": [{"predecessor_id": task_id}, {"successor_id": task_id}]})
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        Ok(())
     }
 
     // //* Delete a task
@@ -317,6 +1369,16 @@ impl TaskService {
             ));
         }
 
+        self.delete_dependencies(task_id).await?;
+
+        // El historial se conserva para la actividad del proyecto
+        HistoryService::new(self.db_state.clone())
+            .record(vec![
+                TaskHistoryEntry::new(task_id, task.project_id, user_id, HistoryAction::Deleted)
+                    .with_change("title", history_value(&task.title), None),
+            ])
+            .await;
+
         // Emitir mensaje WebSocket para tarea eliminada
         let broadcast_message = serde_json::json!({
             "event_type": "TASK_DELETED",
@@ -0,0 +1,155 @@
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::broadcast;
+
+use crate::{
+    db::DatabaseState,
+    errors::AppError,
+    models::{
+        dependency_model::{Timeline, TimelineItem, TimelineLink},
+        task_model::{DateRange, Task},
+    },
+    services::{
+        calendar_service::CalendarService, date_range_service::DateRangeService,
+        dependency_service::DependencyService, permission_service::PermissionService,
+    },
+    utils::{
+        calendar::as_working_days,
+        critical_path::{PlannedTask, critical_path},
+    },
+};
+
+pub struct TimelineService {
+    db_state: Arc<DatabaseState>,
+    ws_tx: broadcast::Sender<String>,
+}
+
+impl TimelineService {
+    pub fn new(db_state: Arc<DatabaseState>, ws_tx: broadcast::Sender<String>) -> Self {
+        Self { db_state, ws_tx }
+    }
+
+    /// Cronograma del proyecto: tareas con sus fechas, dependencias, holgura y camino crítico
+    pub async fn project_timeline(
+        &self,
+        project_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<Timeline, AppError> {
+        PermissionService::new(self.db_state.get_db())
+            .can_access_project(project_id, user_id)
+            .await?;
+
+        let db = self.db_state.get_db();
+        let tasks: Vec<Task> = db
+            .collection::<Task>("tasks")
+            .find(doc! {"project_id": project_id})
+            .sort(doc! {"rank": 1, "_id": 1})
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        let task_ids: Vec<ObjectId> = tasks.iter().filter_map(|task| task.id).collect();
+
+        let ranges: HashMap<ObjectId, DateRange> = db
+            .collection::<DateRange>("task_date_ranges")
+            .find(doc! {"task_id": {"$in": &task_ids}})
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .try_collect::<Vec<DateRange>>()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .into_iter()
+            .map(|range| (range.task_id, range))
+            .collect();
+
+        let dependencies = DependencyService::new(self.db_state.clone(), self.ws_tx.clone())
+            .project_dependencies(project_id)
+            .await?;
+        let calendar_service = CalendarService::new(self.db_state.clone());
+        let time_zone = calendar_service
+            .project_calendar(project_id)
+            .await?
+            .time_zone;
+        let calendar = calendar_service.work_calendar(project_id).await?;
+        let now = Utc::now();
+
+        // //? Solo se planifican las tareas con inicio y fin; el resto aparece sin cálculos
+        let planned: Vec<PlannedTask> = task_ids
+            .iter()
+            .filter_map(|id| {
+                let range = ranges.get(id)?;
+                Some(PlannedTask {
+                    id: *id,
+                    start: range.start_date?,
+                    end: range.end_date?,
+                })
+            })
+            .collect();
+        let links: Vec<(ObjectId, ObjectId)> = dependencies
+            .iter()
+            .map(|link| (link.predecessor_id, link.successor_id))
+            .collect();
+        let analysis = critical_path(&planned, &links, &calendar);
+
+        let mut items: Vec<TimelineItem> = tasks
+            .iter()
+            .filter_map(|task| {
+                let id = task.id?;
+                let range = ranges.get(&id);
+                let start_date = range.and_then(|range| range.start_date);
+                let end_date = range.and_then(|range| range.end_date);
+                let schedule = analysis.schedule.get(&id);
+                Some(TimelineItem {
+                    task_id: id.to_hex(),
+                    key: task.key.clone(),
+                    title: task.title.clone(),
+                    status: task.status.clone(),
+                    assignee_id: task.assignee_id.map(|id| id.to_hex()),
+                    start_date,
+                    end_date,
+                    earliest_start: schedule.map(|s| s.earliest_start),
+                    earliest_finish: schedule.map(|s| s.earliest_finish),
+                    latest_start: schedule.map(|s| s.latest_start),
+                    latest_finish: schedule.map(|s| s.latest_finish),
+                    working_days: start_date
+                        .zip(end_date)
+                        .map(|(start, end)| as_working_days(calendar.working_time(start, end))),
+                    slack_minutes: schedule.map(|s| s.slack.num_minutes()),
+                    slack_working_days: schedule.map(|s| as_working_days(s.slack)),
+                    critical: schedule.is_some_and(|s| s.is_critical()),
+                    overdue: DateRangeService::is_overdue(&calendar, &task.status, end_date, now),
+                })
+            })
+            .collect();
+        // Primero las tareas por fecha de inicio; las que no tienen quedan al final por rango
+        items.sort_by_key(|item| (item.start_date.is_none(), item.start_date));
+
+        // //! Una sucesora que empieza antes de que acabe su predecesora incumple la dependencia
+        let violated = |predecessor: &ObjectId, successor: &ObjectId| {
+            let end = ranges.get(predecessor).and_then(|range| range.end_date);
+            let start = ranges.get(successor).and_then(|range| range.start_date);
+            matches!((end, start), (Some(end), Some(start)) if start < end)
+        };
+        let dependencies = dependencies
+            .iter()
+            .map(|link| TimelineLink {
+                predecessor_id: link.predecessor_id.to_hex(),
+                successor_id: link.successor_id.to_hex(),
+                violated: violated(&link.predecessor_id, &link.successor_id),
+            })
+            .collect();
+
+        Ok(Timeline {
+            project_id: project_id.to_hex(),
+            time_zone,
+            start: planned.iter().map(|task| task.start).min(),
+            end: analysis.end,
+            items,
+            dependencies,
+            critical_path: analysis.path.iter().map(|id| id.to_hex()).collect(),
+        })
+    }
+}
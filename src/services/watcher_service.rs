@@ -0,0 +1,243 @@
+use futures::TryStreamExt;
+use mongodb::{
+    Collection,
+    bson::{doc, oid::ObjectId},
+};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use validator::Validate;
+
+use crate::{
+    db::DatabaseState,
+    errors::AppError,
+    models::{
+        history_model::{HistoryAction, TaskHistoryEntry},
+        task_model::Task,
+        user_model::{User, UserData},
+        watcher_model::{TaskWatchers, WatchedTasksPage, WatchedTasksQuery},
+    },
+    services::{
+        history_service::{HistoryService, history_value},
+        permission_service::PermissionService,
+    },
+    utils::cursor,
+};
+
+pub struct WatcherService {
+    db_state: Arc<DatabaseState>,
+    ws_tx: broadcast::Sender<String>,
+}
+
+impl WatcherService {
+    pub fn new(db_state: Arc<DatabaseState>, ws_tx: broadcast::Sender<String>) -> Self {
+        Self { db_state, ws_tx }
+    }
+
+    fn task_collection(&self) -> Collection<Task> {
+        self.db_state.get_db().collection::<Task>("tasks")
+    }
+
+    // //* Tarea a la que el usuario tiene acceso a través de su proyecto
+    async fn find_task(&self, task_id: ObjectId, user_id: ObjectId) -> Result<Task, AppError> {
+        let task = self
+            .task_collection()
+            .find_one(doc! {"_id": task_id})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .ok_or_else(|| AppError::NotFound("Tarea no encontrada".to_string()))?;
+
+        PermissionService::new(self.db_state.get_db())
+            .can_access_project(task.project_id, user_id)
+            .await?;
+
+        Ok(task)
+    }
+
+    // //* Empezar a observar una tarea (no hace nada si ya la observaba)
+    pub async fn watch(
+        &self,
+        task_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<TaskWatchers, AppError> {
+        self.find_task(task_id, user_id).await?;
+        self.change_watchers(task_id, user_id, true).await
+    }
+
+    // //* Dejar de observar una tarea; también vale para el informador y el asignado
+    pub async fn unwatch(
+        &self,
+        task_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<TaskWatchers, AppError> {
+        self.find_task(task_id, user_id).await?;
+        self.change_watchers(task_id, user_id, false).await
+    }
+
+    async fn change_watchers(
+        &self,
+        task_id: ObjectId,
+        user_id: ObjectId,
+        watching: bool,
+    ) -> Result<TaskWatchers, AppError> {
+        // Los observadores forman parte de la tarea, así que cambian su versión (ETag)
+        let update = if watching {
+            doc! {"$addToSet": {"watchers": user_id}, "$inc": {"version": 1}}
+        } else {
+            doc! {"$pull": {"watchers": user_id}, "$inc": {"version": 1}}
+        };
+        let previous = self
+            .task_collection()
+            .find_one_and_update(doc! {"_id": task_id}, update)
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .ok_or_else(|| AppError::NotFound("Tarea no encontrada".to_string()))?;
+
+        // //? La lista resultante se calcula igual que $addToSet / $pull sobre el documento previo
+        let mut watchers = previous.watchers.clone();
+        if watching && !watchers.contains(&user_id) {
+            watchers.push(user_id);
+        } else if !watching {
+            watchers.retain(|id| *id != user_id);
+        }
+        if watchers != previous.watchers {
+            let hex_list = |ids: &[ObjectId]| {
+                history_value(&ids.iter().map(|id| id.to_hex()).collect::<Vec<_>>())
+            };
+            let entry = TaskHistoryEntry::new(
+                task_id,
+                previous.project_id,
+                user_id,
+                HistoryAction::Updated,
+            )
+            .with_change(
+                "watchers",
+                hex_list(&previous.watchers),
+                hex_list(&watchers),
+            );
+            HistoryService::new(self.db_state.clone())
+                .record(vec![entry])
+                .await;
+        }
+        let task = Task {
+            watchers,
+            ..previous
+        };
+
+        let broadcast_message = serde_json::json!({
+            "event_type": "TASK_WATCHERS_UPDATED",
+            "task_id": task_id.to_hex(),
+            "project_id": task.project_id.to_hex(),
+            "watchers": task.watchers.iter().map(|id| id.to_hex()).collect::<Vec<_>>(),
+        })
+        .to_string();
+
+        if let Err(e) = self.ws_tx.send(broadcast_message) {
+            tracing::warn!(
+                "Error enviando mensaje WebSocket para observadores de tarea: {}",
+                e
+            );
+        }
+
+        self.task_watchers(&task, user_id).await
+    }
+
+    // //* Observadores de una tarea con su nombre de usuario
+    pub async fn get_watchers(
+        &self,
+        task_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<TaskWatchers, AppError> {
+        let task = self.find_task(task_id, user_id).await?;
+        self.task_watchers(&task, user_id).await
+    }
+
+    async fn task_watchers(
+        &self,
+        task: &Task,
+        user_id: ObjectId,
+    ) -> Result<TaskWatchers, AppError> {
+        let task_id = task.id.ok_or(AppError::InternalServerError)?;
+        let mut users: Vec<User> = self
+            .db_state
+            .get_db()
+            .collection::<User>("users")
+            .find(doc! {"_id": {"$in": &task.watchers}})
+            .projection(doc! {"password_hash": 0})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        // Mismo orden en que empezaron a observar la tarea
+        users.sort_by_key(|user| {
+            task.watchers
+                .iter()
+                .position(|id| Some(*id) == user.id)
+                .unwrap_or(usize::MAX)
+        });
+
+        Ok(TaskWatchers {
+            task_id,
+            watching: task.watchers.contains(&user_id),
+            watchers: users.into_iter().map(UserData::from).collect(),
+        })
+    }
+
+    // //* Tareas que observa el usuario en los proyectos a los que sigue teniendo acceso
+    pub async fn watched_tasks(
+        &self,
+        user_id: ObjectId,
+        query: WatchedTasksQuery,
+    ) -> Result<WatchedTasksPage, AppError> {
+        query
+            .validate()
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+        let project_ids: Vec<ObjectId> = PermissionService::new(self.db_state.get_db())
+            .accessible_projects(user_id)
+            .await?
+            .into_iter()
+            .filter_map(|project| project.id)
+            .collect();
+
+        let mut filter = doc! {"watchers": user_id, "project_id": {"$in": project_ids}};
+        if let Some(cursor) = &query.cursor {
+            let cursor = cursor::decode(cursor)?;
+            let invalid = || AppError::ValidationError("Cursor de paginación inválido".to_string());
+            let updated_at = cursor.get_datetime("updated_at").map_err(|_| invalid())?;
+            let last_id = cursor.get_object_id("id").map_err(|_| invalid())?;
+            filter.insert(
+                "$or",
+                vec![
+                    doc! {"updated_at": {"$lt": updated_at}},
+                    doc! {"updated_at": updated_at, "_id": {"$lt": last_id}},
+                ],
+            );
+        }
+
+        let limit = query.limit.unwrap_or(50) as i64;
+        let mut items: Vec<Task> = self
+            .task_collection()
+            .find(filter)
+            .sort(doc! {"updated_at": -1, "_id": -1})
+            .limit(limit + 1)
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let has_more = items.len() as i64 > limit;
+        items.truncate(limit as usize);
+        let next_cursor = match items.last() {
+            Some(last) if has_more => Some(cursor::encode(&doc! {
+                "updated_at": bson::DateTime::from_chrono(last.updated_at),
+                "id": last.id,
+            })),
+            _ => None,
+        };
+
+        Ok(WatchedTasksPage { items, next_cursor })
+    }
+}
@@ -0,0 +1,422 @@
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::{
+    Collection,
+    bson::{Bson, Document, doc, from_document, oid::ObjectId},
+};
+use std::sync::Arc;
+use validator::Validate;
+
+use crate::{
+    db::DatabaseState,
+    errors::AppError,
+    models::{
+        history_model::{HistoryAction, TaskHistoryEntry},
+        project_models::Project,
+        task_model::Task,
+        worklog_model::{
+            CreateWorklogSchema, TimesheetEntry, TimesheetReport, UpdateWorklogSchema, Worklog,
+        },
+    },
+    services::{
+        history_service::{HistoryService, history_value},
+        permission_service::PermissionService,
+    },
+};
+
+pub struct WorklogService {
+    db_state: Arc<DatabaseState>,
+}
+
+impl WorklogService {
+    pub fn new(db_state: Arc<DatabaseState>) -> Self {
+        Self { db_state }
+    }
+
+    fn worklog_collection(&self) -> Collection<Worklog> {
+        self.db_state.get_db().collection::<Worklog>("worklogs")
+    }
+
+    fn task_collection(&self) -> Collection<Task> {
+        self.db_state.get_db().collection::<Task>("tasks")
+    }
+
+    async fn find_task(&self, task_id: ObjectId) -> Result<Task, AppError> {
+        self.task_collection()
+            .find_one(doc! {"_id": task_id})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .ok_or_else(|| AppError::NotFound("Tarea no encontrada".to_string()))
+    }
+
+    async fn find_worklog(
+        &self,
+        task_id: ObjectId,
+        worklog_id: ObjectId,
+    ) -> Result<Worklog, AppError> {
+        self.worklog_collection()
+            .find_one(doc! {"_id": worklog_id, "task_id": task_id})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .ok_or_else(|| AppError::NotFound("Registro de trabajo no encontrado".to_string()))
+    }
+
+    // //* Solo el autor del registro o el dueño del proyecto pueden modificarlo
+    fn ensure_can_modify(
+        worklog: &Worklog,
+        project: &Project,
+        user_id: ObjectId,
+    ) -> Result<(), AppError> {
+        if worklog.user_id != user_id && project.owner_id != user_id {
+            return Err(AppError::Unauthorized(
+                "No tienes permiso para modificar este registro de trabajo".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    // //* Ajusta la estimación restante de la tarea restando `logged_minutes`.
+    // //* Si la tarea no tiene estimación restante se parte de la original; si no
+    // //* tiene ninguna de las dos, se deja sin estimación. Nunca baja de cero.
+    async fn adjust_remaining_estimate(
+        &self,
+        task_id: ObjectId,
+        user_id: ObjectId,
+        logged_minutes: i64,
+    ) -> Result<(), AppError> {
+        if logged_minutes == 0 {
+            return Ok(());
+        }
+
+        let pipeline = vec![doc! {
+            "$set": {
+                "remaining_estimate_minutes": {
+                    "$let": {
+                        "vars": {
+                            "base": {
+                                "$ifNull": [
+                                    "$remaining_estimate_minutes",
+                                    "$original_estimate_minutes",
+                                    Bson::Null
+                                ]
+                            }
+                        },
+                        "in": {
+                            "$cond": [
+                                { "$eq": ["$$base", Bson::Null] },
+                                Bson::Null,
+                                { "$max": [0, { "$subtract": ["$$base", logged_minutes] }] }
+                            ]
+                        }
+                    }
+                },
+                "version": {"$add": [{"$ifNull": ["$version", 0]}, 1]},
+                "updated_at": Utc::now(),
+            }
+        }];
+
+        let previous = self
+            .task_collection()
+            .find_one_and_update(doc! {"_id": task_id}, pipeline)
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .ok_or_else(|| AppError::NotFound("Tarea no encontrada".to_string()))?;
+
+        // Mismo cálculo que el pipeline, aplicado al documento previo para el historial
+        let remaining = previous
+            .remaining_estimate_minutes
+            .or(previous.original_estimate_minutes)
+            .map(|base| (base - logged_minutes).max(0));
+        if remaining != previous.remaining_estimate_minutes {
+            let entry = TaskHistoryEntry::new(
+                task_id,
+                previous.project_id,
+                user_id,
+                HistoryAction::Updated,
+            )
+            .with_change(
+                "remaining_estimate_minutes",
+                history_value(&previous.remaining_estimate_minutes),
+                history_value(&remaining),
+            );
+            HistoryService::new(self.db_state.clone())
+                .record(vec![entry])
+                .await;
+        }
+
+        Ok(())
+    }
+
+    // //* Registrar tiempo en una tarea
+    pub async fn create_worklog(
+        &self,
+        task_id: ObjectId,
+        user_id: ObjectId,
+        schema: CreateWorklogSchema,
+    ) -> Result<Worklog, AppError> {
+        schema
+            .validate()
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+        let task = self.find_task(task_id).await?;
+
+        PermissionService::new(self.db_state.get_db())
+            .can_access_project(task.project_id, user_id)
+            .await?;
+
+        let now = Utc::now();
+        let mut worklog = Worklog {
+            id: None,
+            task_id,
+            project_id: task.project_id,
+            user_id,
+            started_at: schema.started_at.unwrap_or(now),
+            duration_minutes: schema.duration_minutes,
+            comment: schema.comment,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let result = self
+            .worklog_collection()
+            .insert_one(&worklog)
+            .await
+            .map_err(|_| AppError::InternalServerError)?;
+        worklog.id = result.inserted_id.as_object_id();
+
+        self.adjust_remaining_estimate(task_id, user_id, worklog.duration_minutes)
+            .await?;
+
+        Ok(worklog)
+    }
+
+    // //* Listar los registros de trabajo de una tarea
+    pub async fn get_worklogs_for_task(
+        &self,
+        task_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<Vec<Worklog>, AppError> {
+        let task = self.find_task(task_id).await?;
+
+        PermissionService::new(self.db_state.get_db())
+            .can_access_project(task.project_id, user_id)
+            .await?;
+
+        let worklogs = self
+            .worklog_collection()
+            .find(doc! {"task_id": task_id})
+            .sort(doc! {"started_at": 1})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(worklogs)
+    }
+
+    // //* Actualizar un registro de trabajo
+    pub async fn update_worklog(
+        &self,
+        task_id: ObjectId,
+        worklog_id: ObjectId,
+        user_id: ObjectId,
+        schema: UpdateWorklogSchema,
+    ) -> Result<Worklog, AppError> {
+        schema
+            .validate()
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+        let worklog = self.find_worklog(task_id, worklog_id).await?;
+        let project = PermissionService::new(self.db_state.get_db())
+            .can_access_project(worklog.project_id, user_id)
+            .await?;
+        Self::ensure_can_modify(&worklog, &project, user_id)?;
+
+        let mut update_doc = doc! {};
+        if let Some(started_at) = schema.started_at {
+            update_doc.insert("started_at", started_at);
+        }
+        if let Some(duration) = schema.duration_minutes {
+            update_doc.insert("duration_minutes", duration);
+        }
+        if let Some(comment) = &schema.comment {
+            update_doc.insert("comment", comment);
+        }
+
+        if update_doc.is_empty() {
+            return Ok(worklog);
+        }
+        let now = Utc::now();
+        update_doc.insert("updated_at", now);
+
+        // //! La diferencia se calcula sobre el documento que se ha sobrescrito y no sobre
+        // //! la lectura previa: dos ediciones simultáneas restarían la misma duración
+        let previous = self
+            .worklog_collection()
+            .find_one_and_update(doc! {"_id": worklog_id}, doc! {"$set": update_doc})
+            .with_options(
+                mongodb::options::FindOneAndUpdateOptions::builder()
+                    .return_document(mongodb::options::ReturnDocument::Before)
+                    .build(),
+            )
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .ok_or_else(|| AppError::NotFound("Registro de trabajo no encontrado".to_string()))?;
+
+        let updated = Worklog {
+            started_at: schema.started_at.unwrap_or(previous.started_at),
+            duration_minutes: schema.duration_minutes.unwrap_or(previous.duration_minutes),
+            comment: schema.comment.or(previous.comment),
+            updated_at: now,
+            ..previous
+        };
+
+        // Solo la diferencia de duración afecta a la estimación restante
+        self.adjust_remaining_estimate(
+            task_id,
+            user_id,
+            updated.duration_minutes - previous.duration_minutes,
+        )
+        .await?;
+
+        Ok(updated)
+    }
+
+    // //* Eliminar un registro de trabajo, devolviendo su tiempo a la estimación restante
+    pub async fn delete_worklog(
+        &self,
+        task_id: ObjectId,
+        worklog_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<(), AppError> {
+        let worklog = self.find_worklog(task_id, worklog_id).await?;
+        let project = PermissionService::new(self.db_state.get_db())
+            .can_access_project(worklog.project_id, user_id)
+            .await?;
+        Self::ensure_can_modify(&worklog, &project, user_id)?;
+
+        // //? Se devuelve la duración del documento borrado por si otra edición la cambió
+        let deleted = self
+            .worklog_collection()
+            .find_one_and_delete(doc! {"_id": worklog_id})
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .ok_or_else(|| AppError::NotFound("Registro de trabajo no encontrado".to_string()))?;
+
+        self.adjust_remaining_estimate(task_id, user_id, -deleted.duration_minutes)
+            .await?;
+
+        Ok(())
+    }
+
+    // //* Reporte de horas de un proyecto en un rango de fechas
+    pub async fn project_timesheet(
+        &self,
+        project_id: ObjectId,
+        user_id: ObjectId,
+        from: chrono::DateTime<Utc>,
+        to: chrono::DateTime<Utc>,
+    ) -> Result<TimesheetReport, AppError> {
+        PermissionService::new(self.db_state.get_db())
+            .can_access_project(project_id, user_id)
+            .await?;
+
+        self.timesheet(doc! {"project_id": project_id}, from, to)
+            .await
+    }
+
+    // //* Reporte de horas imputadas por un usuario en un rango de fechas
+    pub async fn user_timesheet(
+        &self,
+        user_id: ObjectId,
+        from: chrono::DateTime<Utc>,
+        to: chrono::DateTime<Utc>,
+    ) -> Result<TimesheetReport, AppError> {
+        self.timesheet(doc! {"user_id": user_id}, from, to).await
+    }
+
+    async fn timesheet(
+        &self,
+        mut filter: Document,
+        from: chrono::DateTime<Utc>,
+        to: chrono::DateTime<Utc>,
+    ) -> Result<TimesheetReport, AppError> {
+        if to <= from {
+            return Err(AppError::ValidationError(
+                "La fecha final del reporte debe ser posterior a la inicial".to_string(),
+            ));
+        }
+
+        filter.insert("started_at", doc! {"$gte": from, "$lt": to});
+
+        // //? Agrupar por día, usuario y tarea, resolviendo nombres con $lookup
+        let pipeline = vec![
+            doc! { "$match": filter },
+            doc! {
+                "$group": {
+                    "_id": {
+                        "date": { "$dateToString": { "format": "%Y-%m-%d", "date": "$started_at" } },
+                        "user_id": "$user_id",
+                        "task_id": "$task_id",
+                    },
+                    "project_id": { "$first": "$project_id" },
+                    "minutes": { "$sum": "$duration_minutes" },
+                }
+            },
+            doc! {
+                "$lookup": {
+                    "from": "users",
+                    "localField": "_id.user_id",
+                    "foreignField": "_id",
+                    "as": "user"
+                }
+            },
+            doc! {
+                "$lookup": {
+                    "from": "tasks",
+                    "localField": "_id.task_id",
+                    "foreignField": "_id",
+                    "as": "task"
+                }
+            },
+            doc! { "$sort": { "_id.date": 1, "_id.user_id": 1 } },
+            doc! {
+                "$project": {
+                    "_id": 0,
+                    "date": "$_id.date",
+                    "user_id": { "$toString": "$_id.user_id" },
+                    "username": { "$ifNull": [{ "$first": "$user.username" }, ""] },
+                    "task_id": { "$toString": "$_id.task_id" },
+                    "task_title": { "$ifNull": [{ "$first": "$task.title" }, ""] },
+                    "project_id": { "$toString": "$project_id" },
+                    "minutes": { "$toLong": "$minutes" },
+                }
+            },
+        ];
+
+        let docs: Vec<Document> = self
+            .worklog_collection()
+            .aggregate(pipeline)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let entries = docs
+            .into_iter()
+            .map(from_document::<TimesheetEntry>)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let total_minutes = entries.iter().map(|entry| entry.minutes).sum();
+
+        Ok(TimesheetReport {
+            from,
+            to,
+            total_minutes,
+            entries,
+        })
+    }
+}
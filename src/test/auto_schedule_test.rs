@@ -0,0 +1,181 @@
+use axum::{Router, http::StatusCode};
+use bson::{oid::ObjectId, uuid};
+use chrono::{TimeZone, Utc};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    helpers::helper_setup_app::{
+        create_project_for_user, create_task_for_project, get_auth_token_and_id, send_request,
+        setup_app,
+    },
+    models::{dependency_model::ReschedulePreview, task_model::DateRange},
+    utils::{
+        calendar::WorkCalendar,
+        critical_path::{PlannedTask, propagate_dates},
+    },
+};
+
+#[test]
+fn test_propagate_dates() {
+    let day = |d| Utc.with_ymd_and_hms(2025, 3, d, 0, 0, 0).unwrap();
+    let task = |start, end| PlannedTask {
+        id: ObjectId::new(),
+        start: day(start),
+        end: day(end),
+    };
+    // El diseño se ha retrasado hasta el día 7
+    let design = task(1, 7);
+    let build = task(5, 10);
+    let release = task(11, 12);
+    let docs = task(9, 11);
+    let unrelated = task(1, 2);
+    let links = [
+        (design.id, build.id),
+        (build.id, release.id),
+        (design.id, docs.id),
+    ];
+
+    let shifted = propagate_dates(
+        &[design, build, release, docs, unrelated],
+        &links,
+        design.id,
+        &WorkCalendar::default(),
+    );
+    // //* La construcción se mueve dos días y arrastra a la entrega; la documentación ya
+    // //* empezaba después del diseño y no se mueve
+    let dates: Vec<_> = shifted
+        .iter()
+        .map(|task| (task.id, task.start, task.end))
+        .collect();
+    assert_eq!(
+        dates,
+        [(build.id, day(7), day(12)), (release.id, day(12), day(13))]
+    );
+}
+
+async fn set_range(app: &Router, token: &str, task_id: &str, start: u32, end: u32) {
+    let (status, _) = send_request(
+        app,
+        "POST",
+        format!("/api/tasks/{}/date-range", task_id),
+        token,
+        json!({
+            "start_date": format!("2025-03-{:02}T00:00:00Z", start),
+            "end_date": format!("2025-03-{:02}T00:00:00Z", end),
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+async fn range(app: &Router, token: &str, task_id: &str) -> DateRange {
+    let (_, body) = send_request(
+        app,
+        "GET",
+        format!("/api/tasks/{}/date-range", task_id),
+        token,
+        json!({}),
+    )
+    .await;
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn test_auto_schedule() {
+    let app = setup_app().await;
+    let day = |d| Some(Utc.with_ymd_and_hms(2025, 3, d, 0, 0, 0).unwrap());
+
+    let email = format!("schedule-{}@test.com", Uuid::new());
+    let (token, _) = get_auth_token_and_id(&app, "schedule_user", &email).await;
+    let project_id = create_project_for_user(&app, &token, "SCHED").await;
+    let design = create_task_for_project(&app, &token, &project_id, None).await;
+    let build = create_task_for_project(&app, &token, &project_id, None).await;
+    let release = create_task_for_project(&app, &token, &project_id, None).await;
+    set_range(&app, &token, &design, 1, 5).await;
+    set_range(&app, &token, &build, 5, 10).await;
+    set_range(&app, &token, &release, 10, 11).await;
+    for (task_id, predecessor_id) in [(&build, &design), (&release, &build)] {
+        let (status, _) = send_request(
+            &app,
+            "POST",
+            format!("/api/tasks/{}/dependencies", task_id),
+            &token,
+            json!({"predecessor_id": predecessor_id}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+
+    let slip = json!({"end_date": "2025-03-08T00:00:00Z"});
+
+    // //* La vista previa muestra los desplazamientos sin guardar nada
+    let (status, body) = send_request(
+        &app,
+        "POST",
+        format!("/api/tasks/{}/date-range/preview", design),
+        &token,
+        slip.clone(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let preview: ReschedulePreview = serde_json::from_slice(&body).unwrap();
+    assert!(!preview.auto_schedule);
+    assert_eq!(preview.end_date, day(8));
+    let shifts: Vec<_> = preview
+        .shifts
+        .iter()
+        .map(|shift| {
+            (
+                shift.task_id.as_str(),
+                shift.new_start_date,
+                shift.new_end_date,
+            )
+        })
+        .collect();
+    assert_eq!(
+        shifts,
+        [
+            (build.as_str(), day(8).unwrap(), day(13).unwrap()),
+            (release.as_str(), day(13).unwrap(), day(14).unwrap()),
+        ]
+    );
+    assert_eq!(range(&app, &token, &design).await.end_date, day(5));
+
+    // //? Sin planificación automática solo cambia la tarea editada
+    let uri = format!("/api/tasks/{}/date-range", design);
+    let (status, _) = send_request(&app, "PATCH", uri.clone(), &token, slip).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(range(&app, &token, &build).await.start_date, day(5));
+
+    // //* Con planificación automática el retraso se propaga por las dependencias
+    let (status, _) = send_request(
+        &app,
+        "PATCH",
+        format!("/api/projects/{}", project_id),
+        &token,
+        json!({"auto_schedule": true}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send_request(
+        &app,
+        "PATCH",
+        uri,
+        &token,
+        json!({"end_date": "2025-03-09T00:00:00Z"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let build_range = range(&app, &token, &build).await;
+    assert_eq!(
+        (build_range.start_date, build_range.end_date),
+        (day(9), day(14))
+    );
+    let release_range = range(&app, &token, &release).await;
+    assert_eq!(
+        (release_range.start_date, release_range.end_date),
+        (day(14), day(15))
+    );
+}
@@ -0,0 +1,165 @@
+use axum::http::StatusCode;
+use bson::uuid;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    helpers::helper_setup_app::{
+        create_project_for_user, create_task_for_project, get_auth_token, send_request, setup_app,
+    },
+    models::{board_model::BoardView, bulk_task_model::BulkTaskResponse, task_model::Task},
+};
+
+#[tokio::test]
+async fn test_board_columns_and_wip_limits() {
+    let app = setup_app().await;
+
+    let email = format!("board-owner-{}@test.com", Uuid::new());
+    let token = get_auth_token(&app, "board_owner", &email).await;
+    let project_id = create_project_for_user(&app, &token, "BOARD").await;
+
+    let first = create_task_for_project(&app, &token, &project_id, None).await;
+    let second = create_task_for_project(&app, &token, &project_id, None).await;
+
+    // //! Un estado no puede aparecer en dos columnas
+    let (status, _) = send_request(
+        &app,
+        "PUT",
+        format!("/api/projects/{}/board", project_id),
+        &token,
+        json!({"columns": [
+            {"name": "Pendiente", "statuses": ["ToDo"]},
+            {"name": "Duplicada", "statuses": ["ToDo", "InProgress"]},
+        ]}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = send_request(
+        &app,
+        "PUT",
+        format!("/api/projects/{}/board", project_id),
+        &token,
+        json!({
+            "columns": [
+                {"name": "Pendiente", "statuses": ["ToDo"]},
+                {"name": "En curso", "statuses": ["InProgress"], "wip_limit": 1},
+                {"name": "Terminado", "statuses": ["Done", "Cancelled"]},
+            ],
+            "swimlane_by": "priority",
+            "wip_policy": "reject",
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = send_request(
+        &app,
+        "PATCH",
+        format!("/api/tasks/{}", first),
+        &token,
+        json!({"status": "InProgress"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // //? La segunda tarea superaría el límite de la columna y se rechaza
+    let (status, _) = send_request(
+        &app,
+        "PATCH",
+        format!("/api/tasks/{}", second),
+        &token,
+        json!({"status": "InProgress"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    // //! Con la política de aviso el cambio se aplica y el tablero marca la columna
+    let (status, _) = send_request(
+        &app,
+        "PUT",
+        format!("/api/projects/{}/board", project_id),
+        &token,
+        json!({
+            "columns": [
+                {"name": "Pendiente", "statuses": ["ToDo"]},
+                {"name": "En curso", "statuses": ["InProgress"], "wip_limit": 1},
+            ],
+            "wip_policy": "warn",
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = send_request(
+        &app,
+        "PATCH",
+        format!("/api/tasks/{}", second),
+        &token,
+        json!({"status": "InProgress"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    // //* La respuesta avisa de la columna superada
+    let task: Task = serde_json::from_slice(&body).unwrap();
+    let warning = task.wip_warning.unwrap();
+    assert_eq!(
+        (warning.column.as_str(), warning.task_count),
+        ("En curso", 2)
+    );
+
+    let (status, body) = send_request(
+        &app,
+        "GET",
+        format!("/api/projects/{}/board", project_id),
+        &token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let board: BoardView = serde_json::from_slice(&body).unwrap();
+    assert_eq!(board.columns.len(), 2);
+
+    let in_progress = &board.columns[1];
+    assert_eq!(in_progress.task_count, 2);
+    assert!(in_progress.over_limit);
+    assert_eq!(board.breaches.len(), 1);
+    assert_eq!(board.breaches[0].column, "En curso");
+
+    // //? Las filas siguen agrupadas por prioridad; las tareas del helper son Medium
+    let medium_lane = in_progress
+        .lanes
+        .iter()
+        .find(|lane| lane.key.as_deref() == Some("Medium"))
+        .unwrap();
+    assert_eq!(medium_lane.tasks.len(), 2);
+
+    // //? También avisan la creación y las operaciones masivas
+    let (status, body) = send_request(
+        &app,
+        "POST",
+        format!("/api/projects/{}/tasks", project_id),
+        &token,
+        json!({"title": "Tarea directa en curso", "status": "InProgress"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let task: Task = serde_json::from_slice(&body).unwrap();
+    assert_eq!(task.wip_warning.map(|warning| warning.task_count), Some(3));
+
+    let third = create_task_for_project(&app, &token, &project_id, None).await;
+    let (status, body) = send_request(
+        &app,
+        "POST",
+        format!("/api/projects/{}/tasks/bulk", project_id),
+        &token,
+        json!({"task_ids": [third], "operation": {"type": "update", "status": "InProgress"}}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let response: BulkTaskResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        response.wip_warning.map(|warning| warning.task_count),
+        Some(4)
+    );
+}
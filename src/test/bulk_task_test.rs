@@ -0,0 +1,200 @@
+use axum::{Router, http::StatusCode};
+use bson::{oid::ObjectId, uuid};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use crate::{
+    helpers::helper_setup_app::{
+        add_member_to_project, create_project_for_user, create_task_for_project,
+        get_auth_token_and_id, send_request, setup_app,
+    },
+    models::{
+        bulk_task_model::{BulkItemStatus, BulkTaskResponse},
+        dependency_model::Timeline,
+        sprint_model::Sprint,
+        task_model::{Task, TaskPriority, TaskStatus},
+    },
+};
+
+async fn bulk(
+    app: &Router,
+    token: &str,
+    project_id: &str,
+    task_ids: &[&str],
+    operation: Value,
+) -> (StatusCode, Option<BulkTaskResponse>) {
+    let (status, body) = send_request(
+        app,
+        "POST",
+        format!("/api/projects/{}/tasks/bulk", project_id),
+        token,
+        json!({"task_ids": task_ids, "operation": operation}),
+    )
+    .await;
+    (status, serde_json::from_slice(&body).ok())
+}
+
+async fn get_task(app: &Router, token: &str, task_id: &str) -> (StatusCode, Option<Task>) {
+    let (status, body) = send_request(
+        app,
+        "GET",
+        format!("/api/tasks/{}", task_id),
+        token,
+        json!({}),
+    )
+    .await;
+    (status, serde_json::from_slice(&body).ok())
+}
+
+#[tokio::test]
+async fn test_bulk_task_operations() {
+    let app = setup_app().await;
+
+    let owner_email = format!("bulk-owner-{}@test.com", Uuid::new());
+    let (owner_token, _) = get_auth_token_and_id(&app, "bulk_owner", &owner_email).await;
+    let member_email = format!("bulk-member-{}@test.com", Uuid::new());
+    let (member_token, member_id) = get_auth_token_and_id(&app, "bulk_member", &member_email).await;
+
+    let project_id = create_project_for_user(&app, &owner_token, "BULK").await;
+    add_member_to_project(&app, &owner_token, &project_id, &member_email).await;
+
+    let first = create_task_for_project(&app, &owner_token, &project_id, None).await;
+    let second = create_task_for_project(&app, &owner_token, &project_id, None).await;
+    let third = create_task_for_project(&app, &owner_token, &project_id, None).await;
+
+    // //* Actualización con resultados por tarea
+    let missing = ObjectId::new().to_hex();
+    let (status, response) = bulk(
+        &app,
+        &member_token,
+        &project_id,
+        &[&first, &second, &missing, "no-es-un-id"],
+        json!({"type": "update", "status": "InProgress", "priority": "High",
+               "assignee_id": member_id.to_hex()}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let response = response.unwrap();
+    assert_eq!(response.succeeded, 2);
+    assert_eq!(response.failed, 2);
+    assert_eq!(response.results[2].status, BulkItemStatus::NotFound);
+    assert_eq!(response.results[3].status, BulkItemStatus::Invalid);
+
+    let (_, task) = get_task(&app, &member_token, &second).await;
+    let task = task.unwrap();
+    assert_eq!(task.status, TaskStatus::InProgress);
+    assert_eq!(task.priority, TaskPriority::High);
+    assert_eq!(task.assignee_id, Some(member_id));
+    assert_eq!(task.version, 1);
+
+    // //! Un asignado fuera del proyecto invalida la petición completa
+    let (status, _) = bulk(
+        &app,
+        &member_token,
+        &project_id,
+        &[&third],
+        json!({"type": "update", "assignee_id": ObjectId::new().to_hex()}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // //* Etiquetas: añadir y luego quitar sin perder el resto
+    let (status, _) = bulk(
+        &app,
+        &member_token,
+        &project_id,
+        &[&first, &third],
+        json!({"type": "labels", "add": ["ux", "triage"]}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = bulk(
+        &app,
+        &member_token,
+        &project_id,
+        &[&first, &third],
+        json!({"type": "labels", "add": ["bug", "ux"], "remove": ["triage"]}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, task) = get_task(&app, &member_token, &third).await;
+    assert_eq!(task.unwrap().labels, ["ux", "bug"]);
+
+    // //* Mover a un sprint y devolver al backlog
+    let (status, body) = send_request(
+        &app,
+        "POST",
+        format!("/api/projects/{}/sprints", project_id),
+        &owner_token,
+        json!({"name": "Sprint de triaje"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let sprint: Sprint = serde_json::from_slice(&body).unwrap();
+    let sprint_id = sprint.id.unwrap();
+
+    let (status, _) = bulk(
+        &app,
+        &member_token,
+        &project_id,
+        &[&first, &second, &third],
+        json!({"type": "move", "sprint_id": sprint_id.to_hex()}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, task) = get_task(&app, &member_token, &first).await;
+    assert_eq!(task.unwrap().sprint_id, Some(sprint_id));
+
+    let (status, _) = bulk(
+        &app,
+        &member_token,
+        &project_id,
+        &[&first],
+        json!({"type": "move", "sprint_id": null}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, task) = get_task(&app, &member_token, &first).await;
+    assert_eq!(task.unwrap().sprint_id, None);
+
+    let (status, _) = send_request(
+        &app,
+        "POST",
+        format!("/api/tasks/{}/dependencies", third),
+        &owner_token,
+        json!({"predecessor_id": first}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // //! Eliminar: el miembro solo puede borrar las tareas que tiene asignadas
+    let (status, response) = bulk(
+        &app,
+        &member_token,
+        &project_id,
+        &[&first, &third],
+        json!({"type": "delete"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let response = response.unwrap();
+    assert_eq!(response.results[0].status, BulkItemStatus::Ok);
+    assert_eq!(response.results[1].status, BulkItemStatus::Forbidden);
+
+    let (status, _) = get_task(&app, &member_token, &first).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = get_task(&app, &member_token, &third).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // //? Las dependencias de las tareas borradas desaparecen con ellas
+    let (_, body) = send_request(
+        &app,
+        "GET",
+        format!("/api/projects/{}/timeline", project_id),
+        &member_token,
+        json!({}),
+    )
+    .await;
+    let timeline: Timeline = serde_json::from_slice(&body).unwrap();
+    assert!(timeline.dependencies.is_empty());
+}
@@ -0,0 +1,259 @@
+use axum::{
+    Router,
+    body::{Body, to_bytes},
+    http::{Request, StatusCode, header},
+};
+use bson::uuid;
+use chrono::{Duration, NaiveDate, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use serde_json::json;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use crate::{
+    helpers::helper_setup_app::{
+        add_member_to_project, create_project_for_user, create_task_for_project,
+        get_auth_token_and_id, send_request, setup_app,
+    },
+    models::{
+        calendar_model::ProjectCalendar,
+        task_model::{DateRange, OverdueTask},
+    },
+    utils::calendar::{WorkCalendar, parse_icalendar_holidays},
+};
+
+const WEEKDAYS: [Weekday; 5] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+];
+
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+}
+
+#[test]
+fn test_working_time_skips_weekends_and_holidays() {
+    let at = |d, h| Utc.with_ymd_and_hms(2025, 3, d, h, 0, 0).unwrap();
+    // El viernes 7 se trabaja; el fin de semana y el lunes 10 (festivo) no
+    let calendar = WorkCalendar::new(Tz::UTC, WEEKDAYS, [date(10)]);
+    assert!(calendar.is_working_day(date(7)));
+    assert!(!calendar.is_working_day(date(8)));
+    assert!(!calendar.is_working_day(date(10)));
+
+    assert_eq!(
+        calendar.working_time(at(7, 0), at(11, 0)),
+        Duration::days(1)
+    );
+    assert_eq!(
+        calendar.working_time(at(7, 12), at(11, 6)),
+        Duration::hours(18)
+    );
+    assert_eq!(calendar.next_working_time(at(8, 12)), at(11, 0));
+    assert_eq!(calendar.next_working_time(at(7, 12)), at(7, 12));
+
+    // //* Dos días laborables desde el viernes acaban el miércoles y al revés
+    assert_eq!(calendar.add_working_days(at(7, 0), 2), at(12, 0));
+    assert_eq!(
+        calendar.add_working_time(at(8, 0), Duration::hours(6)),
+        at(11, 6)
+    );
+    assert_eq!(
+        calendar.sub_working_time(at(12, 0), Duration::days(2)),
+        at(7, 0)
+    );
+
+    // El calendario por defecto trabaja todos los días
+    let default = WorkCalendar::default();
+    assert_eq!(default.add_working_days(at(7, 0), 2), at(9, 0));
+}
+
+#[test]
+fn test_working_days_follow_time_zone() {
+    // //? En marzo Madrid va una hora por delante de UTC: el sábado empieza el viernes a las 23:00
+    let calendar = WorkCalendar::new(chrono_tz::Europe::Madrid, WEEKDAYS, []);
+    let at = |d, h, m| Utc.with_ymd_and_hms(2025, 3, d, h, m, 0).unwrap();
+    assert_eq!(calendar.next_working_time(at(7, 22, 30)), at(7, 22, 30));
+    assert_eq!(calendar.next_working_time(at(7, 23, 30)), at(9, 23, 0));
+    assert_eq!(
+        calendar.working_time(at(7, 0, 0), at(10, 0, 0)),
+        Duration::hours(24)
+    );
+}
+
+#[test]
+fn test_parse_icalendar_holidays() {
+    let source = "BEGIN:VCALENDAR\r\n\
+                  VERSION:2.0\r\n\
+                  BEGIN:VEVENT\r\n\
+                  DTSTART;VALUE=DATE:20250310\r\n\
+                  SUMMARY:Fiesta\\, local\r\n\
+                  END:VEVENT\r\n\
+                  BEGIN:VEVENT\r\n\
+                  DTSTART;VALUE=DATE:20250417\r\n\
+                  DTEND;VALUE=DATE:20250419\r\n\
+                  SUMMARY:Semana\r\n  \
+                  Santa\r\n\
+                  END:VEVENT\r\n\
+                  END:VCALENDAR\r\n";
+    let holidays: Vec<_> = parse_icalendar_holidays(source)
+        .unwrap()
+        .into_iter()
+        .map(|holiday| (holiday.date, holiday.name))
+        .collect();
+    let april = |d| NaiveDate::from_ymd_opt(2025, 4, d).unwrap();
+    assert_eq!(
+        holidays,
+        [
+            (date(10), "Fiesta, local".to_string()),
+            (april(17), "Semana Santa".to_string()),
+            (april(18), "Semana Santa".to_string()),
+        ]
+    );
+
+    assert!(parse_icalendar_holidays("no es un calendario").is_err());
+    assert!(
+        parse_icalendar_holidays("BEGIN:VCALENDAR\nBEGIN:VEVENT\nSUMMARY:x\nEND:VEVENT").is_err()
+    );
+}
+
+async fn import_holidays(app: &Router, token: &str, project_id: &str, source: &str) -> StatusCode {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!(
+                    "/api/projects/{}/calendar/holidays/import",
+                    project_id
+                ))
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .header(header::CONTENT_TYPE, "text/calendar")
+                .body(Body::from(source.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    status
+}
+
+#[tokio::test]
+async fn test_project_calendar() {
+    let app = setup_app().await;
+    let day = |d| Utc.with_ymd_and_hms(2025, 3, d, 0, 0, 0).unwrap();
+
+    let email = format!("calendar-{}@test.com", Uuid::new());
+    let (token, _) = get_auth_token_and_id(&app, "calendar_user", &email).await;
+    let member_email = format!("calendar-member-{}@test.com", Uuid::new());
+    let (member_token, _) = get_auth_token_and_id(&app, "calendar_member", &member_email).await;
+    let project_id = create_project_for_user(&app, &token, "CAL").await;
+    add_member_to_project(&app, &token, &project_id, &member_email).await;
+    let uri = format!("/api/projects/{}/calendar", project_id);
+
+    // //* Sin calendario guardado se trabaja todos los días en UTC
+    let (status, body) = send_request(&app, "GET", uri.clone(), &member_token, json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    let calendar: ProjectCalendar = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        (calendar.time_zone.as_str(), calendar.working_days.len()),
+        ("UTC", 7)
+    );
+
+    let week = json!({"time_zone": "UTC", "working_days": ["Fri", "Mon", "Tue", "Wed", "Thu"]});
+    let (status, _) = send_request(&app, "PUT", uri.clone(), &member_token, week.clone()).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = send_request(
+        &app,
+        "PUT",
+        uri.clone(),
+        &token,
+        json!({"time_zone": "Marte/Olympus", "working_days": ["Mon"]}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, body) = send_request(&app, "PUT", uri.clone(), &token, week).await;
+    assert_eq!(status, StatusCode::OK);
+    let calendar: ProjectCalendar = serde_json::from_slice(&body).unwrap();
+    assert_eq!(calendar.working_days, WEEKDAYS);
+
+    let source = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nDTSTART;VALUE=DATE:20250310\nSUMMARY:Fiesta\nEND:VEVENT\nEND:VCALENDAR\n";
+    assert_eq!(
+        import_holidays(&app, &member_token, &project_id, source).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        import_holidays(&app, &token, &project_id, "no es un calendario").await,
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        import_holidays(&app, &token, &project_id, source).await,
+        StatusCode::OK
+    );
+
+    // //* Dos días laborables desde el viernes 7 saltan el fin de semana y el festivo del lunes
+    let task_id = create_task_for_project(&app, &token, &project_id, None).await;
+    let range_uri = format!("/api/tasks/{}/date-range", task_id);
+    let (status, _) = send_request(
+        &app,
+        "POST",
+        range_uri.clone(),
+        &token,
+        json!({
+            "start_date": "2025-03-07T00:00:00Z",
+            "end_date": "2025-03-12T00:00:00Z",
+            "working_days": 2,
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, body) = send_request(
+        &app,
+        "POST",
+        range_uri,
+        &token,
+        json!({"start_date": "2025-03-07T00:00:00Z", "working_days": 2}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let range: DateRange = serde_json::from_slice(&body).unwrap();
+    assert_eq!(range.end_date, Some(day(12)));
+
+    // //! Una tarea que acaba en sábado no vence hasta el siguiente día laborable
+    let weekend_task = create_task_for_project(&app, &token, &project_id, None).await;
+    let (status, _) = send_request(
+        &app,
+        "POST",
+        format!("/api/tasks/{}/date-range", weekend_task),
+        &token,
+        json!({"start_date": "2025-03-03T00:00:00Z", "end_date": "2025-03-08T00:00:00Z"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = send_request(
+        &app,
+        "GET",
+        format!("/api/projects/{}/overdue", project_id),
+        &member_token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let overdue: Vec<OverdueTask> = serde_json::from_slice(&body).unwrap();
+    let due: Vec<_> = overdue
+        .iter()
+        .map(|task| (task.task_id.as_str(), task.due_at))
+        .collect();
+    assert_eq!(
+        due,
+        [
+            (weekend_task.as_str(), day(11)),
+            (task_id.as_str(), day(12))
+        ]
+    );
+    assert!(overdue[0].overdue_working_days > overdue[1].overdue_working_days);
+}
@@ -12,7 +12,7 @@ use crate::{
         create_project_for_user, create_task_for_project, get_auth_token, get_auth_token_and_id,
         setup_app,
     },
-    models::comment_model::CommentData,
+    models::comment_model::{CommentData, CommentPage},
 };
 
 #[tokio::test]
@@ -91,11 +91,11 @@ async fn test_comment_edit_and_delete_permissions() {
         .await
         .unwrap();
 
-    let final_comments: Vec<CommentData> =
+    let final_comments: CommentPage =
         serde_json::from_slice(&to_bytes(list_resp.into_body(), usize::MAX).await.unwrap())
             .unwrap();
     assert!(
-        final_comments.is_empty(),
+        final_comments.items.is_empty(),
         "El comentario no debería existir después de eliminarlo"
     );
 }
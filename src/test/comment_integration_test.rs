@@ -12,7 +12,7 @@ use crate::{
             add_member_to_project, get_auth_token, get_auth_token_and_id, setup_app,
         },
     },
-    models::comment_model::CommentData,
+    models::comment_model::CommentPage,
 };
 
 #[tokio::test]
@@ -93,9 +93,10 @@ async fn test_comment_flow_and_permissions() {
         .await
         .unwrap();
     assert_eq!(list_resp.status(), StatusCode::OK);
-    let comments: Vec<CommentData> =
+    let page: CommentPage =
         serde_json::from_slice(&to_bytes(list_resp.into_body(), usize::MAX).await.unwrap())
             .unwrap();
+    let comments = page.items;
     assert_eq!(comments.len(), 2);
     assert_eq!(comments[0].content, "Soy el dueño");
     assert_eq!(comments[0].author.username, "owner_comm");
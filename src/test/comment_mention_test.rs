@@ -0,0 +1,106 @@
+use axum::{Router, http::StatusCode};
+use bson::uuid;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    helpers::helper_setup_app::{
+        add_member_to_project, create_project_for_user, create_task_for_project,
+        get_auth_token_and_id, send_request, setup_app,
+    },
+    models::{
+        comment_model::{CommentData, CommentPage},
+        notification_model::{NotificationKind, NotificationPage},
+        task_model::Task,
+    },
+};
+
+// Avisos de mención en la bandeja del usuario
+async fn mention_count(app: &Router, token: &str) -> usize {
+    let (status, body) = send_request(
+        app,
+        "GET",
+        "/api/notifications".to_string(),
+        token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let page: NotificationPage = serde_json::from_slice(&body).unwrap();
+    page.items
+        .iter()
+        .filter(|notification| notification.kind == NotificationKind::Mentioned)
+        .count()
+}
+
+#[tokio::test]
+async fn test_comment_mentions() {
+    let app = setup_app().await;
+
+    let owner_email = format!("mention-owner-{}@test.com", Uuid::new());
+    let (owner_token, owner_id) = get_auth_token_and_id(&app, "mention_owner", &owner_email).await;
+    let member_email = format!("mention-member-{}@test.com", Uuid::new());
+    let (member_token, member_id) =
+        get_auth_token_and_id(&app, "mention_member", &member_email).await;
+    let outsider_email = format!("mention-outsider-{}@test.com", Uuid::new());
+    get_auth_token_and_id(&app, "mention_outsider", &outsider_email).await;
+
+    let project_id = create_project_for_user(&app, &owner_token, "MENT").await;
+    add_member_to_project(&app, &owner_token, &project_id, &member_email).await;
+    let task_id = create_task_for_project(&app, &owner_token, &project_id, None).await;
+
+    // //* Solo se resuelven los miembros del proyecto, sin distinguir mayúsculas
+    let (status, body) = send_request(
+        &app,
+        "POST",
+        format!("/api/tasks/{}/comments", task_id),
+        &owner_token,
+        json!({"content": "@MENTION_MEMBER y @mention_outsider, ¿lo veis?"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let comment: CommentData = serde_json::from_slice(&body).unwrap();
+    assert_eq!(comment.mentions, [member_id.to_hex()]);
+
+    // //* El mencionado pasa a observar la tarea y recibe un aviso
+    let (_, body) = send_request(
+        &app,
+        "GET",
+        format!("/api/tasks/{}", task_id),
+        &owner_token,
+        json!({}),
+    )
+    .await;
+    let task: Task = serde_json::from_slice(&body).unwrap();
+    assert!(task.watchers.contains(&member_id));
+
+    assert_eq!(mention_count(&app, &member_token).await, 1);
+
+    // //* Al editar solo se avisa a los nuevos mencionados
+    let (status, body) = send_request(
+        &app,
+        "PATCH",
+        format!("/api/tasks/{}/comments/{}", task_id, comment.id),
+        &owner_token,
+        json!({"content": "@mention_member ya está; ahora también @mention_owner"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let comment: CommentData = serde_json::from_slice(&body).unwrap();
+    assert_eq!(comment.mentions, [member_id.to_hex(), owner_id.to_hex()]);
+    assert_eq!(mention_count(&app, &member_token).await, 1);
+    // //? Nadie recibe avisos de sus propias menciones
+    assert_eq!(mention_count(&app, &owner_token).await, 0);
+
+    // //* El listado de comentarios también devuelve las menciones
+    let (_, body) = send_request(
+        &app,
+        "GET",
+        format!("/api/tasks/{}/comments", task_id),
+        &member_token,
+        json!({}),
+    )
+    .await;
+    let comments: CommentPage = serde_json::from_slice(&body).unwrap();
+    assert_eq!(comments.items[0].mentions.len(), 2);
+}
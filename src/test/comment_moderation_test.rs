@@ -0,0 +1,181 @@
+use axum::{Router, http::StatusCode};
+use bson::uuid;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    helpers::helper_setup_app::{
+        add_member_to_project, create_project_for_user, create_task_for_project,
+        get_auth_token_and_id, send_request, setup_app,
+    },
+    models::{
+        comment_model::{CommentData, CommentPage, CommentRevisions},
+        search_model::{TextSearchHitKind, TextSearchResult},
+    },
+};
+
+async fn comments(app: &Router, token: &str, task_id: &str) -> Vec<CommentData> {
+    let (status, body) = send_request(
+        app,
+        "GET",
+        format!("/api/tasks/{}/comments", task_id),
+        token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let page: CommentPage = serde_json::from_slice(&body).unwrap();
+    page.items
+}
+
+// Comentarios que encuentra la búsqueda de texto completo
+async fn comment_hits(app: &Router, token: &str, q: &str) -> usize {
+    let (status, body) = send_request(
+        app,
+        "GET",
+        format!("/api/search/text?q={}", q),
+        token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let result: TextSearchResult = serde_json::from_slice(&body).unwrap();
+    result
+        .items
+        .iter()
+        .filter(|hit| hit.kind == TextSearchHitKind::Comment)
+        .count()
+}
+
+#[tokio::test]
+async fn test_comment_revisions_and_moderation() {
+    let app = setup_app().await;
+
+    let owner_email = format!("moderation-owner-{}@test.com", Uuid::new());
+    let (owner_token, _) = get_auth_token_and_id(&app, "moderation_owner", &owner_email).await;
+    let member_email = format!("moderation-member-{}@test.com", Uuid::new());
+    let (member_token, _) = get_auth_token_and_id(&app, "moderation_member", &member_email).await;
+
+    let project_id = create_project_for_user(&app, &owner_token, "MODER").await;
+    add_member_to_project(&app, &owner_token, &project_id, &member_email).await;
+    let task_id = create_task_for_project(&app, &owner_token, &project_id, None).await;
+
+    let (status, body) = send_request(
+        &app,
+        "POST",
+        format!("/api/tasks/{}/comments", task_id),
+        &member_token,
+        json!({"content": "Primera versión"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let comment: CommentData = serde_json::from_slice(&body).unwrap();
+    assert!(!comment.edited);
+    let comment_uri = format!("/api/tasks/{}/comments/{}", task_id, comment.id);
+
+    // //* Cada edición guarda la versión anterior
+    for content in ["Segunda versión", "Tercera versión"] {
+        let (status, body) = send_request(
+            &app,
+            "PATCH",
+            comment_uri.clone(),
+            &member_token,
+            json!({"content": content}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let comment: CommentData = serde_json::from_slice(&body).unwrap();
+        assert!(comment.edited);
+    }
+
+    let (status, body) = send_request(
+        &app,
+        "GET",
+        format!("{}/revisions", comment_uri),
+        &owner_token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let history: CommentRevisions = serde_json::from_slice(&body).unwrap();
+    assert_eq!(history.content, "Tercera versión");
+    let previous: Vec<_> = history
+        .revisions
+        .iter()
+        .map(|r| r.content.as_str())
+        .collect();
+    assert_eq!(previous, ["Primera versión", "Segunda versión"]);
+
+    // //! Solo los administradores del proyecto pueden moderar
+    let hide_uri = format!("{}/hide", comment_uri);
+    let (status, _) = send_request(
+        &app,
+        "POST",
+        hide_uri.clone(),
+        &member_token,
+        json!({"reason": "Fuera de tono"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = send_request(
+        &app,
+        "POST",
+        hide_uri.clone(),
+        &owner_token,
+        json!({"reason": "no"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = send_request(
+        &app,
+        "POST",
+        hide_uri.clone(),
+        &owner_token,
+        json!({"reason": "Fuera de tono"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let hidden: CommentData = serde_json::from_slice(&body).unwrap();
+    assert!(hidden.hidden);
+    assert_eq!(hidden.moderation_reason.as_deref(), Some("Fuera de tono"));
+
+    // //* Los miembros ven el comentario oculto, sin contenido ni motivo
+    let list = comments(&app, &member_token, &task_id).await;
+    assert!(list[0].hidden);
+    assert!(list[0].content.is_empty());
+    assert!(list[0].moderation_reason.is_none());
+    let list = comments(&app, &owner_token, &task_id).await;
+    assert_eq!(list[0].content, "Tercera versión");
+    // //! Ni la búsqueda de texto completo lo muestra a los miembros
+    assert_eq!(comment_hits(&app, &member_token, "Tercera").await, 0);
+    assert_eq!(comment_hits(&app, &owner_token, "Tercera").await, 1);
+
+    // //! El autor no puede editar ni consultar versiones de un comentario oculto
+    let (status, _) = send_request(
+        &app,
+        "PATCH",
+        comment_uri.clone(),
+        &member_token,
+        json!({"content": "Me retracto"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = send_request(
+        &app,
+        "GET",
+        format!("{}/revisions", comment_uri),
+        &member_token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // //* Al restaurarlo vuelve a verse para todos
+    let (status, _) = send_request(&app, "DELETE", hide_uri, &owner_token, json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    let list = comments(&app, &member_token, &task_id).await;
+    assert!(!list[0].hidden);
+    assert_eq!(list[0].content, "Tercera versión");
+    assert_eq!(comment_hits(&app, &member_token, "Tercera").await, 1);
+}
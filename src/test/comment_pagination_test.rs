@@ -0,0 +1,190 @@
+use axum::{Router, http::StatusCode};
+use bson::uuid;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    helpers::helper_setup_app::{
+        create_project_for_user, create_task_for_project, get_auth_token_and_id, send_request,
+        setup_app,
+    },
+    models::{
+        comment_model::{CommentData, CommentPage},
+        task_model::TaskPage,
+    },
+};
+
+async fn list_comments(app: &Router, token: &str, task_id: &str, query: &str) -> CommentPage {
+    let (status, body) = send_request(
+        app,
+        "GET",
+        format!("/api/tasks/{}/comments?{}", task_id, query),
+        token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "Listado con '{}' falló", query);
+    serde_json::from_slice(&body).unwrap()
+}
+
+fn contents(page: &CommentPage) -> Vec<&str> {
+    page.items
+        .iter()
+        .map(|comment| comment.content.as_str())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_comment_pagination_and_sync() {
+    let app = setup_app().await;
+
+    let email = format!("comment-page-{}@test.com", Uuid::new());
+    let (token, _) = get_auth_token_and_id(&app, "comment_page_user", &email).await;
+    let project_id = create_project_for_user(&app, &token, "CPAGE").await;
+    let task_id = create_task_for_project(&app, &token, &project_id, None).await;
+    let quiet_task_id = create_task_for_project(&app, &token, &project_id, None).await;
+
+    let mut comments: Vec<CommentData> = Vec::new();
+    for content in ["uno", "dos", "tres", "cuatro", "cinco"] {
+        let (status, body) = send_request(
+            &app,
+            "POST",
+            format!("/api/tasks/{}/comments", task_id),
+            &token,
+            json!({"content": content}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        comments.push(serde_json::from_slice(&body).unwrap());
+    }
+    let (status, _) = send_request(
+        &app,
+        "POST",
+        format!("/api/tasks/{}/comments", task_id),
+        &token,
+        json!({"content": "respuesta", "parent_id": comments[1].id}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // //* Hacia delante: las respuestas van dentro de su hilo y no cuentan para el límite
+    let first = list_comments(&app, &token, &task_id, "limit=2").await;
+    assert_eq!(first.total, 5);
+    assert_eq!(contents(&first), ["uno", "dos"]);
+    assert_eq!(first.items[1].replies[0].content, "respuesta");
+    assert!(first.prev_cursor.is_none());
+
+    let second = list_comments(
+        &app,
+        &token,
+        &task_id,
+        &format!("limit=2&after={}", first.next_cursor.unwrap()),
+    )
+    .await;
+    assert_eq!(contents(&second), ["tres", "cuatro"]);
+
+    let last = list_comments(
+        &app,
+        &token,
+        &task_id,
+        &format!("limit=2&after={}", second.next_cursor.unwrap()),
+    )
+    .await;
+    assert_eq!(contents(&last), ["cinco"]);
+    assert!(last.next_cursor.is_none());
+
+    // //* Hacia atrás desde la última página
+    let back = list_comments(
+        &app,
+        &token,
+        &task_id,
+        &format!("limit=2&before={}", last.prev_cursor.unwrap()),
+    )
+    .await;
+    assert_eq!(contents(&back), ["tres", "cuatro"]);
+    assert!(back.prev_cursor.is_some());
+    assert!(back.next_cursor.is_some());
+
+    // //! Parámetros incompatibles
+    let (status, _) = send_request(
+        &app,
+        "GET",
+        format!(
+            "/api/tasks/{}/comments?after={}&since={}",
+            task_id,
+            back.next_cursor.unwrap(),
+            first
+                .synced_at
+                .to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+        ),
+        &token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // //* Sincronización incremental: ediciones, reacciones y borrados desde `synced_at`
+    let since = list_comments(&app, &token, &task_id, "")
+        .await
+        .synced_at
+        .to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+
+    let (status, _) = send_request(
+        &app,
+        "PATCH",
+        format!("/api/tasks/{}/comments/{}", task_id, comments[0].id),
+        &token,
+        json!({"content": "uno (editado)"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send_request(
+        &app,
+        "POST",
+        format!(
+            "/api/tasks/{}/comments/{}/reactions",
+            task_id, comments[1].id
+        ),
+        &token,
+        json!({"emoji": "eyes"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send_request(
+        &app,
+        "DELETE",
+        format!("/api/tasks/{}/comments/{}", task_id, comments[4].id),
+        &token,
+        json!({}),
+    )
+    .await;
+    assert!(status.is_success());
+
+    let changes = list_comments(&app, &token, &task_id, &format!("since={}", since)).await;
+    assert_eq!(contents(&changes), ["uno (editado)", "dos"]);
+    assert_eq!(changes.items[1].reply_count, 1);
+    assert_eq!(changes.items[1].reactions[0].emoji, "eyes");
+    assert_eq!(changes.deleted_ids, [comments[4].id.clone()]);
+    assert_eq!(changes.total, 4);
+
+    // //* El listado de tareas incluye el número de comentarios
+    let (status, body) = send_request(
+        &app,
+        "GET",
+        format!("/api/projects/{}/tasks", project_id),
+        &token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let page: TaskPage = serde_json::from_slice(&body).unwrap();
+    let count = |id: &str| {
+        page.items
+            .iter()
+            .find(|task| task.id.unwrap().to_hex() == id)
+            .and_then(|task| task.comment_count)
+    };
+    assert_eq!(count(&task_id), Some(5));
+    assert_eq!(count(&quiet_task_id), Some(0));
+}
@@ -0,0 +1,140 @@
+use axum::{Router, http::StatusCode};
+use bson::uuid;
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use crate::{
+    helpers::helper_setup_app::{
+        create_project_for_user, create_task_for_project, get_auth_token_and_id, send_request,
+        setup_app,
+    },
+    models::comment_model::{CommentData, CommentPage},
+};
+
+async fn comment(app: &Router, token: &str, task_id: &str, body: Value) -> (StatusCode, Vec<u8>) {
+    send_request(
+        app,
+        "POST",
+        format!("/api/tasks/{}/comments", task_id),
+        token,
+        body,
+    )
+    .await
+}
+
+async fn threads(app: &Router, token: &str, task_id: &str) -> Vec<CommentData> {
+    let (status, body) = send_request(
+        app,
+        "GET",
+        format!("/api/tasks/{}/comments", task_id),
+        token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let page: CommentPage = serde_json::from_slice(&body).unwrap();
+    page.items
+}
+
+#[tokio::test]
+async fn test_comment_threads() {
+    let app = setup_app().await;
+
+    let email = format!("thread-{}@test.com", Uuid::new());
+    let (token, _) = get_auth_token_and_id(&app, "thread_user", &email).await;
+    let project_id = create_project_for_user(&app, &token, "THREAD").await;
+    let task_id = create_task_for_project(&app, &token, &project_id, None).await;
+    let other_task_id = create_task_for_project(&app, &token, &project_id, None).await;
+
+    let (status, body) = comment(&app, &token, &task_id, json!({"content": "¿Qué opináis?"})).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let parent: CommentData = serde_json::from_slice(&body).unwrap();
+
+    // //* Dos respuestas al comentario
+    let mut reply_ids = Vec::new();
+    for content in ["De acuerdo", "Yo no"] {
+        let (status, body) = comment(
+            &app,
+            &token,
+            &task_id,
+            json!({"content": content, "parent_id": parent.id}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let reply: CommentData = serde_json::from_slice(&body).unwrap();
+        assert_eq!(reply.parent_id.as_ref(), Some(&parent.id));
+        reply_ids.push(reply.id);
+    }
+
+    // //! Solo hay un nivel de respuestas y el padre debe ser de la misma tarea
+    let (status, _) = comment(
+        &app,
+        &token,
+        &task_id,
+        json!({"content": "Respuesta anidada", "parent_id": reply_ids[0]}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = comment(
+        &app,
+        &token,
+        &other_task_id,
+        json!({"content": "Otra tarea", "parent_id": parent.id}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let list = threads(&app, &token, &task_id).await;
+    assert_eq!(list.len(), 1);
+    assert_eq!(list[0].reply_count, 2);
+    assert_eq!(list[0].replies[0].content, "De acuerdo");
+    assert_eq!(list[0].replies[1].content, "Yo no");
+
+    // //* Borrar un padre con respuestas deja una lápida
+    let (status, _) = send_request(
+        &app,
+        "DELETE",
+        format!("/api/tasks/{}/comments/{}", task_id, parent.id),
+        &token,
+        json!({}),
+    )
+    .await;
+    assert!(status.is_success());
+    let list = threads(&app, &token, &task_id).await;
+    assert!(list[0].deleted);
+    assert!(list[0].content.is_empty());
+    assert_eq!(list[0].reply_count, 2);
+
+    // //! La lápida no se puede editar ni recibir respuestas
+    let (status, _) = send_request(
+        &app,
+        "PATCH",
+        format!("/api/tasks/{}/comments/{}", task_id, parent.id),
+        &token,
+        json!({"content": "Resucitado"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = comment(
+        &app,
+        &token,
+        &task_id,
+        json!({"content": "Tarde", "parent_id": parent.id}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // //* Al borrar la última respuesta desaparece también la lápida
+    for reply_id in &reply_ids {
+        let (status, _) = send_request(
+            &app,
+            "DELETE",
+            format!("/api/tasks/{}/comments/{}", task_id, reply_id),
+            &token,
+            json!({}),
+        )
+        .await;
+        assert!(status.is_success());
+    }
+    assert!(threads(&app, &token, &task_id).await.is_empty());
+}
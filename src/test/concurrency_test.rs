@@ -0,0 +1,224 @@
+use axum::{
+    Router,
+    body::{Body, to_bytes},
+    http::{HeaderMap, HeaderValue, Request, StatusCode, header},
+};
+use bson::uuid;
+use serde_json::{Value, json};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use crate::{
+    helpers::helper_setup_app::{
+        create_project_for_user, create_task_for_project, get_auth_token, send_request, setup_app,
+    },
+    utils::etag::if_match,
+};
+
+// Petición con If-Match opcional; devuelve estado, ETag y cuerpo
+async fn send_with_if_match(
+    app: &Router,
+    method: &str,
+    uri: String,
+    token: &str,
+    if_match: Option<&str>,
+    body: Value,
+) -> (StatusCode, Option<String>, Value) {
+    let mut request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json");
+    if let Some(version) = if_match {
+        request = request.header(header::IF_MATCH, version);
+    }
+    let response = app
+        .clone()
+        .oneshot(request.body(Body::from(body.to_string())).unwrap())
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let etag = response
+        .headers()
+        .get(header::ETAG)
+        .map(|value| value.to_str().unwrap().to_string());
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (
+        status,
+        etag,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+#[test]
+fn test_if_match_parsing() {
+    let parse = |value: &str| {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_MATCH, HeaderValue::from_str(value).unwrap());
+        if_match(&headers)
+    };
+
+    assert_eq!(if_match(&HeaderMap::new()).unwrap(), None);
+    assert_eq!(parse("*").unwrap(), None);
+    assert_eq!(parse("\"3\"").unwrap(), Some(3));
+    assert_eq!(parse("W/\"7\"").unwrap(), Some(7));
+    assert!(parse("\"abc\"").is_err());
+}
+
+#[tokio::test]
+async fn test_task_update_with_stale_etag_is_rejected() {
+    let app = setup_app().await;
+
+    let email = format!("etag-owner-{}@test.com", Uuid::new());
+    let token = get_auth_token(&app, "etag_owner", &email).await;
+    let project_id = create_project_for_user(&app, &token, "ETAG").await;
+    let task_id = create_task_for_project(&app, &token, &project_id, None).await;
+
+    let (status, etag, _) = send_with_if_match(
+        &app,
+        "GET",
+        format!("/api/tasks/{}", task_id),
+        &token,
+        None,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let etag = etag.expect("La tarea debe devolver ETag");
+    assert_eq!(etag, "\"0\"");
+
+    // //* El primer cliente guarda con la versión que leyó
+    let (status, new_etag, body) = send_with_if_match(
+        &app,
+        "PATCH",
+        format!("/api/tasks/{}", task_id),
+        &token,
+        Some(&etag),
+        json!({"title": "Cambio del primer cliente"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(new_etag.as_deref(), Some("\"1\""));
+    assert_eq!(body["version"], json!(1));
+
+    // //! El segundo cliente usa la versión vieja y recibe el documento actual
+    let (status, _, body) = send_with_if_match(
+        &app,
+        "PATCH",
+        format!("/api/tasks/{}", task_id),
+        &token,
+        Some(&etag),
+        json!({"title": "Cambio del segundo cliente"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+    assert_eq!(body["current"]["title"], json!("Cambio del primer cliente"));
+    assert_eq!(body["current"]["version"], json!(1));
+
+    // //? Sin If-Match la edición se aplica igual y sigue incrementando la versión
+    let (status, etag, _) = send_with_if_match(
+        &app,
+        "PATCH",
+        format!("/api/tasks/{}", task_id),
+        &token,
+        None,
+        json!({"title": "Cambio sin versión"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(etag.as_deref(), Some("\"2\""));
+
+    // //! Los cambios que no pasan por la edición (observadores, sprint, orden...) también
+    // //! cambian la versión
+    let (status, _) = send_request(
+        &app,
+        "POST",
+        format!("/api/tasks/{}/watch", task_id),
+        &token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _, body) = send_with_if_match(
+        &app,
+        "PATCH",
+        format!("/api/tasks/{}", task_id),
+        &token,
+        etag.as_deref(),
+        json!({"title": "Cambio con versión anterior a observar"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+    assert_eq!(body["current"]["version"], json!(3));
+}
+
+#[tokio::test]
+async fn test_project_and_comment_updates_check_version() {
+    let app = setup_app().await;
+
+    let email = format!("etag-project-{}@test.com", Uuid::new());
+    let token = get_auth_token(&app, "etag_project", &email).await;
+    let project_id = create_project_for_user(&app, &token, "ETAGP").await;
+    let task_id = create_task_for_project(&app, &token, &project_id, None).await;
+
+    let (status, etag, _) = send_with_if_match(
+        &app,
+        "PATCH",
+        format!("/api/projects/{}", project_id),
+        &token,
+        Some("\"0\""),
+        json!({"name": "Proyecto renombrado"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(etag.as_deref(), Some("\"1\""));
+
+    let (status, _, body) = send_with_if_match(
+        &app,
+        "PATCH",
+        format!("/api/projects/{}", project_id),
+        &token,
+        Some("\"0\""),
+        json!({"name": "Otro nombre"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+    assert_eq!(body["current"]["name"], json!("Proyecto renombrado"));
+
+    let (status, body) = send_request(
+        &app,
+        "POST",
+        format!("/api/tasks/{}/comments", task_id),
+        &token,
+        json!({"content": "Versión inicial"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let comment: Value = serde_json::from_slice(&body).unwrap();
+    let comment_id = comment["id"].as_str().unwrap();
+
+    let (status, _, body) = send_with_if_match(
+        &app,
+        "PATCH",
+        format!("/api/tasks/{}/comments/{}", task_id, comment_id),
+        &token,
+        Some("\"5\""),
+        json!({"content": "Edición con versión vieja"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+    assert_eq!(body["current"]["content"], json!("Versión inicial"));
+
+    let (status, etag, _) = send_with_if_match(
+        &app,
+        "PATCH",
+        format!("/api/tasks/{}/comments/{}", task_id, comment_id),
+        &token,
+        Some("\"0\""),
+        json!({"content": "Edición correcta"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(etag.as_deref(), Some("\"1\""));
+}
@@ -0,0 +1,275 @@
+use axum::{Router, http::StatusCode};
+use bson::{Bson, oid::ObjectId, uuid};
+use chrono::Utc;
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use crate::{
+    helpers::helper_setup_app::{
+        create_project_for_user, get_auth_token_and_id, send_request, setup_app,
+    },
+    models::{
+        custom_field_model::{CustomFieldDefinition, CustomFieldType},
+        task_model::{Task, TaskPage},
+    },
+};
+
+fn definition(field_type: CustomFieldType) -> CustomFieldDefinition {
+    CustomFieldDefinition {
+        id: None,
+        project_id: ObjectId::new(),
+        key: "campo".to_string(),
+        name: "Campo".to_string(),
+        field_type,
+        required: false,
+        options: Vec::new(),
+        min: None,
+        max: None,
+        max_length: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+#[test]
+fn test_custom_field_value_coercion() {
+    let mut number = definition(CustomFieldType::Number);
+    number.min = Some(0.0);
+    number.max = Some(10.0);
+    assert_eq!(number.coerce_value(&json!(3)), Ok(Some(Bson::Double(3.0))));
+    assert!(number.coerce_value(&json!(11)).is_err());
+    assert!(number.coerce_value(&json!("3")).is_err());
+    assert_eq!(number.coerce_value(&Value::Null), Ok(None));
+
+    let date = definition(CustomFieldType::Date);
+    assert_eq!(
+        date.coerce_value(&json!("2025-03-01")),
+        Ok(Some(Bson::String("2025-03-01".to_string())))
+    );
+    assert!(date.coerce_value(&json!("01/03/2025")).is_err());
+
+    let mut select = definition(CustomFieldType::MultiSelect);
+    select.options = vec!["Chrome".to_string(), "Firefox".to_string()];
+    assert!(select.check_settings().is_ok());
+    assert_eq!(
+        select.coerce_value(&json!(["Firefox", "Firefox"])),
+        Ok(Some(Bson::Array(vec![Bson::String("Firefox".to_string())])))
+    );
+    assert!(select.coerce_value(&json!(["Safari"])).is_err());
+
+    let mut text = definition(CustomFieldType::Text);
+    text.max_length = Some(5);
+    assert!(text.coerce_value(&json!("demasiado largo")).is_err());
+
+    // //! La configuración debe corresponder al tipo
+    text.options = vec!["A".to_string()];
+    assert!(text.check_settings().is_err());
+    assert!(
+        definition(CustomFieldType::SingleSelect)
+            .check_settings()
+            .is_err()
+    );
+}
+
+async fn create_field(app: &Router, token: &str, project_id: &str, body: Value) -> StatusCode {
+    let (status, _) = send_request(
+        app,
+        "POST",
+        format!("/api/projects/{}/custom-fields", project_id),
+        token,
+        body,
+    )
+    .await;
+    status
+}
+
+async fn list_tasks(app: &Router, token: &str, project_id: &str, query: &str) -> TaskPage {
+    let (status, body) = send_request(
+        app,
+        "GET",
+        format!("/api/projects/{}/tasks?{}", project_id, query),
+        token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "Listado con '{}' falló", query);
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn test_custom_fields_on_tasks() {
+    let app = setup_app().await;
+
+    let email = format!("cf-owner-{}@test.com", Uuid::new());
+    let (token, user_id) = get_auth_token_and_id(&app, "cf_owner", &email).await;
+    let project_id = create_project_for_user(&app, &token, "CUSTOM").await;
+
+    for body in [
+        json!({"key": "customer", "name": "Cliente", "field_type": "text", "required": true}),
+        json!({"key": "severity", "name": "Severidad", "field_type": "single_select",
+               "options": ["S1", "S2", "S3"]}),
+        json!({"key": "impact", "name": "Impacto", "field_type": "number", "min": 0, "max": 100}),
+        json!({"key": "release", "name": "Release", "field_type": "date"}),
+        json!({"key": "qa", "name": "QA", "field_type": "user"}),
+    ] {
+        assert_eq!(
+            create_field(&app, &token, &project_id, body).await,
+            StatusCode::CREATED
+        );
+    }
+    // //! La clave es única dentro del proyecto
+    assert_eq!(
+        create_field(
+            &app,
+            &token,
+            &project_id,
+            json!({"key": "customer", "name": "Otro", "field_type": "text"}),
+        )
+        .await,
+        StatusCode::CONFLICT
+    );
+
+    // //! Validaciones al crear tareas
+    for custom_fields in [
+        json!({"severity": "S1"}),
+        json!({"customer": "ACME", "severity": "S9"}),
+        json!({"customer": "ACME", "impact": 500}),
+        json!({"customer": "ACME", "qa": ObjectId::new().to_hex()}),
+        json!({"customer": "ACME", "color": "rojo"}),
+    ] {
+        let (status, _) = send_request(
+            &app,
+            "POST",
+            format!("/api/projects/{}/tasks", project_id),
+            &token,
+            json!({"title": "Tarea inválida", "custom_fields": custom_fields}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    let mut task_ids = Vec::new();
+    for (title, custom_fields) in [
+        (
+            "Caída del login",
+            json!({"customer": "ACME", "severity": "S1", "impact": 80,
+                   "release": "2025-04-01", "qa": user_id.to_hex()}),
+        ),
+        (
+            "Texto mal traducido",
+            json!({"customer": "Globex", "severity": "S3", "impact": 5}),
+        ),
+        (
+            "Lentitud en informes",
+            json!({"customer": "ACME Iberia", "severity": "S2", "impact": 40,
+                   "release": "2025-05-15"}),
+        ),
+    ] {
+        let (status, body) = send_request(
+            &app,
+            "POST",
+            format!("/api/projects/{}/tasks", project_id),
+            &token,
+            json!({"title": title, "custom_fields": custom_fields}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let task: Task = serde_json::from_slice(&body).unwrap();
+        task_ids.push(task.id.unwrap());
+    }
+
+    // //? Filtros por tipo
+    let page = list_tasks(&app, &token, &project_id, "cf.customer=acme").await;
+    assert_eq!(page.total, 2);
+    let page = list_tasks(&app, &token, &project_id, "cf.severity=S1,S2").await;
+    assert_eq!(page.total, 2);
+    let page = list_tasks(&app, &token, &project_id, "cf.impact=10..").await;
+    assert_eq!(page.total, 2);
+    let page = list_tasks(&app, &token, &project_id, "cf.release=..2025-04-30").await;
+    assert_eq!(page.total, 1);
+    let page = list_tasks(&app, &token, &project_id, "cf.qa=me").await;
+    assert_eq!(page.items[0].title, "Caída del login");
+
+    // //? Orden por campo personalizado, paginado de uno en uno
+    let mut titles = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let query = match &cursor {
+            Some(cursor) => format!("sort=-cf.impact&limit=1&cursor={}", cursor),
+            None => "sort=-cf.impact&limit=1".to_string(),
+        };
+        let page = list_tasks(&app, &token, &project_id, &query).await;
+        titles.extend(page.items.into_iter().map(|task| task.title));
+        cursor = page.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+    assert_eq!(
+        titles,
+        [
+            "Caída del login",
+            "Lentitud en informes",
+            "Texto mal traducido"
+        ]
+    );
+
+    for query in ["cf.color=rojo", "cf.impact=mucho", "sort=cf.color"] {
+        let (status, _) = send_request(
+            &app,
+            "GET",
+            format!("/api/projects/{}/tasks?{}", project_id, query),
+            &token,
+            json!({}),
+        )
+        .await;
+        assert_eq!(
+            status,
+            StatusCode::BAD_REQUEST,
+            "'{}' debería fallar",
+            query
+        );
+    }
+
+    // //! Al editar no se puede vaciar un campo obligatorio, pero sí uno opcional
+    let (status, _) = send_request(
+        &app,
+        "PATCH",
+        format!("/api/tasks/{}", task_ids[1]),
+        &token,
+        json!({"custom_fields": {"customer": null}}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = send_request(
+        &app,
+        "PATCH",
+        format!("/api/tasks/{}", task_ids[1]),
+        &token,
+        json!({"custom_fields": {"severity": null, "impact": 7}}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let task: Task = serde_json::from_slice(&body).unwrap();
+    assert!(task.custom_fields.get("severity").is_none());
+    assert_eq!(task.custom_fields.get_f64("impact").unwrap(), 7.0);
+    assert_eq!(task.custom_fields.get_str("customer").unwrap(), "Globex");
+
+    // //? Eliminar la definición borra los valores de las tareas
+    let (status, _) = send_request(
+        &app,
+        "DELETE",
+        format!("/api/projects/{}/custom-fields/impact", project_id),
+        &token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let page = list_tasks(&app, &token, &project_id, "").await;
+    assert!(
+        page.items
+            .iter()
+            .all(|task| task.custom_fields.get("impact").is_none())
+    );
+}
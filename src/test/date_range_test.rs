@@ -0,0 +1,143 @@
+use axum::http::StatusCode;
+use bson::{doc, oid::ObjectId, uuid};
+use chrono::{TimeZone, Utc};
+use serde_json::json;
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    db::DatabaseState,
+    helpers::helper_setup_app::{
+        create_project_for_user, create_task_for_project, get_auth_token_and_id, send_request,
+        setup_app,
+    },
+    models::task_model::{DateRange, DateRangeProblem},
+    services::date_range_service::DateRangeService,
+};
+
+#[test]
+fn test_date_range_validation() {
+    let day = |d| Some(Utc.with_ymd_and_hms(2025, 3, d, 0, 0, 0).unwrap());
+    let range = |start, end| DateRange {
+        task_id: ObjectId::new(),
+        start_date: start,
+        end_date: end,
+    };
+
+    assert!(range(day(1), day(5)).validate().is_ok());
+    assert!(range(day(1), day(1)).validate().is_ok());
+    assert!(range(None, day(1)).validate().is_ok());
+
+    let error = range(day(5), day(1)).validate().unwrap_err().to_string();
+    assert!(error.contains(
+        "La fecha de fin (2025-03-01T00:00:00+00:00) no puede ser anterior a la de inicio (2025-03-05T00:00:00+00:00)"
+    ));
+
+    // //? El calendario laboral recorre el rango día a día, así que su longitud está acotada
+    let centuries = Some(Utc.with_ymd_and_hms(2525, 3, 1, 0, 0, 0).unwrap());
+    let error = range(day(1), centuries).validate().unwrap_err().to_string();
+    assert!(error.contains("no puede abarcar más de 7300 días"));
+}
+
+#[tokio::test]
+async fn test_date_range_invariants() {
+    let app = setup_app().await;
+
+    let email = format!("range-{}@test.com", Uuid::new());
+    let (token, _) = get_auth_token_and_id(&app, "range_user", &email).await;
+    let project_id = create_project_for_user(&app, &token, "RANGE").await;
+    let task_id = create_task_for_project(&app, &token, &project_id, None).await;
+    let uri = format!("/api/tasks/{}/date-range", task_id);
+
+    // //! El fin no puede ser anterior al inicio
+    let (status, _) = send_request(
+        &app,
+        "POST",
+        uri.clone(),
+        &token,
+        json!({"start_date": "2025-03-05T00:00:00Z", "end_date": "2025-03-01T00:00:00Z"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // //* Crear el rango varias veces a la vez deja un único rango
+    let body = json!({"start_date": "2025-03-01T00:00:00Z", "end_date": "2025-03-10T00:00:00Z"});
+    let (first, second) = tokio::join!(
+        send_request(&app, "POST", uri.clone(), &token, body.clone()),
+        send_request(&app, "POST", uri.clone(), &token, body.clone()),
+    );
+    assert_eq!(first.0, StatusCode::OK);
+    assert_eq!(second.0, StatusCode::OK);
+    let (_, body) = send_request(
+        &app,
+        "GET",
+        format!("/api/projects/{}/date-ranges", project_id),
+        &token,
+        json!({}),
+    )
+    .await;
+    let ranges: Vec<DateRange> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(ranges.len(), 1);
+
+    // //! Cambiar solo un lado también se comprueba contra el otro
+    for body in [
+        json!({"end_date": "2025-02-01T00:00:00Z"}),
+        json!({"start_date": "2025-04-01T00:00:00Z"}),
+    ] {
+        let (status, _) = send_request(&app, "PATCH", uri.clone(), &token, body).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    let (status, body) = send_request(
+        &app,
+        "PATCH",
+        uri.clone(),
+        &token,
+        json!({"end_date": "2025-03-20T00:00:00Z"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let range: DateRange = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        range.start_date,
+        Some(Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap())
+    );
+    assert_eq!(
+        range.end_date,
+        Some(Utc.with_ymd_and_hms(2025, 3, 20, 0, 0, 0).unwrap())
+    );
+
+    // //* El informe de migración lista los rangos inválidos guardados antes de validarlos
+    let db_state = Arc::new(
+        DatabaseState::init(
+            &std::env::var("DATABASE_URL")
+                .unwrap_or_else(|_| "mongodb://localhost:27017".to_string()),
+            "test_db",
+        )
+        .await
+        .expect("Fallo al conectar a la DB de prueba"),
+    );
+    let legacy_task_id = ObjectId::new();
+    db_state
+        .get_db()
+        .collection::<bson::Document>("task_date_ranges")
+        .insert_one(doc! {
+            "task_id": legacy_task_id,
+            "start_date": "2024-06-10T00:00:00Z",
+            "end_date": "2024-06-01T00:00:00Z",
+        })
+        .await
+        .unwrap();
+
+    let report = DateRangeService::new(db_state)
+        .migration_report()
+        .await
+        .unwrap();
+    let legacy = report
+        .iter()
+        .find(|range| range.task_id == legacy_task_id)
+        .expect("El rango inválido debería aparecer en el informe");
+    assert_eq!(legacy.problems, [DateRangeProblem::EndBeforeStart]);
+    assert!(!report.iter().any(|range| range.task_id.to_hex() == task_id));
+}
@@ -0,0 +1,147 @@
+use axum::http::StatusCode;
+use bson::{oid::ObjectId, uuid};
+use serde_json::json;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    db::DatabaseState,
+    helpers::helper_setup_app::{
+        add_member_to_project, create_project_for_user, create_task_for_project,
+        get_auth_token_and_id, send_request, setup_app,
+    },
+    models::notification_model::{EmailDelivery, NotificationPreferences},
+    services::{
+        email_notification_service::EmailNotificationService,
+        mail_service::{FileMailer, Mailer},
+    },
+    utils::email_templates::{digest_email, immediate_email},
+};
+
+#[test]
+fn test_email_templates_by_locale() {
+    let (subject, body) = immediate_email("en", "ana", "Web", "luis assigned you the task WEB-1");
+    assert_eq!(subject, "[Web] luis assigned you the task WEB-1");
+    assert!(body.starts_with("Hi ana,"));
+
+    let lines = vec!["uno".to_string(), "dos".to_string()];
+    let (subject, body) = digest_email("es", "ana", &lines);
+    assert_eq!(subject, "Tu resumen diario de notificaciones (2)");
+    assert!(body.contains("- uno\n- dos"));
+
+    // Un idioma desconocido usa las plantillas en español
+    let (subject, _) = digest_email("fr", "ana", &lines);
+    assert!(subject.starts_with("Tu resumen diario"));
+}
+
+#[tokio::test]
+async fn test_email_notifications() {
+    let app = setup_app().await;
+
+    let owner_email = format!("mail-owner-{}@test.com", Uuid::new());
+    let (owner_token, _) = get_auth_token_and_id(&app, "mail_owner", &owner_email).await;
+    let member_email = format!("mail-member-{}@test.com", Uuid::new());
+    let (member_token, member_id) = get_auth_token_and_id(&app, "mail_member", &member_email).await;
+
+    // //* Sin preferencias guardadas se usan las de por defecto
+    let (status, body) = send_request(
+        &app,
+        "GET",
+        "/api/me/notification-preferences".to_string(),
+        &member_token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let preferences: NotificationPreferences = serde_json::from_slice(&body).unwrap();
+    assert_eq!(preferences.locale, "es");
+    assert_eq!(preferences.mentioned, EmailDelivery::Immediate);
+
+    // //! Solo se aceptan los idiomas soportados
+    let (status, _) = send_request(
+        &app,
+        "PATCH",
+        "/api/me/notification-preferences".to_string(),
+        &member_token,
+        json!({"locale": "fr"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = send_request(
+        &app,
+        "PATCH",
+        "/api/me/notification-preferences".to_string(),
+        &member_token,
+        json!({"locale": "en", "task_assigned": "digest", "status_changed": "off"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let preferences: NotificationPreferences = serde_json::from_slice(&body).unwrap();
+    assert_eq!(preferences.task_assigned, EmailDelivery::Digest);
+    assert_eq!(preferences.mentioned, EmailDelivery::Immediate);
+
+    // //* Alta en el proyecto (inmediato), asignación (resumen) y cambio de estado (desactivado)
+    let project_id = create_project_for_user(&app, &owner_token, "MAIL").await;
+    add_member_to_project(&app, &owner_token, &project_id, &member_email).await;
+    let task_id = create_task_for_project(&app, &owner_token, &project_id, Some(member_id)).await;
+    let (status, _) = send_request(
+        &app,
+        "PATCH",
+        format!("/api/tasks/{}", task_id),
+        &owner_token,
+        json!({"status": "Done"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let db_state = Arc::new(
+        DatabaseState::init(
+            &std::env::var("DATABASE_URL")
+                .unwrap_or_else(|_| "mongodb://localhost:27017".to_string()),
+            "test_db",
+        )
+        .await
+        .expect("Fallo al conectar a la DB de prueba"),
+    );
+    let outbox = std::env::temp_dir().join(format!("outbox-{}", ObjectId::new().to_hex()));
+    let mailer = Arc::new(FileMailer::new(&outbox));
+    let email_service = EmailNotificationService::new(db_state, mailer.clone() as Arc<dyn Mailer>);
+
+    // //* Los inmediatos salen uno a uno, en el idioma del usuario
+    assert_eq!(
+        email_service
+            .send_pending(EmailDelivery::Immediate)
+            .await
+            .unwrap(),
+        1
+    );
+    let sent = mailer.sent().unwrap();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].to, member_email);
+    assert!(sent[0].body.contains("mail_owner added you to the project"));
+
+    // //* El resumen agrupa los pendientes y no se repite
+    assert_eq!(
+        email_service
+            .send_pending(EmailDelivery::Digest)
+            .await
+            .unwrap(),
+        1
+    );
+    let sent = mailer.sent().unwrap();
+    assert_eq!(sent.len(), 2);
+    assert_eq!(sent[1].subject, "Your daily notification digest (1)");
+    assert!(
+        sent[1]
+            .body
+            .contains("mail_owner assigned you the task MAIL-1")
+    );
+    assert!(!sent[1].body.contains("moved"));
+
+    for delivery in [EmailDelivery::Immediate, EmailDelivery::Digest] {
+        assert_eq!(email_service.send_pending(delivery).await.unwrap(), 0);
+    }
+
+    std::fs::remove_dir_all(&outbox).ok();
+}
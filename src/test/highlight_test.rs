@@ -0,0 +1,38 @@
+use crate::utils::highlight::{search_terms, snippet};
+
+#[test]
+fn test_snippet_marks_terms_and_escapes_html() {
+    let terms = search_terms("Login roto");
+    assert_eq!(terms, vec!["login", "roto"]);
+
+    assert_eq!(
+        snippet("El <b>login</b> está ROTO", &terms, 100),
+        "El &lt;b&gt;<mark>login</mark>&lt;/b&gt; está <mark>ROTO</mark>"
+    );
+}
+
+#[test]
+fn test_snippet_is_centered_on_first_match() {
+    let text = format!("{} despliegue final {}", "a".repeat(200), "b".repeat(200));
+    let result = snippet(&text, &search_terms("despliegue"), 60);
+
+    assert!(result.starts_with('…') && result.ends_with('…'));
+    assert!(result.contains("<mark>despliegue</mark>"));
+    assert_eq!(
+        result
+            .replace("<mark>", "")
+            .replace("</mark>", "")
+            .chars()
+            .count(),
+        62
+    );
+}
+
+#[test]
+fn test_snippet_without_match_starts_at_beginning() {
+    assert_eq!(
+        snippet("Texto corto", &search_terms("otra"), 100),
+        "Texto corto"
+    );
+    assert_eq!(snippet("abcdef", &[], 3), "abc…");
+}
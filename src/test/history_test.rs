@@ -0,0 +1,240 @@
+use axum::http::StatusCode;
+use bson::uuid;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    helpers::helper_setup_app::{
+        create_project_for_user, create_task_for_project, get_auth_token, send_request, setup_app,
+    },
+    models::{
+        history_model::{ActivityPage, HistoryAction, TaskHistoryEntry},
+        sprint_model::Sprint,
+    },
+};
+
+#[tokio::test]
+async fn test_task_history_and_project_activity() {
+    let app = setup_app().await;
+
+    let email = format!("history-owner-{}@test.com", Uuid::new());
+    let token = get_auth_token(&app, "history_owner", &email).await;
+    let project_id = create_project_for_user(&app, &token, "HIST").await;
+    let task_id = create_task_for_project(&app, &token, &project_id, None).await;
+
+    let (status, _) = send_request(
+        &app,
+        "PATCH",
+        format!("/api/tasks/{}", task_id),
+        &token,
+        json!({"title": "Título nuevo", "status": "InProgress"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = send_request(
+        &app,
+        "POST",
+        format!("/api/tasks/{}/comments", task_id),
+        &token,
+        json!({"content": "Primer comentario"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, _) = send_request(
+        &app,
+        "POST",
+        format!("/api/tasks/{}/date-range", task_id),
+        &token,
+        json!({"start_date": "2025-03-01T00:00:00Z"}),
+    )
+    .await;
+    assert!(status.is_success());
+
+    let (status, body) = send_request(
+        &app,
+        "GET",
+        format!("/api/tasks/{}/history", task_id),
+        &token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let history: Vec<TaskHistoryEntry> = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(history[0].action, HistoryAction::Created);
+    let title_change = history
+        .iter()
+        .find(|entry| entry.field.as_deref() == Some("title"))
+        .unwrap();
+    assert_eq!(title_change.new_value, Some(json!("Título nuevo")));
+    assert!(title_change.old_value.is_some());
+    let status_change = history
+        .iter()
+        .find(|entry| entry.field.as_deref() == Some("status"))
+        .unwrap();
+    assert_eq!(status_change.old_value, Some(json!("ToDo")));
+    assert_eq!(status_change.new_value, Some(json!("InProgress")));
+    // //? Del comentario solo se guarda el id, nunca su texto
+    let comment_added = history
+        .iter()
+        .find(|entry| entry.action == HistoryAction::CommentAdded)
+        .unwrap();
+    assert!(comment_added.related_id.is_some());
+    assert!(comment_added.new_value.is_none());
+    assert!(history.iter().any(|entry| {
+        entry.action == HistoryAction::DateRangeChanged
+            && entry.field.as_deref() == Some("start_date")
+    }));
+
+    // //! El historial sigue disponible después de eliminar la tarea
+    let (status, _) = send_request(
+        &app,
+        "DELETE",
+        format!("/api/tasks/{}", task_id),
+        &token,
+        json!({}),
+    )
+    .await;
+    assert!(status.is_success());
+
+    let (status, body) = send_request(
+        &app,
+        "GET",
+        format!("/api/tasks/{}/history", task_id),
+        &token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let history: Vec<TaskHistoryEntry> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(history.last().unwrap().action, HistoryAction::Deleted);
+
+    // //? La actividad del proyecto va de la más reciente a la más antigua, paginada
+    let mut actions = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let query = match &cursor {
+            Some(cursor) => format!("limit=2&cursor={}", cursor),
+            None => "limit=2".to_string(),
+        };
+        let (status, body) = send_request(
+            &app,
+            "GET",
+            format!("/api/projects/{}/activity?{}", project_id, query),
+            &token,
+            json!({}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let page: ActivityPage = serde_json::from_slice(&body).unwrap();
+        actions.extend(page.items.into_iter().map(|entry| entry.action));
+        cursor = page.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+    assert_eq!(actions.len(), history.len());
+    assert_eq!(actions[0], HistoryAction::Deleted);
+    assert_eq!(*actions.last().unwrap(), HistoryAction::Created);
+
+    // //! Un usuario ajeno al proyecto no puede ver el historial
+    let stranger_email = format!("history-stranger-{}@test.com", Uuid::new());
+    let stranger_token = get_auth_token(&app, "history_stranger", &stranger_email).await;
+    let (status, _) = send_request(
+        &app,
+        "GET",
+        format!("/api/tasks/{}/history", task_id),
+        &stranger_token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_sprint_rank_watcher_and_estimate_changes_are_recorded() {
+    let app = setup_app().await;
+
+    let email = format!("history-side-{}@test.com", Uuid::new());
+    let token = get_auth_token(&app, "history_side", &email).await;
+    let project_id = create_project_for_user(&app, &token, "HSIDE").await;
+    let task_id = create_task_for_project(&app, &token, &project_id, None).await;
+    let other_id = create_task_for_project(&app, &token, &project_id, None).await;
+
+    let (_, body) = send_request(
+        &app,
+        "POST",
+        format!("/api/projects/{}/sprints", project_id),
+        &token,
+        json!({"name": "Sprint historial"}),
+    )
+    .await;
+    let sprint: Sprint = serde_json::from_slice(&body).unwrap();
+    let sprint_id = sprint.id.unwrap().to_hex();
+
+    let requests = [
+        (
+            "POST",
+            format!("/api/sprints/{}/tasks", sprint_id),
+            json!({"task_ids": [task_id]}),
+        ),
+        (
+            "POST",
+            format!("/api/tasks/{}/rank", task_id),
+            json!({"before_task_id": other_id}),
+        ),
+        ("DELETE", format!("/api/tasks/{}/watch", task_id), json!({})),
+        ("POST", format!("/api/tasks/{}/watch", task_id), json!({})),
+        (
+            "PATCH",
+            format!("/api/tasks/{}", task_id),
+            json!({"remaining_estimate_minutes": 120}),
+        ),
+        (
+            "POST",
+            format!("/api/tasks/{}/worklogs", task_id),
+            json!({"duration_minutes": 30}),
+        ),
+        (
+            "DELETE",
+            format!("/api/sprints/{}/tasks/{}", sprint_id, task_id),
+            json!({}),
+        ),
+    ];
+    for (method, uri, body) in requests {
+        let (status, _) = send_request(&app, method, uri, &token, body).await;
+        assert!(status.is_success());
+    }
+
+    let (_, body) = send_request(
+        &app,
+        "GET",
+        format!("/api/tasks/{}/history", task_id),
+        &token,
+        json!({}),
+    )
+    .await;
+    let history: Vec<TaskHistoryEntry> = serde_json::from_slice(&body).unwrap();
+    let changes = |field: &str| -> Vec<_> {
+        history
+            .iter()
+            .filter(|entry| entry.field.as_deref() == Some(field))
+            .map(|entry| (entry.old_value.clone(), entry.new_value.clone()))
+            .collect()
+    };
+
+    // //! Entrar y salir del sprint deja dos entradas con el id del sprint
+    let sprint = Some(json!(sprint_id));
+    assert_eq!(
+        changes("sprint_id"),
+        [(None, sprint.clone()), (sprint, None)]
+    );
+    assert_eq!(changes("rank").len(), 1);
+    assert!(!changes("watchers").is_empty());
+    assert_eq!(
+        changes("remaining_estimate_minutes").last(),
+        Some(&(Some(json!(120)), Some(json!(90))))
+    );
+}
@@ -0,0 +1,143 @@
+use chrono::{TimeZone, Utc};
+use mongodb::bson::{doc, oid::ObjectId};
+use std::collections::HashMap;
+
+use crate::utils::jql::{self, JqlContext};
+
+fn context(user: ObjectId, web: ObjectId, api: ObjectId) -> JqlContext {
+    JqlContext {
+        current_user: user,
+        now: Utc.with_ymd_and_hms(2025, 3, 10, 12, 0, 0).unwrap(),
+        projects: HashMap::from([("web".to_string(), web), ("api".to_string(), api)]),
+        users: HashMap::from([("ana".to_string(), user)]),
+        open_sprints: vec![],
+    }
+}
+
+#[test]
+fn test_compiles_cross_project_query() {
+    let (user, web, api) = (ObjectId::new(), ObjectId::new(), ObjectId::new());
+    let query = jql::parse(
+        "project in (WEB, api) AND status != Done AND assignee = currentUser() ORDER BY priority DESC",
+    )
+    .unwrap();
+
+    let compiled = jql::compile(&query, &context(user, web, api)).unwrap();
+    assert_eq!(
+        compiled.filter,
+        doc! {"$and": [
+            {"$and": [
+                {"project_id": {"$in": [web, api]}},
+                {"status": {"$ne": "Done"}},
+            ]},
+            {"assignee_id": user},
+        ]}
+    );
+    assert_eq!(compiled.sort, doc! {"_sort_0": -1, "_id": 1});
+}
+
+#[test]
+fn test_supports_not_empty_text_and_dates() {
+    let (user, web, api) = (ObjectId::new(), ObjectId::new(), ObjectId::new());
+    let query = jql::parse(
+        "NOT (assignee IS EMPTY OR summary ~ \"login\") and created >= -7d and priority in (High, 'urgent')",
+    )
+    .unwrap();
+
+    let compiled = jql::compile(&query, &context(user, web, api)).unwrap();
+    let since = Utc.with_ymd_and_hms(2025, 3, 3, 12, 0, 0).unwrap();
+    assert_eq!(
+        compiled.filter,
+        doc! {"$and": [
+            {"$and": [
+                {"$nor": [{"$or": [
+                    {"assignee_id": {"$in": [null, ""]}},
+                    {"title": {"$regex": "login", "$options": "i"}},
+                ]}]},
+                {"created_at": {"$gte": bson::DateTime::from_chrono(since)}},
+            ]},
+            {"priority": {"$in": ["High", "Urgent"]}},
+        ]}
+    );
+    // Sin ORDER BY se ordena por fecha de creación descendente
+    assert_eq!(compiled.sort, doc! {"created_at": -1, "_id": 1});
+}
+
+#[test]
+fn test_reports_syntax_errors_with_position() {
+    let cases = [
+        ("status = ", 10, "Se esperaba un valor"),
+        ("status Done", 8, "Se esperaba un operador"),
+        ("colour = red", 1, "Campo desconocido 'colour'"),
+        ("project in (WEB, API", 21, "Se esperaba ')' o ','"),
+        ("summary ~ \"abierto", 11, "Texto entre comillas sin cerrar"),
+        (
+            "status = Done status = ToDo",
+            15,
+            "Se esperaba AND, OR, ORDER BY o el final de la consulta",
+        ),
+        (
+            "created = 2024-01-01",
+            9,
+            "Operador no válido para el campo 'created'",
+        ),
+        ("status ! Done", 8, "Se esperaba '!=' o '!~'"),
+    ];
+
+    for (input, position, message) in cases {
+        let err = jql::parse(input).unwrap_err();
+        assert_eq!(
+            (err.position, err.message.as_str()),
+            (position, message),
+            "{}",
+            input
+        );
+    }
+}
+
+#[test]
+fn test_reports_unknown_values_with_position() {
+    let (user, web, api) = (ObjectId::new(), ObjectId::new(), ObjectId::new());
+    let ctx = context(user, web, api);
+
+    let cases = [
+        (
+            "project = MOBILE",
+            11,
+            "Proyecto desconocido o sin acceso 'MOBILE'",
+        ),
+        ("status = Blocked", 10, "Estado desconocido 'Blocked'"),
+        ("assignee = nadie", 12, "Usuario desconocido 'nadie'"),
+        (
+            "updated > ayer",
+            11,
+            "Fecha inválida 'ayer' (usa AAAA-MM-DD, RFC 3339 o relativa como -7d)",
+        ),
+        (
+            "created > -99999999999d",
+            11,
+            "La fecha relativa '-99999999999d' está fuera de rango",
+        ),
+        (
+            "created < +9999999999999w",
+            11,
+            "La fecha relativa '+9999999999999w' está fuera de rango",
+        ),
+        (
+            "assignee = someone()",
+            12,
+            "Función desconocida 'someone()'",
+        ),
+    ];
+
+    for (input, position, message) in cases {
+        let query = jql::parse(input).unwrap();
+        let err = jql::compile(&query, &ctx).err().unwrap();
+        assert_eq!(
+            (err.position, err.message.as_str()),
+            (position, message),
+            "{}",
+            input
+        );
+    }
+}
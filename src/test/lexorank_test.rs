@@ -0,0 +1,66 @@
+use crate::utils::lexorank::{between, evenly_spaced, is_valid_rank, needs_rebalance};
+
+#[test]
+fn test_between_produces_ordered_ranks() {
+    let cases = [
+        (None, None),
+        (None, Some("1")),
+        (Some("a"), None),
+        (Some("a"), Some("b")),
+        (Some("az"), Some("b")),
+        (Some("ab"), Some("ac5")),
+        (Some("b5"), Some("c")),
+        (Some("zz"), None),
+    ];
+
+    for (prev, next) in cases {
+        let rank = between(prev, next).unwrap();
+        assert!(is_valid_rank(&rank), "rango inválido: {}", rank);
+        if let Some(prev) = prev {
+            assert!(
+                prev < rank.as_str(),
+                "{} debería ir después de {}",
+                rank,
+                prev
+            );
+        }
+        if let Some(next) = next {
+            assert!(
+                rank.as_str() < next,
+                "{} debería ir antes de {}",
+                rank,
+                next
+            );
+        }
+    }
+}
+
+#[test]
+fn test_between_rejects_inverted_neighbours() {
+    assert!(between(Some("b"), Some("a")).is_err());
+    assert!(between(Some("a"), Some("a")).is_err());
+}
+
+#[test]
+fn test_repeated_insertions_grow_until_rebalance() {
+    // Insertar siempre justo después de "a" alarga el rango hasta pedir rebalanceo
+    let mut next = "b".to_string();
+    let mut inserted = 0;
+    while !needs_rebalance(&next) {
+        next = between(Some("a"), Some(&next)).unwrap();
+        inserted += 1;
+    }
+    assert!(inserted > 10);
+}
+
+#[test]
+fn test_evenly_spaced_ranks_are_sorted_and_short() {
+    let ranks = evenly_spaced(500);
+    assert_eq!(ranks.len(), 500);
+    assert!(ranks.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(
+        ranks
+            .iter()
+            .all(|rank| is_valid_rank(rank) && !needs_rebalance(rank))
+    );
+}
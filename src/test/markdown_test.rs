@@ -0,0 +1,121 @@
+use axum::http::StatusCode;
+use bson::uuid;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    helpers::helper_setup_app::{
+        add_member_to_project, create_project_for_user, create_task_for_project,
+        get_auth_token_and_id, send_request, setup_app,
+    },
+    models::{comment_model::CommentData, task_model::Task, user_model::UserData},
+    utils::markdown::render_markdown,
+};
+
+fn member(id: &str, username: &str) -> UserData {
+    UserData {
+        id: id.to_string(),
+        username: username.to_string(),
+        email: format!("{}@test.com", username),
+    }
+}
+
+#[test]
+fn test_render_markdown() {
+    assert_eq!(
+        render_markdown("**Hola** _mundo_\n\n- uno\n- ~~dos~~", &[]),
+        "<p><strong>Hola</strong> <em>mundo</em></p>\n<ul>\n<li>uno</li>\n<li><del>dos</del></li>\n</ul>\n"
+    );
+}
+
+#[test]
+fn test_render_markdown_sanitizes_html() {
+    let html = render_markdown(
+        "<script>alert(1)</script><b onclick=\"x()\">negrita</b>\n\n[pincha](javascript:alert(1)) <iframe src=\"https://evil\"></iframe>",
+        &[],
+    );
+    assert!(!html.contains("script"));
+    assert!(!html.contains("onclick"));
+    assert!(!html.contains("javascript:"));
+    assert!(!html.contains("iframe"));
+    // //? `b` no está en la lista de etiquetas permitidas; su texto se conserva
+    assert!(html.contains("negrita"));
+}
+
+#[test]
+fn test_render_markdown_autolinks() {
+    let members = [member("64b000000000000000000001", "ana.b")];
+    let html = render_markdown(
+        "@Ana.B revisa WEB-12 antes del 2024-10, @luis no es miembro.\n\n`WEB-3` y [WEB-4](https://x.test)",
+        &members,
+    );
+    assert!(html.contains(
+        r#"<a class="mention" href="/users/64b000000000000000000001" rel="noopener noreferrer">@Ana.B</a>"#
+    ));
+    assert!(html.contains(
+        r#"<a class="issue-key" href="/browse/WEB-12" rel="noopener noreferrer">WEB-12</a>"#
+    ));
+    assert!(!html.contains("/browse/2024-10"));
+    assert!(html.contains("@luis no es miembro"));
+    // //? Ni el código ni el texto de otros enlaces se tocan
+    assert!(html.contains("<code>WEB-3</code>"));
+    assert!(!html.contains("/browse/WEB-4"));
+}
+
+#[tokio::test]
+async fn test_markdown_in_api() {
+    let app = setup_app().await;
+
+    let owner_email = format!("markdown-owner-{}@test.com", Uuid::new());
+    let (owner_token, _) = get_auth_token_and_id(&app, "markdown_owner", &owner_email).await;
+    let member_email = format!("markdown-member-{}@test.com", Uuid::new());
+    let (_, member_id) = get_auth_token_and_id(&app, "markdown_member", &member_email).await;
+
+    let project_id = create_project_for_user(&app, &owner_token, "MDOWN").await;
+    add_member_to_project(&app, &owner_token, &project_id, &member_email).await;
+    let task_id = create_task_for_project(&app, &owner_token, &project_id, None).await;
+
+    // //* La descripción guarda el Markdown original y el HTML saneado
+    let (status, body) = send_request(
+        &app,
+        "PATCH",
+        format!("/api/tasks/{}", task_id),
+        &owner_token,
+        json!({"description": "# Pasos\n\nVer MDOWN-1 con @markdown_member<script>x</script>"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let task: Task = serde_json::from_slice(&body).unwrap();
+    assert!(task.description.unwrap().contains("<script>"));
+    let html = task.description_html.unwrap();
+    assert!(html.starts_with("<h1>Pasos</h1>"));
+    assert!(html.contains(r#"href="/browse/MDOWN-1""#));
+    assert!(html.contains(&format!(r#"href="/users/{}""#, member_id.to_hex())));
+    assert!(!html.contains("<script>"));
+
+    // //* Igual en los comentarios, también al editarlos
+    let (status, body) = send_request(
+        &app,
+        "POST",
+        format!("/api/tasks/{}/comments", task_id),
+        &owner_token,
+        json!({"content": "*Primera* versión"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let comment: CommentData = serde_json::from_slice(&body).unwrap();
+    assert_eq!(comment.content, "*Primera* versión");
+    assert_eq!(comment.content_html, "<p><em>Primera</em> versión</p>\n");
+
+    let (status, body) = send_request(
+        &app,
+        "PATCH",
+        format!("/api/tasks/{}/comments/{}", task_id, comment.id),
+        &owner_token,
+        json!({"content": "Gracias @markdown_member"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let comment: CommentData = serde_json::from_slice(&body).unwrap();
+    assert!(comment.content_html.contains(r#"class="mention""#));
+}
@@ -0,0 +1,155 @@
+use axum::{Router, http::StatusCode};
+use bson::{oid::ObjectId, uuid};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    handlers::websocket_handler::is_for_user,
+    helpers::helper_setup_app::{
+        add_member_to_project, create_project_for_user, create_task_for_project,
+        get_auth_token_and_id, send_request, setup_app,
+    },
+    models::notification_model::{NotificationCount, NotificationKind, NotificationPage},
+    utils::mentions::extract_mentions,
+};
+
+async fn inbox(app: &Router, token: &str, query: &str) -> NotificationPage {
+    let (status, body) = send_request(
+        app,
+        "GET",
+        format!("/api/notifications{}", query),
+        token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[test]
+fn test_extract_mentions() {
+    assert_eq!(
+        extract_mentions("@ana revisa esto con @Luis.perez y @ANA."),
+        ["ana", "Luis.perez"]
+    );
+    assert!(extract_mentions("escribe a ana@correo.com").is_empty());
+    assert!(extract_mentions("@ y nada más").is_empty());
+}
+
+#[test]
+fn test_websocket_message_target() {
+    let user_id = ObjectId::new();
+    let broadcast = json!({"event_type": "TASK_UPDATED"}).to_string();
+    let own = json!({"event_type": "NOTIFICATION_CREATED", "target_user_id": user_id.to_hex()})
+        .to_string();
+
+    assert!(is_for_user(&broadcast, None));
+    assert!(is_for_user(&broadcast, Some(ObjectId::new())));
+    assert!(is_for_user(&own, Some(user_id)));
+    assert!(!is_for_user(&own, Some(ObjectId::new())));
+    assert!(!is_for_user(&own, None));
+}
+
+#[tokio::test]
+async fn test_notification_inbox() {
+    let app = setup_app().await;
+
+    let owner_email = format!("notif-owner-{}@test.com", Uuid::new());
+    let (owner_token, _) = get_auth_token_and_id(&app, "notif_owner", &owner_email).await;
+    let member_email = format!("notif-member-{}@test.com", Uuid::new());
+    let (member_token, member_id) =
+        get_auth_token_and_id(&app, "notif_member", &member_email).await;
+
+    // //* Añadir un miembro le avisa
+    let project_id = create_project_for_user(&app, &owner_token, "NOTIF").await;
+    add_member_to_project(&app, &owner_token, &project_id, &member_email).await;
+    let page = inbox(&app, &member_token, "").await;
+    assert_eq!(page.unread_count, 1);
+    assert_eq!(page.items[0].kind, NotificationKind::AddedToProject);
+
+    // //* Asignar una tarea avisa al asignado, pero nadie recibe avisos de sí mismo
+    let task_id = create_task_for_project(&app, &owner_token, &project_id, Some(member_id)).await;
+    let page = inbox(&app, &member_token, "").await;
+    assert_eq!(page.unread_count, 2);
+    assert_eq!(page.items[0].kind, NotificationKind::TaskAssigned);
+    assert_eq!(
+        page.items[0].task_id.map(|id| id.to_hex()),
+        Some(task_id.clone())
+    );
+    assert_eq!(inbox(&app, &owner_token, "").await.unread_count, 0);
+
+    // //* Los observadores se enteran de los cambios de estado
+    let (status, _) = send_request(
+        &app,
+        "PATCH",
+        format!("/api/tasks/{}", task_id),
+        &owner_token,
+        json!({"status": "InProgress"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let page = inbox(&app, &member_token, "").await;
+    assert_eq!(page.items[0].kind, NotificationKind::StatusChanged);
+
+    // //* Mencionar a un miembro en un comentario le avisa
+    let (status, _) = send_request(
+        &app,
+        "POST",
+        format!("/api/tasks/{}/comments", task_id),
+        &owner_token,
+        json!({"content": "@Notif_Member échale un vistazo"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let page = inbox(&app, &member_token, "?limit=2").await;
+    assert_eq!(page.unread_count, 4);
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.items[0].kind, NotificationKind::Mentioned);
+    assert!(page.items[0].comment_id.is_some());
+    let next = inbox(
+        &app,
+        &member_token,
+        &format!("?limit=2&cursor={}", page.next_cursor.unwrap()),
+    )
+    .await;
+    assert_eq!(next.items.len(), 2);
+    assert!(next.next_cursor.is_none());
+
+    // //! Solo el destinatario puede marcar su notificación
+    let notification_id = page.items[0].id.unwrap();
+    let (status, _) = send_request(
+        &app,
+        "POST",
+        format!("/api/notifications/{}/read", notification_id),
+        &owner_token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = send_request(
+        &app,
+        "POST",
+        format!("/api/notifications/{}/read", notification_id),
+        &member_token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let unread = inbox(&app, &member_token, "?unread=true").await;
+    assert_eq!(unread.unread_count, 3);
+    assert_eq!(unread.items.len(), 3);
+
+    // //* Marcar todas deja la bandeja sin pendientes
+    let (status, body) = send_request(
+        &app,
+        "POST",
+        "/api/notifications/read-all".to_string(),
+        &member_token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let count: NotificationCount = serde_json::from_slice(&body).unwrap();
+    assert_eq!(count.unread_count, 0);
+}
@@ -0,0 +1,125 @@
+use axum::{Router, http::StatusCode};
+use bson::{oid::ObjectId, uuid};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    helpers::helper_setup_app::{
+        add_member_to_project, create_project_for_user, create_task_for_project,
+        get_auth_token_and_id, send_request, setup_app,
+    },
+    models::{
+        comment_model::{CommentData, CommentPage},
+        reaction_model::{ReactionSummary, Reactions, summarize_reactions},
+    },
+};
+
+async fn react(
+    app: &Router,
+    token: &str,
+    uri: String,
+    emoji: &str,
+) -> (StatusCode, Vec<ReactionSummary>) {
+    let (status, body) = send_request(app, "POST", uri, token, json!({"emoji": emoji})).await;
+    (status, serde_json::from_slice(&body).unwrap_or_default())
+}
+
+#[test]
+fn test_summarize_reactions() {
+    let me = ObjectId::new();
+    let other = ObjectId::new();
+    let mut reactions = Reactions::new();
+    reactions.insert("tada".to_string(), vec![other]);
+    reactions.insert("+1".to_string(), vec![other, me]);
+    reactions.insert("eyes".to_string(), vec![]);
+
+    let summary = summarize_reactions(&reactions, me);
+    assert_eq!(
+        summary,
+        [
+            ReactionSummary {
+                emoji: "+1".to_string(),
+                count: 2,
+                reacted_by_me: true
+            },
+            ReactionSummary {
+                emoji: "tada".to_string(),
+                count: 1,
+                reacted_by_me: false
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_task_and_comment_reactions() {
+    let app = setup_app().await;
+
+    let owner_email = format!("react-owner-{}@test.com", Uuid::new());
+    let (owner_token, _) = get_auth_token_and_id(&app, "react_owner", &owner_email).await;
+    let member_email = format!("react-member-{}@test.com", Uuid::new());
+    let (member_token, _) = get_auth_token_and_id(&app, "react_member", &member_email).await;
+    let stranger_email = format!("react-stranger-{}@test.com", Uuid::new());
+    let (stranger_token, _) = get_auth_token_and_id(&app, "react_stranger", &stranger_email).await;
+
+    let project_id = create_project_for_user(&app, &owner_token, "REACT").await;
+    add_member_to_project(&app, &owner_token, &project_id, &member_email).await;
+    let task_id = create_task_for_project(&app, &owner_token, &project_id, None).await;
+    let task_uri = format!("/api/tasks/{}/reactions", task_id);
+
+    // //* Cada usuario cuenta una vez por emoji
+    react(&app, &owner_token, task_uri.clone(), "thumbsup").await;
+    let (status, reactions) = react(&app, &member_token, task_uri.clone(), "thumbsup").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(reactions[0].count, 2);
+    assert!(reactions[0].reacted_by_me);
+
+    // //* Repetir la reacción la quita
+    let (_, reactions) = react(&app, &member_token, task_uri.clone(), "thumbsup").await;
+    assert_eq!(reactions[0].count, 1);
+    assert!(!reactions[0].reacted_by_me);
+    let (_, reactions) = react(&app, &owner_token, task_uri.clone(), "thumbsup").await;
+    assert!(reactions.is_empty());
+
+    // //! Códigos de emoji inválidos y usuarios sin acceso
+    let (status, _) = react(&app, &owner_token, task_uri.clone(), "thumbs.up").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = react(&app, &stranger_token, task_uri.clone(), "tada").await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // //* Las reacciones de un comentario aparecen en el listado según quién consulta
+    let (_, body) = send_request(
+        &app,
+        "POST",
+        format!("/api/tasks/{}/comments", task_id),
+        &owner_token,
+        json!({"content": "Listo para revisar"}),
+    )
+    .await;
+    let comment: CommentData = serde_json::from_slice(&body).unwrap();
+    let comment_uri = format!("/api/tasks/{}/comments/{}/reactions", task_id, comment.id);
+    react(&app, &member_token, comment_uri.clone(), "tada").await;
+    react(&app, &member_token, comment_uri.clone(), "+1").await;
+    react(&app, &owner_token, comment_uri.clone(), "+1").await;
+
+    for (token, tada_by_me) in [(&owner_token, false), (&member_token, true)] {
+        let (_, body) = send_request(
+            &app,
+            "GET",
+            format!("/api/tasks/{}/comments", task_id),
+            token,
+            json!({}),
+        )
+        .await;
+        let comments: CommentPage = serde_json::from_slice(&body).unwrap();
+        let reactions = &comments.items[0].reactions;
+        assert_eq!(reactions.len(), 2);
+        assert_eq!((reactions[0].emoji.as_str(), reactions[0].count), ("+1", 2));
+        assert!(reactions[0].reacted_by_me);
+        assert_eq!(
+            (reactions[1].emoji.as_str(), reactions[1].count),
+            ("tada", 1)
+        );
+        assert_eq!(reactions[1].reacted_by_me, tada_by_me);
+    }
+}
@@ -0,0 +1,222 @@
+use axum::http::StatusCode;
+use bson::uuid;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    helpers::helper_setup_app::{
+        create_project_for_user, get_auth_token_and_id, send_request, setup_app,
+    },
+    models::{
+        search_model::{SearchResult, TextSearchHitKind, TextSearchResult},
+        task_model::Task,
+    },
+};
+
+#[tokio::test]
+async fn test_jql_search_is_limited_to_accessible_projects() {
+    let app = setup_app().await;
+
+    let owner_email = format!("jql-owner-{}@test.com", Uuid::new());
+    let other_email = format!("jql-other-{}@test.com", Uuid::new());
+    let (token, user_id) = get_auth_token_and_id(&app, "jql_owner", &owner_email).await;
+    let (other_token, _) = get_auth_token_and_id(&app, "jql_other", &other_email).await;
+
+    let web_id = create_project_for_user(&app, &token, "WEB").await;
+    let api_id = create_project_for_user(&app, &token, "API").await;
+    let private_id = create_project_for_user(&app, &other_token, "PRIV").await;
+
+    for (project_id, tok, payload) in [
+        (
+            &web_id,
+            &token,
+            json!({"title": "Portada", "priority": "Low", "assignee_id": user_id.to_hex()}),
+        ),
+        (
+            &api_id,
+            &token,
+            json!({"title": "Endpoint", "priority": "Urgent", "assignee_id": user_id.to_hex()}),
+        ),
+        (
+            &api_id,
+            &token,
+            json!({"title": "Cerrada", "status": "Done", "assignee_id": user_id.to_hex()}),
+        ),
+        (&private_id, &other_token, json!({"title": "Privada"})),
+    ] {
+        let (status, _) = send_request(
+            &app,
+            "POST",
+            format!("/api/projects/{}/tasks", project_id),
+            tok,
+            payload,
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+
+    let jql = "project in (WEB, API) AND status != Done AND assignee = currentUser() ORDER BY priority DESC";
+    let (status, body) = send_request(
+        &app,
+        "GET",
+        format!("/api/search?jql={}", urlencode(jql)),
+        &token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let result: SearchResult = serde_json::from_slice(&body).unwrap();
+    assert_eq!(result.total, 2);
+    assert_eq!(result.items[0].title, "Endpoint");
+    assert_eq!(result.items[1].title, "Portada");
+
+    // //? Sin filtro solo aparecen tareas de proyectos propios
+    let (_, body) = send_request(
+        &app,
+        "GET",
+        format!("/api/search?jql={}", urlencode("ORDER BY created")),
+        &token,
+        json!({}),
+    )
+    .await;
+    let result: SearchResult = serde_json::from_slice(&body).unwrap();
+    assert_eq!(result.total, 3);
+
+    // //! Un proyecto ajeno se trata como desconocido
+    let (status, body) = send_request(
+        &app,
+        "GET",
+        format!("/api/search?jql={}", urlencode("project = PRIV")),
+        &token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(String::from_utf8_lossy(&body).contains("posición 11"));
+
+    // //? Un desplazamiento enorme se rechaza antes de llegar a MongoDB
+    let (status, _) = send_request(
+        &app,
+        "GET",
+        format!(
+            "/api/search?jql={}&start_at={}",
+            urlencode("project = WEB"),
+            u64::MAX
+        ),
+        &token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_text_search_over_tasks_and_comments() {
+    let app = setup_app().await;
+
+    let owner_email = format!("text-owner-{}@test.com", Uuid::new());
+    let other_email = format!("text-other-{}@test.com", Uuid::new());
+    let (token, _) = get_auth_token_and_id(&app, "text_owner", &owner_email).await;
+    let (other_token, _) = get_auth_token_and_id(&app, "text_other", &other_email).await;
+
+    let project_id = create_project_for_user(&app, &token, "TEXT").await;
+    let private_id = create_project_for_user(&app, &other_token, "HIDDEN").await;
+
+    let (_, body) = send_request(
+        &app,
+        "POST",
+        format!("/api/projects/{}/tasks", project_id),
+        &token,
+        json!({"title": "Migrar facturación", "description": "Mover la facturación al nuevo servidor"}),
+    )
+    .await;
+    let task: Task = serde_json::from_slice(&body).unwrap();
+    let task_id = task.id.unwrap().to_hex();
+
+    let (status, _) = send_request(
+        &app,
+        "POST",
+        format!("/api/tasks/{}/comments", task_id),
+        &token,
+        json!({"content": "El servidor de <staging> ya está listo"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, _) = send_request(
+        &app,
+        "POST",
+        format!("/api/projects/{}/tasks", private_id),
+        &other_token,
+        json!({"title": "Servidor privado"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, body) = send_request(
+        &app,
+        "GET",
+        "/api/search/text?q=servidor".to_string(),
+        &token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let result: TextSearchResult = serde_json::from_slice(&body).unwrap();
+
+    // //? La tarea del otro usuario no aparece
+    assert_eq!(result.total, 2);
+    assert!(
+        result
+            .items
+            .iter()
+            .all(|hit| hit.task_id.to_hex() == task_id)
+    );
+
+    let comment_hit = result
+        .items
+        .iter()
+        .find(|hit| hit.kind == TextSearchHitKind::Comment)
+        .unwrap();
+    assert_eq!(comment_hit.task_title, "Migrar facturación");
+    assert!(
+        comment_hit
+            .snippet
+            .contains("<mark>servidor</mark> de &lt;staging&gt;")
+    );
+
+    // //! Paginación sobre el resultado combinado
+    let (_, body) = send_request(
+        &app,
+        "GET",
+        "/api/search/text?q=servidor&start_at=1&max_results=1".to_string(),
+        &token,
+        json!({}),
+    )
+    .await;
+    let page: TextSearchResult = serde_json::from_slice(&body).unwrap();
+    assert_eq!(page.total, 2);
+    assert_eq!(page.items.len(), 1);
+
+    let (status, _) = send_request(
+        &app,
+        "GET",
+        format!("/api/search/text?q=servidor&start_at={}", u64::MAX),
+        &token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+fn urlencode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
@@ -0,0 +1,112 @@
+use axum::http::StatusCode;
+use bson::uuid;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    helpers::helper_setup_app::{
+        create_project_for_user, create_task_for_project, get_auth_token, send_request, setup_app,
+    },
+    models::{
+        sprint_model::{CloseSprintResult, Sprint, SprintState},
+        task_model::Task,
+    },
+};
+
+#[tokio::test]
+async fn test_sprint_lifecycle_and_backlog() {
+    let app = setup_app().await;
+
+    let email = format!("sprint-owner-{}@test.com", Uuid::new());
+    let token = get_auth_token(&app, "sprint_owner", &email).await;
+    let project_id = create_project_for_user(&app, &token, "SPRINT").await;
+
+    let done_task_id = create_task_for_project(&app, &token, &project_id, None).await;
+    let open_task_id = create_task_for_project(&app, &token, &project_id, None).await;
+
+    // //! Crear el sprint y añadir las dos tareas, aunque una venga repetida
+    let (status, body) = send_request(
+        &app,
+        "POST",
+        format!("/api/projects/{}/sprints", project_id),
+        &token,
+        json!({"name": "Sprint 1", "goal": "Primera entrega"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let sprint: Sprint = serde_json::from_slice(&body).unwrap();
+    assert_eq!(sprint.state, SprintState::Planned);
+    let sprint_id = sprint.id.unwrap().to_hex();
+
+    let (status, body) = send_request(
+        &app,
+        "POST",
+        format!("/api/sprints/{}/tasks", sprint_id),
+        &token,
+        json!({"task_ids": [done_task_id, open_task_id, done_task_id]}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let sprint_tasks: Vec<Task> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(sprint_tasks.len(), 2);
+
+    let (status, _) = send_request(
+        &app,
+        "POST",
+        format!("/api/sprints/{}/start", sprint_id),
+        &token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // //? Con el sprint activo, el backlog queda vacío
+    let (status, body) = send_request(
+        &app,
+        "GET",
+        format!("/api/projects/{}/backlog", project_id),
+        &token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let backlog: Vec<Task> = serde_json::from_slice(&body).unwrap();
+    assert!(backlog.is_empty());
+
+    let (status, _) = send_request(
+        &app,
+        "PATCH",
+        format!("/api/tasks/{}", done_task_id),
+        &token,
+        json!({"status": "Done"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // //! Al cerrar, la tarea incompleta vuelve al backlog
+    let (status, body) = send_request(
+        &app,
+        "POST",
+        format!("/api/sprints/{}/close", sprint_id),
+        &token,
+        json!({"move_to": "backlog"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let result: CloseSprintResult = serde_json::from_slice(&body).unwrap();
+    assert_eq!(result.sprint.state, SprintState::Closed);
+    assert_eq!(result.completed_tasks, 1);
+    assert_eq!(result.moved_tasks, 1);
+
+    let (_, body) = send_request(
+        &app,
+        "GET",
+        format!("/api/projects/{}/backlog", project_id),
+        &token,
+        json!({}),
+    )
+    .await;
+    let backlog: Vec<Task> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(backlog.len(), 1);
+    assert_eq!(backlog[0].id.unwrap().to_hex(), open_task_id);
+}
@@ -0,0 +1,112 @@
+use axum::{Router, http::StatusCode};
+use bson::uuid;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    helpers::helper_setup_app::{
+        create_project_for_user, get_auth_token_and_id, send_request, setup_app,
+    },
+    models::task_model::{Task, TaskPage},
+};
+
+async fn list_tasks(app: &Router, token: &str, project_id: &str, query: &str) -> TaskPage {
+    let (status, body) = send_request(
+        app,
+        "GET",
+        format!("/api/projects/{}/tasks?{}", project_id, query),
+        token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "Listado con '{}' falló", query);
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn test_task_listing_filters_and_pagination() {
+    let app = setup_app().await;
+
+    let email = format!("list-owner-{}@test.com", Uuid::new());
+    let (token, user_id) = get_auth_token_and_id(&app, "list_owner", &email).await;
+    let project_id = create_project_for_user(&app, &token, "LIST").await;
+
+    let payloads = [
+        json!({"title": "Diseñar login", "priority": "High", "assignee_id": user_id.to_hex()}),
+        json!({"title": "Revisar API", "priority": "Low"}),
+        json!({"title": "Corregir login roto", "priority": "Urgent", "status": "InProgress"}),
+        json!({"title": "Documentar despliegue", "priority": "Medium"}),
+        json!({"title": "Preparar demo", "priority": "High", "status": "Done"}),
+    ];
+    for payload in payloads {
+        let (status, body) = send_request(
+            &app,
+            "POST",
+            format!("/api/projects/{}/tasks", project_id),
+            &token,
+            payload,
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let _: Task = serde_json::from_slice(&body).unwrap();
+    }
+
+    // //! Filtros
+    let page = list_tasks(&app, &token, &project_id, "status=ToDo,InProgress").await;
+    assert_eq!(page.total, 4);
+
+    let page = list_tasks(&app, &token, &project_id, "assignee=me").await;
+    assert_eq!(page.total, 1);
+    assert_eq!(page.items[0].title, "Diseñar login");
+
+    let page = list_tasks(
+        &app,
+        &token,
+        &project_id,
+        "assignee=unassigned&priority=High",
+    )
+    .await;
+    assert_eq!(page.total, 1);
+    assert_eq!(page.items[0].title, "Preparar demo");
+
+    let page = list_tasks(&app, &token, &project_id, "q=LOGIN").await;
+    assert_eq!(page.total, 2);
+
+    // //? Orden por prioridad descendente recorrido de dos en dos
+    let mut titles = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let query = match &cursor {
+            Some(cursor) => format!("sort=-priority&limit=2&cursor={}", cursor),
+            None => "sort=-priority&limit=2".to_string(),
+        };
+        let page = list_tasks(&app, &token, &project_id, &query).await;
+        assert_eq!(page.total, 5);
+        titles.extend(page.items.into_iter().map(|task| task.title));
+        cursor = page.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+    assert_eq!(titles.len(), 5);
+    assert_eq!(titles[0], "Corregir login roto");
+    assert_eq!(titles[4], "Revisar API");
+
+    // //! Parámetros inválidos
+    for query in ["status=Bloqueada", "sort=color", "limit=0", "cursor=zz"] {
+        let (status, _) = send_request(
+            &app,
+            "GET",
+            format!("/api/projects/{}/tasks?{}", project_id, query),
+            &token,
+            json!({}),
+        )
+        .await;
+        assert_eq!(
+            status,
+            StatusCode::BAD_REQUEST,
+            "'{}' debería fallar",
+            query
+        );
+    }
+}
@@ -0,0 +1,233 @@
+use axum::{Router, http::StatusCode};
+use bson::{oid::ObjectId, uuid};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use crate::{
+    helpers::helper_setup_app::{
+        add_member_to_project, create_project_for_user, get_auth_token_and_id, send_request,
+        setup_app,
+    },
+    models::{
+        board_model::{Board, BoardColumn},
+        comment_model::CommentPage,
+        task_model::{Task, TaskStatus},
+        worklog_model::Worklog,
+    },
+};
+
+#[test]
+fn test_remap_status_between_boards() {
+    let source = Board::default_for_project(ObjectId::new());
+    let mut target = Board::default_for_project(ObjectId::new());
+    target.columns = vec![
+        BoardColumn {
+            name: "Pendiente".to_string(),
+            statuses: vec![TaskStatus::ToDo],
+            wip_limit: None,
+        },
+        BoardColumn {
+            name: "Cerrado".to_string(),
+            statuses: vec![TaskStatus::Done],
+            wip_limit: None,
+        },
+    ];
+
+    // Se conserva si el destino tiene columna para el estado
+    assert_eq!(
+        target.remap_status(&source, &TaskStatus::ToDo),
+        TaskStatus::ToDo
+    );
+    // Si no, se usa la columna en la misma posición o la última
+    assert_eq!(
+        target.remap_status(&source, &TaskStatus::InProgress),
+        TaskStatus::Done
+    );
+    assert_eq!(
+        target.remap_status(&source, &TaskStatus::Cancelled),
+        TaskStatus::Done
+    );
+}
+
+async fn create_task(app: &Router, token: &str, project_id: &str, body: Value) -> Task {
+    let (status, body) = send_request(
+        app,
+        "POST",
+        format!("/api/projects/{}/tasks", project_id),
+        token,
+        body,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn test_move_task_between_projects() {
+    let app = setup_app().await;
+
+    let owner_email = format!("move-owner-{}@test.com", Uuid::new());
+    let (owner_token, _) = get_auth_token_and_id(&app, "move_owner", &owner_email).await;
+    let member_email = format!("move-member-{}@test.com", Uuid::new());
+    let (member_token, member_id) = get_auth_token_and_id(&app, "move_member", &member_email).await;
+
+    let source_id = create_project_for_user(&app, &owner_token, "MOVA").await;
+    let target_id = create_project_for_user(&app, &owner_token, "MOVB").await;
+    add_member_to_project(&app, &owner_token, &source_id, &member_email).await;
+
+    for project_id in [&source_id, &target_id] {
+        let (status, _) = send_request(
+            &app,
+            "POST",
+            format!("/api/projects/{}/custom-fields", project_id),
+            &owner_token,
+            json!({"key": "customer", "name": "Cliente", "field_type": "text"}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+    let (status, _) = send_request(
+        &app,
+        "PUT",
+        format!("/api/projects/{}/board", target_id),
+        &owner_token,
+        json!({"columns": [
+            {"name": "Pendiente", "statuses": ["ToDo"]},
+            {"name": "Cerrado", "statuses": ["Done", "Cancelled"]},
+        ]}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // //* Las claves se numeran por proyecto
+    let child = create_task(
+        &app,
+        &owner_token,
+        &source_id,
+        json!({"title": "Subtarea de la migración"}),
+    )
+    .await;
+    assert_eq!(child.key.as_deref(), Some("MOVA-1"));
+    let task = create_task(
+        &app,
+        &owner_token,
+        &source_id,
+        json!({"title": "Tarea creada en el proyecto equivocado", "status": "InProgress",
+               "assignee_id": member_id.to_hex(), "custom_fields": {"customer": "ACME"}}),
+    )
+    .await;
+    assert_eq!(task.key.as_deref(), Some("MOVA-2"));
+    let task_id = task.id.unwrap().to_hex();
+
+    // Una tarea del proyecto de origen tiene como épica la que se va a mover
+    let (status, _) = send_request(
+        &app,
+        "PATCH",
+        format!("/api/tasks/{}", child.id.unwrap().to_hex()),
+        &owner_token,
+        json!({"epic_id": task_id}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    for (uri, body) in [
+        (
+            format!("/api/tasks/{}/comments", task_id),
+            json!({"content": "Esto va en el otro proyecto"}),
+        ),
+        (
+            format!("/api/tasks/{}/worklogs", task_id),
+            json!({"duration_minutes": 30}),
+        ),
+    ] {
+        let (status, _) = send_request(&app, "POST", uri, &owner_token, body).await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+
+    // //! Hace falta acceso a los dos proyectos
+    let (status, _) = send_request(
+        &app,
+        "POST",
+        format!("/api/tasks/{}/move", task_id),
+        &member_token,
+        json!({"project_id": target_id}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // //! El estado indicado debe existir en el tablero destino
+    let (status, _) = send_request(
+        &app,
+        "POST",
+        format!("/api/tasks/{}/move", task_id),
+        &owner_token,
+        json!({"project_id": target_id, "status": "InProgress"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = send_request(
+        &app,
+        "POST",
+        format!("/api/tasks/{}/move", task_id),
+        &owner_token,
+        json!({"project_id": target_id}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let moved: Task = serde_json::from_slice(&body).unwrap();
+    assert_eq!(moved.project_id.to_hex(), target_id);
+    assert_eq!(moved.key.as_deref(), Some("MOVB-1"));
+    assert_eq!(moved.key_aliases, ["MOVA-2"]);
+    assert_eq!(moved.status, TaskStatus::Done);
+    // El miembro no pertenece al destino; el campo personalizado existe en ambos
+    assert_eq!(moved.assignee_id, None);
+    assert_eq!(moved.custom_fields.get_str("customer").unwrap(), "ACME");
+
+    // //? La clave anterior sigue resolviendo la tarea
+    let (status, body) = send_request(
+        &app,
+        "GET",
+        "/api/tasks/key/mova-2".to_string(),
+        &owner_token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let found: Task = serde_json::from_slice(&body).unwrap();
+    assert_eq!(found.id, moved.id);
+
+    // //? Comentarios y registros de trabajo acompañan a la tarea
+    let (_, body) = send_request(
+        &app,
+        "GET",
+        format!("/api/tasks/{}/comments", task_id),
+        &owner_token,
+        json!({}),
+    )
+    .await;
+    let comments: CommentPage = serde_json::from_slice(&body).unwrap();
+    assert_eq!(comments.items.len(), 1);
+    let (_, body) = send_request(
+        &app,
+        "GET",
+        format!("/api/tasks/{}/worklogs", task_id),
+        &owner_token,
+        json!({}),
+    )
+    .await;
+    let worklogs: Vec<Worklog> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(worklogs[0].project_id.to_hex(), target_id);
+
+    // //? La tarea hija del proyecto de origen ya no apunta a otro proyecto
+    let (_, body) = send_request(
+        &app,
+        "GET",
+        format!("/api/tasks/{}", child.id.unwrap().to_hex()),
+        &owner_token,
+        json!({}),
+    )
+    .await;
+    let child: Task = serde_json::from_slice(&body).unwrap();
+    assert_eq!(child.epic_id, None);
+}
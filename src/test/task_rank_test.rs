@@ -0,0 +1,128 @@
+use axum::{Router, http::StatusCode};
+use bson::{doc, oid::ObjectId, uuid};
+use serde_json::json;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::{
+    db::DatabaseState,
+    helpers::helper_setup_app::{
+        create_project_for_user, create_task_for_project, get_auth_token, send_request, setup_app,
+    },
+    models::task_model::TaskPage,
+    services::rank_service::RankService,
+};
+
+async fn task_order(app: &Router, token: &str, project_id: &str) -> Vec<String> {
+    let (status, body) = send_request(
+        app,
+        "GET",
+        format!("/api/projects/{}/tasks", project_id),
+        token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let page: TaskPage = serde_json::from_slice(&body).unwrap();
+    page.items.iter().map(|t| t.id.unwrap().to_hex()).collect()
+}
+
+#[tokio::test]
+async fn test_manual_task_ordering() {
+    let app = setup_app().await;
+
+    let email = format!("rank-owner-{}@test.com", Uuid::new());
+    let stranger_email = format!("rank-stranger-{}@test.com", Uuid::new());
+    let token = get_auth_token(&app, "rank_owner", &email).await;
+    let stranger_token = get_auth_token(&app, "rank_stranger", &stranger_email).await;
+    let project_id = create_project_for_user(&app, &token, "RANK").await;
+
+    let first = create_task_for_project(&app, &token, &project_id, None).await;
+    let second = create_task_for_project(&app, &token, &project_id, None).await;
+    let third = create_task_for_project(&app, &token, &project_id, None).await;
+
+    // //? Un usuario sin acceso no puede reordenar
+    let (status, _) = send_request(
+        &app,
+        "POST",
+        format!("/api/tasks/{}/rank", third),
+        &stranger_token,
+        json!({"before_task_id": first}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // //! Mover la tercera tarea entre la primera y la segunda
+    let (status, _) = send_request(
+        &app,
+        "POST",
+        format!("/api/tasks/{}/rank", third),
+        &token,
+        json!({"before_task_id": first, "after_task_id": second}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // //! Y la primera al final, indicando solo su vecina anterior
+    let (status, _) = send_request(
+        &app,
+        "POST",
+        format!("/api/tasks/{}/rank", first),
+        &token,
+        json!({"before_task_id": second}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    assert_eq!(
+        task_order(&app, &token, &project_id).await,
+        vec![third, second, first]
+    );
+}
+
+#[tokio::test]
+async fn test_rebalance_keeps_order_and_puts_unranked_last() {
+    let app = setup_app().await;
+
+    let email = format!("rebalance-owner-{}@test.com", Uuid::new());
+    let token = get_auth_token(&app, "rebalance_owner", &email).await;
+    let project_id = create_project_for_user(&app, &token, "REBAL").await;
+
+    let first = create_task_for_project(&app, &token, &project_id, None).await;
+    let second = create_task_for_project(&app, &token, &project_id, None).await;
+    let third = create_task_for_project(&app, &token, &project_id, None).await;
+
+    // //? Una tarea antigua sin rango no debe pasar por delante de las ordenadas
+    let db_state = Arc::new(
+        DatabaseState::init(
+            &std::env::var("DATABASE_URL")
+                .unwrap_or_else(|_| "mongodb://localhost:27017".to_string()),
+            "test_db",
+        )
+        .await
+        .expect("Fallo al conectar a la DB de prueba"),
+    );
+    db_state
+        .get_db()
+        .collection::<bson::Document>("tasks")
+        .update_one(
+            doc! {"_id": ObjectId::parse_str(&first).unwrap()},
+            doc! {"$unset": {"rank": ""}},
+        )
+        .await
+        .unwrap();
+
+    let (ws_tx, _) = broadcast::channel(16);
+    let project = ObjectId::parse_str(&project_id).unwrap();
+    let rebalanced = RankService::new(db_state, ws_tx)
+        .rebalance_project(project)
+        .await
+        .unwrap();
+    assert_eq!(rebalanced, 3);
+
+    assert_eq!(
+        task_order(&app, &token, &project_id).await,
+        vec![second, third, first]
+    );
+}
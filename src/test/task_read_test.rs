@@ -10,7 +10,10 @@ use uuid::Uuid;
 
 use crate::{
     helpers::helper_setup_app::{get_auth_token, setup_app},
-    models::{project_models::Project, task_model::Task},
+    models::{
+        project_models::Project,
+        task_model::{Task, TaskPage},
+    },
 };
 
 #[tokio::test]
@@ -118,13 +121,15 @@ async fn test_task_read_permissions() {
         .await
         .unwrap();
     assert_eq!(authorized_list_resp.status(), StatusCode::OK);
-    let tasks: Vec<Task> = serde_json::from_slice(
+    let page: TaskPage = serde_json::from_slice(
         &to_bytes(authorized_list_resp.into_body(), usize::MAX)
             .await
             .unwrap(),
     )
     .unwrap();
-    assert_eq!(tasks.len(), 2);
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.total, 2);
+    assert!(page.next_cursor.is_none());
 
     // --- 3. PRUEBAS DE OBTENER TAREA INDIVIDUAL ---
     // Usuario B intenta obtener una tarea por ID -> DEBE FALLAR (401 Unauthorized)
@@ -0,0 +1,242 @@
+use axum::{Router, http::StatusCode};
+use bson::{oid::ObjectId, uuid};
+use chrono::{Duration, TimeZone, Utc};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    helpers::helper_setup_app::{
+        create_project_for_user, create_task_for_project, get_auth_token_and_id, send_request,
+        setup_app,
+    },
+    models::dependency_model::{TaskDependencies, Timeline},
+    utils::{
+        calendar::WorkCalendar,
+        critical_path::{PlannedTask, critical_path, topological_order},
+    },
+};
+
+#[test]
+fn test_critical_path() {
+    let day = |d| Utc.with_ymd_and_hms(2025, 3, d, 0, 0, 0).unwrap();
+    let task = |start, end| PlannedTask {
+        id: ObjectId::new(),
+        start: day(start),
+        end: day(end),
+    };
+    let design = task(1, 5);
+    let review = task(1, 3);
+    let build = task(5, 10);
+    // Planificada antes de que acabe el diseño: se desplaza hasta el día 5
+    let docs = task(2, 4);
+    let links = [
+        (design.id, build.id),
+        (review.id, build.id),
+        (design.id, docs.id),
+    ];
+
+    let analysis = critical_path(
+        &[design, review, build, docs],
+        &links,
+        &WorkCalendar::default(),
+    );
+    assert_eq!(analysis.end, Some(day(10)));
+    assert_eq!(analysis.path, [design.id, build.id]);
+
+    let schedule = &analysis.schedule;
+    assert!(schedule[&design.id].is_critical());
+    assert!(schedule[&build.id].is_critical());
+    assert_eq!(schedule[&review.id].slack, Duration::days(2));
+    assert_eq!(schedule[&docs.id].earliest_start, day(5));
+    assert_eq!(schedule[&docs.id].earliest_finish, day(7));
+    assert_eq!(schedule[&docs.id].slack, Duration::days(3));
+}
+
+#[test]
+fn test_topological_order_skips_cycles() {
+    let (a, b, c) = (ObjectId::new(), ObjectId::new(), ObjectId::new());
+    assert_eq!(topological_order(&[a, b, c], &[(b, a)]), [b, c, a]);
+    assert_eq!(topological_order(&[a, b, c], &[(a, b), (b, a)]), [c]);
+}
+
+async fn set_range(app: &Router, token: &str, task_id: &str, start: u32, end: u32) {
+    let (status, _) = send_request(
+        app,
+        "POST",
+        format!("/api/tasks/{}/date-range", task_id),
+        token,
+        json!({
+            "start_date": format!("2025-03-{:02}T00:00:00Z", start),
+            "end_date": format!("2025-03-{:02}T00:00:00Z", end),
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+async fn depend(app: &Router, token: &str, task_id: &str, predecessor_id: &str) -> StatusCode {
+    let (status, _) = send_request(
+        app,
+        "POST",
+        format!("/api/tasks/{}/dependencies", task_id),
+        token,
+        json!({"predecessor_id": predecessor_id}),
+    )
+    .await;
+    status
+}
+
+async fn timeline(app: &Router, token: &str, project_id: &str) -> Timeline {
+    let (status, body) = send_request(
+        app,
+        "GET",
+        format!("/api/projects/{}/timeline", project_id),
+        token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn test_concurrent_dependencies_never_close_a_cycle() {
+    let app = setup_app().await;
+
+    let email = format!("timeline-race-{}@test.com", Uuid::new());
+    let (token, _) = get_auth_token_and_id(&app, "timeline_race", &email).await;
+    let project_id = create_project_for_user(&app, &token, "RACE").await;
+    let first = create_task_for_project(&app, &token, &project_id, None).await;
+    let second = create_task_for_project(&app, &token, &project_id, None).await;
+
+    // //! A→B y B→A a la vez: como mucho una de las dos se queda guardada
+    let (forward, backward) = tokio::join!(
+        depend(&app, &token, &second, &first),
+        depend(&app, &token, &first, &second)
+    );
+    assert!(forward != StatusCode::CREATED || backward != StatusCode::CREATED);
+    assert!(timeline(&app, &token, &project_id).await.dependencies.len() <= 1);
+}
+
+#[tokio::test]
+async fn test_project_timeline() {
+    let app = setup_app().await;
+
+    let email = format!("timeline-{}@test.com", Uuid::new());
+    let (token, _) = get_auth_token_and_id(&app, "timeline_user", &email).await;
+    let stranger_email = format!("timeline-stranger-{}@test.com", Uuid::new());
+    let (stranger_token, _) =
+        get_auth_token_and_id(&app, "timeline_stranger", &stranger_email).await;
+    let project_id = create_project_for_user(&app, &token, "PLAN").await;
+    let other_project_id = create_project_for_user(&app, &token, "OTHER").await;
+
+    let design = create_task_for_project(&app, &token, &project_id, None).await;
+    let build = create_task_for_project(&app, &token, &project_id, None).await;
+    let docs = create_task_for_project(&app, &token, &project_id, None).await;
+    let unplanned = create_task_for_project(&app, &token, &project_id, None).await;
+    let foreign = create_task_for_project(&app, &token, &other_project_id, None).await;
+    set_range(&app, &token, &design, 1, 5).await;
+    set_range(&app, &token, &build, 5, 10).await;
+    // Empieza antes de que acabe el diseño
+    set_range(&app, &token, &docs, 3, 4).await;
+
+    assert_eq!(
+        depend(&app, &token, &build, &design).await,
+        StatusCode::CREATED
+    );
+    assert_eq!(
+        depend(&app, &token, &docs, &design).await,
+        StatusCode::CREATED
+    );
+
+    // //! Dependencias repetidas, de sí misma, circulares o con otro proyecto
+    assert_eq!(
+        depend(&app, &token, &build, &design).await,
+        StatusCode::CONFLICT
+    );
+    assert_eq!(
+        depend(&app, &token, &build, &build).await,
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        depend(&app, &token, &design, &build).await,
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        depend(&app, &token, &build, &foreign).await,
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        depend(&app, &stranger_token, &build, &docs).await,
+        StatusCode::UNAUTHORIZED
+    );
+
+    let (status, body) = send_request(
+        &app,
+        "GET",
+        format!("/api/tasks/{}/dependencies", design),
+        &token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let dependencies: TaskDependencies = serde_json::from_slice(&body).unwrap();
+    assert!(dependencies.predecessors.is_empty());
+    assert_eq!(dependencies.successors.len(), 2);
+
+    // //* Cronograma con holgura, camino crítico e incumplimientos
+    let plan = timeline(&app, &token, &project_id).await;
+    assert_eq!(plan.items.len(), 4);
+    assert_eq!(plan.critical_path, [design.clone(), build.clone()]);
+    let item = |id: &str| plan.items.iter().find(|item| item.task_id == id).unwrap();
+    assert!(item(&design).critical);
+    assert_eq!(item(&build).slack_minutes, Some(0));
+    assert!(!item(&docs).critical);
+    assert_eq!(item(&docs).slack_minutes, Some(4 * 24 * 60));
+    assert!(item(&unplanned).slack_minutes.is_none());
+    assert_eq!(plan.items.last().unwrap().task_id, unplanned);
+
+    let violated: Vec<_> = plan
+        .dependencies
+        .iter()
+        .filter(|link| link.violated)
+        .map(|link| link.successor_id.as_str())
+        .collect();
+    assert_eq!(violated, [docs.as_str()]);
+
+    let (status, _) = send_request(
+        &app,
+        "GET",
+        format!("/api/projects/{}/timeline", project_id),
+        &stranger_token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // //* Quitar una dependencia y borrar una tarea eliminan sus enlaces
+    let (status, _) = send_request(
+        &app,
+        "DELETE",
+        format!("/api/tasks/{}/dependencies/{}", docs, design),
+        &token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send_request(
+        &app,
+        "DELETE",
+        format!("/api/tasks/{}", design),
+        &token,
+        json!({}),
+    )
+    .await;
+    assert!(status.is_success());
+    assert!(
+        timeline(&app, &token, &project_id)
+            .await
+            .dependencies
+            .is_empty()
+    );
+}
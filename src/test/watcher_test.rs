@@ -0,0 +1,145 @@
+use axum::{Router, http::StatusCode};
+use bson::{oid::ObjectId, uuid};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    helpers::helper_setup_app::{
+        add_member_to_project, create_project_for_user, create_task_for_project,
+        get_auth_token_and_id, send_request, setup_app,
+    },
+    models::{
+        task_model::Task,
+        watcher_model::{TaskWatchers, WatchedTasksPage},
+    },
+};
+
+async fn watchers(
+    app: &Router,
+    token: &str,
+    method: &str,
+    uri: String,
+) -> (StatusCode, Option<TaskWatchers>) {
+    let (status, body) = send_request(app, method, uri, token, json!({})).await;
+    (status, serde_json::from_slice(&body).ok())
+}
+
+async fn watched_ids(app: &Router, token: &str) -> Vec<ObjectId> {
+    let (status, body) =
+        send_request(app, "GET", "/api/me/watching".to_string(), token, json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    let page: WatchedTasksPage = serde_json::from_slice(&body).unwrap();
+    page.items.into_iter().filter_map(|task| task.id).collect()
+}
+
+#[tokio::test]
+async fn test_task_watchers() {
+    let app = setup_app().await;
+
+    let owner_email = format!("watch-owner-{}@test.com", Uuid::new());
+    let (owner_token, owner_id) = get_auth_token_and_id(&app, "watch_owner", &owner_email).await;
+    let member_email = format!("watch-member-{}@test.com", Uuid::new());
+    let (member_token, member_id) =
+        get_auth_token_and_id(&app, "watch_member", &member_email).await;
+    let stranger_email = format!("watch-stranger-{}@test.com", Uuid::new());
+    let (stranger_token, _) = get_auth_token_and_id(&app, "watch_stranger", &stranger_email).await;
+
+    let project_id = create_project_for_user(&app, &owner_token, "WATCH").await;
+    add_member_to_project(&app, &owner_token, &project_id, &member_email).await;
+
+    // //* El informador observa la tarea desde que la crea
+    let task_id = create_task_for_project(&app, &owner_token, &project_id, None).await;
+    let other_id = create_task_for_project(&app, &owner_token, &project_id, None).await;
+    let (status, body) = send_request(
+        &app,
+        "GET",
+        format!("/api/tasks/{}", task_id),
+        &owner_token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let task: Task = serde_json::from_slice(&body).unwrap();
+    assert_eq!(task.watchers, [owner_id]);
+
+    // //* Al asignarla, el asignado pasa a observarla
+    let (status, body) = send_request(
+        &app,
+        "PATCH",
+        format!("/api/tasks/{}", task_id),
+        &owner_token,
+        json!({"assignee_id": member_id.to_hex()}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let task: Task = serde_json::from_slice(&body).unwrap();
+    assert_eq!(task.watchers, [owner_id, member_id]);
+
+    // //* Cualquier miembro puede observar otra tarea, una sola vez
+    for _ in 0..2 {
+        let (status, response) = watchers(
+            &app,
+            &member_token,
+            "POST",
+            format!("/api/tasks/{}/watch", other_id),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let response = response.unwrap();
+        assert!(response.watching);
+        assert_eq!(response.watchers.len(), 2);
+    }
+    assert_eq!(watched_ids(&app, &member_token).await.len(), 2);
+
+    // //! Sin acceso al proyecto no se puede observar
+    let (status, _) = watchers(
+        &app,
+        &stranger_token,
+        "POST",
+        format!("/api/tasks/{}/watch", task_id),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // //* El asignado también puede dejar de observar
+    let (status, response) = watchers(
+        &app,
+        &member_token,
+        "DELETE",
+        format!("/api/tasks/{}/watch", task_id),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(!response.unwrap().watching);
+    let (_, response) = watchers(
+        &app,
+        &owner_token,
+        "GET",
+        format!("/api/tasks/{}/watchers", task_id),
+    )
+    .await;
+    let response = response.unwrap();
+    assert!(response.watching);
+    assert_eq!(response.watchers.len(), 1);
+    assert_eq!(response.watchers[0].id, owner_id.to_hex());
+
+    // //? Al salir del proyecto deja de observar sus tareas
+    let (status, _) = send_request(
+        &app,
+        "DELETE",
+        format!("/api/projects/{}/members/{}", project_id, member_id),
+        &owner_token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert!(watched_ids(&app, &member_token).await.is_empty());
+    let (_, response) = watchers(
+        &app,
+        &owner_token,
+        "GET",
+        format!("/api/tasks/{}/watchers", other_id),
+    )
+    .await;
+    assert_eq!(response.unwrap().watchers.len(), 1);
+}
@@ -0,0 +1,167 @@
+use axum::{
+    body::{Body, to_bytes},
+    http::{Request, StatusCode, header},
+};
+use bson::uuid;
+use serde_json::json;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use crate::{
+    helpers::helper_setup_app::{create_project_for_user, get_auth_token, setup_app},
+    models::{
+        task_model::Task,
+        worklog_model::{TimesheetReport, Worklog},
+    },
+};
+
+async fn fetch_task(app: &axum::Router, token: &str, task_id: &str) -> Task {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/tasks/{}", task_id))
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn test_worklogs_adjust_remaining_estimate() {
+    let app = setup_app().await;
+
+    let owner_email = format!("worklog-owner-{}@test.com", Uuid::new());
+    let stranger_email = format!("worklog-stranger-{}@test.com", Uuid::new());
+    let owner_token = get_auth_token(&app, "worklog_owner", &owner_email).await;
+    let stranger_token = get_auth_token(&app, "worklog_stranger", &stranger_email).await;
+
+    let project_id = create_project_for_user(&app, &owner_token, "WLOG").await;
+
+    // //! La tarea se crea con 2 horas estimadas
+    let task_payload =
+        json!({"title": "Tarea estimada", "original_estimate_minutes": 120, "story_points": 3.0});
+    let create_task_resp = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/projects/{}/tasks", project_id))
+                .header(header::AUTHORIZATION, format!("Bearer {}", owner_token))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(task_payload.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(create_task_resp.status(), StatusCode::CREATED);
+    let task: Task = serde_json::from_slice(
+        &to_bytes(create_task_resp.into_body(), usize::MAX)
+            .await
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(task.remaining_estimate_minutes, Some(120));
+    let task_id = task.id.unwrap().to_hex();
+
+    // //? Un usuario ajeno al proyecto no puede registrar tiempo
+    let worklog_payload = json!({"duration_minutes": 30, "comment": "Análisis"});
+    let unauthorized_resp = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/tasks/{}/worklogs", task_id))
+                .header(header::AUTHORIZATION, format!("Bearer {}", stranger_token))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(worklog_payload.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(unauthorized_resp.status(), StatusCode::UNAUTHORIZED);
+
+    let create_resp = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/tasks/{}/worklogs", task_id))
+                .header(header::AUTHORIZATION, format!("Bearer {}", owner_token))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(worklog_payload.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(create_resp.status(), StatusCode::CREATED);
+    let worklog: Worklog =
+        serde_json::from_slice(&to_bytes(create_resp.into_body(), usize::MAX).await.unwrap())
+            .unwrap();
+    let worklog_id = worklog.id.unwrap().to_hex();
+
+    let task = fetch_task(&app, &owner_token, &task_id).await;
+    assert_eq!(task.remaining_estimate_minutes, Some(90));
+
+    // //! Al ampliar la duración solo se descuenta la diferencia
+    let update_resp = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("PATCH")
+                .uri(format!("/api/tasks/{}/worklogs/{}", task_id, worklog_id))
+                .header(header::AUTHORIZATION, format!("Bearer {}", owner_token))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(json!({"duration_minutes": 50}).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(update_resp.status(), StatusCode::OK);
+    let task = fetch_task(&app, &owner_token, &task_id).await;
+    assert_eq!(task.remaining_estimate_minutes, Some(70));
+
+    // //? El reporte del proyecto incluye el tiempo registrado
+    let from = (chrono::Utc::now() - chrono::Duration::days(1)).format("%Y-%m-%dT%H:%M:%SZ");
+    let to = (chrono::Utc::now() + chrono::Duration::days(1)).format("%Y-%m-%dT%H:%M:%SZ");
+    let report_resp = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/projects/{}/timesheet?from={}&to={}",
+                    project_id, from, to
+                ))
+                .header(header::AUTHORIZATION, format!("Bearer {}", owner_token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(report_resp.status(), StatusCode::OK);
+    let report: TimesheetReport =
+        serde_json::from_slice(&to_bytes(report_resp.into_body(), usize::MAX).await.unwrap())
+            .unwrap();
+    assert_eq!(report.total_minutes, 50);
+    assert_eq!(report.entries.len(), 1);
+
+    // //! Al borrar el registro el tiempo vuelve a la estimación restante
+    let delete_resp = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri(format!("/api/tasks/{}/worklogs/{}", task_id, worklog_id))
+                .header(header::AUTHORIZATION, format!("Bearer {}", owner_token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(delete_resp.status(), StatusCode::NO_CONTENT);
+    let task = fetch_task(&app, &owner_token, &task_id).await;
+    assert_eq!(task.remaining_estimate_minutes, Some(120));
+}
@@ -0,0 +1,241 @@
+// Calendario laboral: qué días se trabaja en la zona horaria del proyecto. Las duraciones se
+// miden en tiempo laborable (los fines de semana y festivos no cuentan) y al mover una tarea
+// se conserva ese tiempo, no el transcurrido. Un día laborable completo son 24 horas, así que
+// con el calendario por defecto (todos los días, UTC) los cálculos son los de siempre.
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use std::collections::HashSet;
+
+use crate::models::calendar_model::Holiday;
+
+// Un festivo de varios días en un iCalendar no puede durar más que esto
+const MAX_HOLIDAY_DAYS: i64 = 31;
+
+#[derive(Debug, Clone)]
+pub struct WorkCalendar {
+    time_zone: Tz,
+    working_days: HashSet<Weekday>,
+    holidays: HashSet<NaiveDate>,
+}
+
+impl Default for WorkCalendar {
+    fn default() -> Self {
+        Self::new(Tz::UTC, [], [])
+    }
+}
+
+impl WorkCalendar {
+    // //* Sin días laborables se trabajaría nunca; en ese caso se consideran todos laborables
+    pub fn new(
+        time_zone: Tz,
+        working_days: impl IntoIterator<Item = Weekday>,
+        holidays: impl IntoIterator<Item = NaiveDate>,
+    ) -> Self {
+        let mut working_days: HashSet<Weekday> = working_days.into_iter().collect();
+        if working_days.is_empty() {
+            working_days = (0..7)
+                .filter_map(|day| Weekday::try_from(day).ok())
+                .collect();
+        }
+        Self {
+            time_zone,
+            working_days,
+            holidays: holidays.into_iter().collect(),
+        }
+    }
+
+    pub fn is_working_day(&self, date: NaiveDate) -> bool {
+        self.working_days.contains(&date.weekday()) && !self.holidays.contains(&date)
+    }
+
+    fn local_date(&self, instant: DateTime<Utc>) -> NaiveDate {
+        instant.with_timezone(&self.time_zone).date_naive()
+    }
+
+    // //? Si el cambio de hora se salta la medianoche, el día empieza en la primera hora válida
+    fn day_start(&self, date: NaiveDate) -> DateTime<Utc> {
+        let midnight = date.and_time(NaiveTime::MIN);
+        self.time_zone
+            .from_local_datetime(&midnight)
+            .earliest()
+            .or_else(|| {
+                self.time_zone
+                    .from_local_datetime(&(midnight + Duration::hours(1)))
+                    .earliest()
+            })
+            .map(|start| start.with_timezone(&Utc))
+            .unwrap_or_else(|| Utc.from_utc_datetime(&midnight))
+    }
+
+    // Primer día laborable a partir de `date`, incluido
+    fn next_working_day(&self, mut date: NaiveDate) -> NaiveDate {
+        while !self.is_working_day(date) {
+            date = date.succ_opt().unwrap_or(date);
+        }
+        date
+    }
+
+    // //* El propio instante si cae en día laborable o, si no, el comienzo del siguiente
+    pub fn next_working_time(&self, instant: DateTime<Utc>) -> DateTime<Utc> {
+        let date = self.local_date(instant);
+        if self.is_working_day(date) {
+            instant
+        } else {
+            self.day_start(self.next_working_day(date))
+        }
+    }
+
+    // //* Tiempo laborable entre dos instantes (cero si `end` no es posterior a `start`)
+    pub fn working_time(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Duration {
+        let mut total = Duration::zero();
+        let mut date = self.local_date(start);
+        let last = self.local_date(end);
+        while date <= last {
+            let Some(next) = date.succ_opt() else {
+                break;
+            };
+            if self.is_working_day(date) {
+                let from = self.day_start(date).max(start);
+                let to = self.day_start(next).min(end);
+                if to > from {
+                    total += to - from;
+                }
+            }
+            date = next;
+        }
+        total
+    }
+
+    // //* Instante en el que se completa `duration` de tiempo laborable empezando en `start`
+    pub fn add_working_time(&self, start: DateTime<Utc>, duration: Duration) -> DateTime<Utc> {
+        let mut current = self.next_working_time(start);
+        let mut remaining = duration;
+        while remaining > Duration::zero() {
+            let Some(next) = self.local_date(current).succ_opt() else {
+                break;
+            };
+            let day_end = self.day_start(next);
+            let available = day_end - current;
+            if remaining <= available {
+                return current + remaining;
+            }
+            remaining -= available;
+            current = self.next_working_time(day_end);
+        }
+        current
+    }
+
+    // //* Instante en que hay que empezar para completar `duration` de tiempo laborable en `end`
+    pub fn sub_working_time(&self, end: DateTime<Utc>, duration: Duration) -> DateTime<Utc> {
+        let mut current = end;
+        let mut remaining = duration;
+        while remaining > Duration::zero() {
+            // El día en curso es el del instante inmediatamente anterior
+            let date = self.local_date(current - Duration::nanoseconds(1));
+            let day_start = self.day_start(date);
+            if self.is_working_day(date) {
+                let available = current - day_start;
+                if remaining <= available {
+                    return current - remaining;
+                }
+                remaining -= available;
+            }
+            current = day_start;
+        }
+        current
+    }
+
+    // //* Fecha de fin de una tarea que empieza en `start` y dura `days` días laborables
+    pub fn add_working_days(&self, start: DateTime<Utc>, days: u32) -> DateTime<Utc> {
+        self.add_working_time(start, Duration::days(days.into()))
+    }
+}
+
+// //* Duración expresada en días laborables (con decimales si no son días completos)
+pub fn as_working_days(duration: Duration) -> f64 {
+    duration.num_minutes() as f64 / (24.0 * 60.0)
+}
+
+// //* Festivos de un fichero iCalendar (RFC 5545): cada `VEVENT` es un festivo con su `SUMMARY`
+// //* como nombre, y si su `DTEND` es posterior al día siguiente abarca varios días. Las reglas
+// //* de repetición (`RRULE`) no se expanden: se importa solo la primera aparición.
+pub fn parse_icalendar_holidays(source: &str) -> Result<Vec<Holiday>, String> {
+    // //? Las líneas largas se parten en varias que empiezan por un espacio o tabulador
+    let mut lines: Vec<String> = Vec::new();
+    for line in source.lines() {
+        let line = line.trim_end_matches('\r');
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    if !lines
+        .iter()
+        .any(|line| line.eq_ignore_ascii_case("BEGIN:VCALENDAR"))
+    {
+        return Err("El fichero no es un calendario iCalendar".to_string());
+    }
+
+    let mut holidays = Vec::new();
+    let mut event: Option<(Option<NaiveDate>, Option<NaiveDate>, String)> = None;
+    for line in &lines {
+        let Some((property, value)) = line.split_once(':') else {
+            continue;
+        };
+        let name = property
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_uppercase();
+
+        match (name.as_str(), event.as_mut()) {
+            ("BEGIN", _) if value.eq_ignore_ascii_case("VEVENT") => {
+                event = Some((None, None, String::new()));
+            }
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                let Some((Some(start), end, summary)) = event.take() else {
+                    return Err("Hay un evento sin fecha de inicio (DTSTART)".to_string());
+                };
+                let days = end
+                    .map_or(1, |end| (end - start).num_days())
+                    .clamp(1, MAX_HOLIDAY_DAYS);
+                holidays.extend(start.iter_days().take(days as usize).map(|date| Holiday {
+                    date,
+                    name: summary.clone(),
+                }));
+            }
+            ("DTSTART", Some((start, _, _))) => *start = Some(parse_ical_date(value)?),
+            ("DTEND", Some((_, end, _))) => *end = Some(parse_ical_date(value)?),
+            ("SUMMARY", Some((_, _, summary))) => *summary = unescape_ical_text(value),
+            _ => {}
+        }
+    }
+
+    Ok(holidays)
+}
+
+// `20250101` o `20250101T000000Z`; de las fechas con hora solo interesa el día
+fn parse_ical_date(value: &str) -> Result<NaiveDate, String> {
+    value
+        .get(..8)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok())
+        .ok_or_else(|| format!("Fecha de iCalendar inválida: {}", value))
+}
+
+fn unescape_ical_text(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => text.push(' '),
+            Some(escaped) => text.push(escaped),
+            None => {}
+        }
+    }
+    text.trim().to_string()
+}
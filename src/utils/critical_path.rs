@@ -0,0 +1,234 @@
+// Método del camino crítico sobre tareas con dependencias fin-inicio. Cada tarea se planifica
+// lo antes posible sin adelantarse a su inicio previsto ni al fin de sus predecesoras, y
+// conserva su duración. La holgura es cuánto puede retrasarse sin retrasar el fin del
+// proyecto; las tareas sin holgura forman el camino crítico. La planificación automática usa
+// las mismas reglas para retrasar las tareas que dependen de una que se retrasa. Duraciones y
+// holguras se miden en tiempo laborable según el calendario del proyecto.
+use chrono::{DateTime, Duration, Utc};
+use mongodb::bson::oid::ObjectId;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::utils::calendar::WorkCalendar;
+
+// Tarea con inicio y fin previstos
+#[derive(Debug, Clone, Copy)]
+pub struct PlannedTask {
+    pub id: ObjectId,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TaskSchedule {
+    pub earliest_start: DateTime<Utc>,
+    pub earliest_finish: DateTime<Utc>,
+    pub latest_start: DateTime<Utc>,
+    pub latest_finish: DateTime<Utc>,
+    // Tiempo laborable
+    pub slack: Duration,
+}
+
+impl TaskSchedule {
+    pub fn is_critical(&self) -> bool {
+        self.slack <= Duration::zero()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CriticalPath {
+    pub schedule: HashMap<ObjectId, TaskSchedule>,
+    // Tareas del camino crítico, de la primera a la última
+    pub path: Vec<ObjectId>,
+    pub end: Option<DateTime<Utc>>,
+}
+
+/// Orden topológico de `ids` según las dependencias `(predecesora, sucesora)`. Se ignoran las
+/// dependencias con tareas que no están en `ids`, y las tareas que forman un ciclo se quedan
+/// fuera. Entre tareas independientes se respeta el orden de `ids`.
+pub fn topological_order(ids: &[ObjectId], links: &[(ObjectId, ObjectId)]) -> Vec<ObjectId> {
+    let known: HashSet<ObjectId> = ids.iter().copied().collect();
+    let mut pending: HashMap<ObjectId, usize> = ids.iter().map(|id| (*id, 0)).collect();
+    let mut successors: HashMap<ObjectId, Vec<ObjectId>> = HashMap::new();
+    for (predecessor, successor) in links {
+        if known.contains(predecessor) && known.contains(successor) {
+            successors.entry(*predecessor).or_default().push(*successor);
+            *pending.entry(*successor).or_default() += 1;
+        }
+    }
+
+    let mut queue: VecDeque<ObjectId> = ids.iter().filter(|id| pending[id] == 0).copied().collect();
+    let mut order = Vec::with_capacity(ids.len());
+    while let Some(id) = queue.pop_front() {
+        order.push(id);
+        for successor in successors.get(&id).into_iter().flatten() {
+            let count = pending.entry(*successor).or_default();
+            *count -= 1;
+            if *count == 0 {
+                queue.push_back(*successor);
+            }
+        }
+    }
+    order
+}
+
+/// Fechas tempranas y tardías, holgura y camino crítico de `tasks`
+pub fn critical_path(
+    tasks: &[PlannedTask],
+    links: &[(ObjectId, ObjectId)],
+    calendar: &WorkCalendar,
+) -> CriticalPath {
+    let planned: HashMap<ObjectId, &PlannedTask> =
+        tasks.iter().map(|task| (task.id, task)).collect();
+    let ids: Vec<ObjectId> = tasks.iter().map(|task| task.id).collect();
+    let order = topological_order(&ids, links);
+
+    let mut predecessors: HashMap<ObjectId, Vec<ObjectId>> = HashMap::new();
+    let mut successors: HashMap<ObjectId, Vec<ObjectId>> = HashMap::new();
+    for (predecessor, successor) in links {
+        if planned.contains_key(predecessor) && planned.contains_key(successor) {
+            predecessors
+                .entry(*successor)
+                .or_default()
+                .push(*predecessor);
+            successors.entry(*predecessor).or_default().push(*successor);
+        }
+    }
+
+    let duration = |task: &PlannedTask| calendar.working_time(task.start, task.end);
+
+    // //* Hacia delante: lo antes que puede empezar y acabar cada tarea
+    let mut earliest: HashMap<ObjectId, (DateTime<Utc>, DateTime<Utc>)> = HashMap::new();
+    for id in &order {
+        let task = planned[id];
+        let start = predecessors
+            .get(id)
+            .into_iter()
+            .flatten()
+            .filter_map(|predecessor| earliest.get(predecessor).map(|(_, finish)| *finish))
+            .fold(task.start, DateTime::max);
+        let dates = if start == task.start {
+            (task.start, task.end)
+        } else {
+            let start = calendar.next_working_time(start);
+            (start, calendar.add_working_time(start, duration(task)))
+        };
+        earliest.insert(*id, dates);
+    }
+
+    let Some(end) = earliest.values().map(|(_, finish)| *finish).max() else {
+        return CriticalPath::default();
+    };
+
+    // //* Hacia atrás: lo más tarde que puede acabar sin retrasar a sus sucesoras
+    let mut schedule = HashMap::new();
+    for id in order.iter().rev() {
+        let task = planned[id];
+        let latest_finish = successors
+            .get(id)
+            .into_iter()
+            .flatten()
+            .filter_map(|successor| schedule.get(successor))
+            .map(|successor: &TaskSchedule| successor.latest_start)
+            .fold(end, DateTime::min);
+        let latest_start = calendar.sub_working_time(latest_finish, duration(task));
+        let (earliest_start, earliest_finish) = earliest[id];
+        schedule.insert(
+            *id,
+            TaskSchedule {
+                earliest_start,
+                earliest_finish,
+                latest_start,
+                latest_finish,
+                slack: calendar.working_time(earliest_start, latest_start),
+            },
+        );
+    }
+
+    // //? El camino se reconstruye desde la tarea crítica que acaba el proyecto, siguiendo la
+    // //? predecesora crítica que fija su inicio
+    let mut path = Vec::new();
+    let mut current = order
+        .iter()
+        .find(|id| schedule[*id].is_critical() && schedule[*id].earliest_finish == end)
+        .copied();
+    while let Some(id) = current {
+        path.push(id);
+        let start = calendar.next_working_time(schedule[&id].earliest_start);
+        current = predecessors
+            .get(&id)
+            .into_iter()
+            .flatten()
+            .find(|predecessor| {
+                schedule.get(*predecessor).is_some_and(|p| {
+                    p.is_critical() && calendar.next_working_time(p.earliest_finish) == start
+                })
+            })
+            .copied();
+    }
+    path.reverse();
+
+    CriticalPath {
+        schedule,
+        path,
+        end: Some(end),
+    }
+}
+
+/// Nuevas fechas de las tareas que dependen de `changed` (ya con sus fechas nuevas en `tasks`)
+/// para que ninguna empiece antes de que acaben sus predecesoras. Cada tarea conserva su
+/// duración en tiempo laborable y solo se retrasa: las que ya empiezan después no se mueven.
+/// Solo se devuelven las tareas que cambian, en orden de dependencias.
+pub fn propagate_dates(
+    tasks: &[PlannedTask],
+    links: &[(ObjectId, ObjectId)],
+    changed: ObjectId,
+    calendar: &WorkCalendar,
+) -> Vec<PlannedTask> {
+    let mut dates: HashMap<ObjectId, PlannedTask> =
+        tasks.iter().map(|task| (task.id, *task)).collect();
+    let ids: Vec<ObjectId> = tasks.iter().map(|task| task.id).collect();
+
+    let mut predecessors: HashMap<ObjectId, Vec<ObjectId>> = HashMap::new();
+    for (predecessor, successor) in links {
+        if dates.contains_key(predecessor) && dates.contains_key(successor) {
+            predecessors
+                .entry(*successor)
+                .or_default()
+                .push(*predecessor);
+        }
+    }
+
+    // //? En orden topológico, una tarea se revisa después de todas sus predecesoras; solo
+    // //? hace falta mirar las que tienen alguna predecesora afectada por el cambio
+    let mut affected = HashSet::from([changed]);
+    let mut shifted = Vec::new();
+    for id in topological_order(&ids, links) {
+        let Some(task_predecessors) = predecessors.get(&id) else {
+            continue;
+        };
+        if !task_predecessors
+            .iter()
+            .any(|predecessor| affected.contains(predecessor))
+        {
+            continue;
+        }
+
+        let task = dates[&id];
+        let start = task_predecessors
+            .iter()
+            .map(|predecessor| dates[predecessor].end)
+            .fold(task.start, DateTime::max);
+        if start > task.start {
+            let start = calendar.next_working_time(start);
+            let moved = PlannedTask {
+                id,
+                start,
+                end: calendar.add_working_time(start, calendar.working_time(task.start, task.end)),
+            };
+            dates.insert(id, moved);
+            affected.insert(id);
+            shifted.push(moved);
+        }
+    }
+    shifted
+}
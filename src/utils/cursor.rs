@@ -0,0 +1,28 @@
+// Cursores opacos para paginación: un documento BSON serializado y codificado en hex.
+// El cliente solo debe devolver el valor tal cual lo recibió.
+use mongodb::bson::Document;
+
+use crate::errors::AppError;
+
+pub fn encode(cursor: &Document) -> String {
+    let mut bytes = Vec::new();
+    cursor
+        .to_writer(&mut bytes)
+        .expect("Serializar un documento BSON en memoria no puede fallar");
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn decode(cursor: &str) -> Result<Document, AppError> {
+    let invalid = || AppError::ValidationError("Cursor de paginación inválido".to_string());
+
+    if !cursor.len().is_multiple_of(2) || !cursor.is_ascii() {
+        return Err(invalid());
+    }
+    let bytes = (0..cursor.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&cursor[i..i + 2], 16))
+        .collect::<Result<Vec<u8>, _>>()
+        .map_err(|_| invalid())?;
+
+    Document::from_reader(bytes.as_slice()).map_err(|_| invalid())
+}
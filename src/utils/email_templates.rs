@@ -0,0 +1,137 @@
+// Plantillas de los correos de notificación, una por idioma soportado.
+// Los huecos se escriben `{nombre}` y se rellenan con `render`.
+
+use crate::models::{notification_model::NotificationKind, task_model::TaskStatus};
+
+struct Templates {
+    immediate_subject: &'static str,
+    digest_subject: &'static str,
+    greeting: &'static str,
+    digest_intro: &'static str,
+    footer: &'static str,
+    task_assigned: &'static str,
+    mentioned: &'static str,
+    status_changed: &'static str,
+    added_to_project: &'static str,
+    // Por hacer, en curso, hecha y cancelada
+    statuses: [&'static str; 4],
+}
+
+const ES: Templates = Templates {
+    immediate_subject: "[{project}] {line}",
+    digest_subject: "Tu resumen diario de notificaciones ({count})",
+    greeting: "Hola {username},",
+    digest_intro: "Esto es lo que ha pasado desde tu último resumen:",
+    footer: "Puedes cambiar qué correos recibes en las preferencias de notificación.",
+    task_assigned: "{actor} te ha asignado la tarea {task}",
+    mentioned: "{actor} te ha mencionado en un comentario de la tarea {task}",
+    status_changed: "{actor} ha movido la tarea {task} a {status}",
+    added_to_project: "{actor} te ha añadido al proyecto {project}",
+    statuses: ["Por hacer", "En curso", "Hecha", "Cancelada"],
+};
+
+const EN: Templates = Templates {
+    immediate_subject: "[{project}] {line}",
+    digest_subject: "Your daily notification digest ({count})",
+    greeting: "Hi {username},",
+    digest_intro: "Here is what happened since your last digest:",
+    footer: "You can choose which emails you receive in your notification preferences.",
+    task_assigned: "{actor} assigned you the task {task}",
+    mentioned: "{actor} mentioned you in a comment on {task}",
+    status_changed: "{actor} moved the task {task} to {status}",
+    added_to_project: "{actor} added you to the project {project}",
+    statuses: ["To do", "In progress", "Done", "Cancelled"],
+};
+
+// Un idioma desconocido cae en el español
+fn templates(locale: &str) -> &'static Templates {
+    match locale {
+        "en" => &EN,
+        _ => &ES,
+    }
+}
+
+pub fn status_name(locale: &str, status: &TaskStatus) -> &'static str {
+    let statuses = templates(locale).statuses;
+    match status {
+        TaskStatus::ToDo => statuses[0],
+        TaskStatus::InProgress => statuses[1],
+        TaskStatus::Done => statuses[2],
+        TaskStatus::Cancelled => statuses[3],
+    }
+}
+
+pub fn render(template: &str, values: &[(&str, &str)]) -> String {
+    values
+        .iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), value)
+        })
+}
+
+// Datos con los que se describe un aviso en el correo
+pub struct NotificationLine<'a> {
+    pub kind: NotificationKind,
+    pub actor: &'a str,
+    pub project: &'a str,
+    pub task: &'a str,
+    pub status: &'a str,
+}
+
+pub fn notification_line(locale: &str, line: &NotificationLine) -> String {
+    let templates = templates(locale);
+    let template = match line.kind {
+        NotificationKind::TaskAssigned => templates.task_assigned,
+        NotificationKind::Mentioned => templates.mentioned,
+        NotificationKind::StatusChanged => templates.status_changed,
+        NotificationKind::AddedToProject => templates.added_to_project,
+    };
+    render(
+        template,
+        &[
+            ("actor", line.actor),
+            ("project", line.project),
+            ("task", line.task),
+            ("status", line.status),
+        ],
+    )
+}
+
+// Asunto y cuerpo del correo de un único aviso
+pub fn immediate_email(
+    locale: &str,
+    username: &str,
+    project: &str,
+    line: &str,
+) -> (String, String) {
+    let templates = templates(locale);
+    let subject = render(
+        templates.immediate_subject,
+        &[("project", project), ("line", line)],
+    );
+    let body = format!(
+        "{}\n\n{}\n\n{}\n",
+        render(templates.greeting, &[("username", username)]),
+        line,
+        templates.footer
+    );
+    (subject, body)
+}
+
+// Asunto y cuerpo del resumen con varios avisos
+pub fn digest_email(locale: &str, username: &str, lines: &[String]) -> (String, String) {
+    let templates = templates(locale);
+    let subject = render(
+        templates.digest_subject,
+        &[("count", &lines.len().to_string())],
+    );
+    let items: Vec<String> = lines.iter().map(|line| format!("- {}", line)).collect();
+    let body = format!(
+        "{}\n\n{}\n\n{}\n\n{}\n",
+        render(templates.greeting, &[("username", username)]),
+        templates.digest_intro,
+        items.join("\n"),
+        templates.footer
+    );
+    (subject, body)
+}
@@ -0,0 +1,39 @@
+// Control de concurrencia optimista: la versión del documento viaja como ETag
+// y el cliente la devuelve en If-Match al modificarlo.
+use axum::http::{
+    HeaderMap, HeaderName, HeaderValue,
+    header::{ETAG, IF_MATCH},
+};
+
+use crate::errors::AppError;
+
+// Cabecera lista para devolver junto al cuerpo de la respuesta
+pub type ETagHeader = [(HeaderName, HeaderValue); 1];
+
+pub fn etag(version: i64) -> HeaderValue {
+    HeaderValue::from_str(&format!("\"{}\"", version))
+        .expect("Un número entre comillas siempre es un header válido")
+}
+
+// Versión esperada según If-Match; `None` si no se envió o es `*`
+pub fn if_match(headers: &HeaderMap) -> Result<Option<i64>, AppError> {
+    let Some(value) = headers.get(IF_MATCH) else {
+        return Ok(None);
+    };
+    let invalid = || AppError::ValidationError("Cabecera If-Match inválida".to_string());
+
+    let value = value.to_str().map_err(|_| invalid())?.trim();
+    if value == "*" {
+        return Ok(None);
+    }
+    let value = value.strip_prefix("W/").unwrap_or(value);
+    value
+        .trim_matches('"')
+        .parse::<i64>()
+        .map(Some)
+        .map_err(|_| invalid())
+}
+
+pub fn etag_header(version: i64) -> ETagHeader {
+    [(ETAG, etag(version))]
+}
@@ -0,0 +1,84 @@
+// Fragmentos de texto con los términos buscados resaltados en <mark>.
+// El texto se escapa como HTML, así el cliente puede insertar el fragmento tal cual.
+
+// Términos de una búsqueda de texto de MongoDB (sin frases ni exclusiones)
+pub fn search_terms(query: &str) -> Vec<String> {
+    query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| term.chars().count() >= 2)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn escape_html(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '&' => "&amp;".to_string(),
+            '<' => "&lt;".to_string(),
+            '>' => "&gt;".to_string(),
+            '"' => "&quot;".to_string(),
+            '\'' => "&#39;".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+fn lower(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+// Posición (en caracteres) del término que empieza en `i`, si lo hay
+fn match_at(chars: &[char], i: usize, terms: &[Vec<char>]) -> Option<usize> {
+    terms
+        .iter()
+        .filter(|term| i + term.len() <= chars.len())
+        .filter(|term| {
+            term.iter()
+                .zip(&chars[i..i + term.len()])
+                .all(|(a, b)| *a == lower(*b))
+        })
+        .map(|term| term.len())
+        .max()
+}
+
+/// Devuelve un fragmento de como mucho `max_chars` caracteres centrado en la primera
+/// coincidencia, con cada coincidencia envuelta en `<mark>`.
+pub fn snippet(text: &str, terms: &[String], max_chars: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let terms: Vec<Vec<char>> = terms.iter().map(|t| t.chars().collect()).collect();
+
+    let first = (0..chars.len()).find(|&i| match_at(&chars, i, &terms).is_some());
+    let start = match first {
+        Some(first) if chars.len() > max_chars => first
+            .saturating_sub(max_chars / 3)
+            .min(chars.len() - max_chars),
+        _ => 0,
+    };
+    let end = (start + max_chars).min(chars.len());
+
+    let mut result = String::new();
+    if start > 0 {
+        result.push('…');
+    }
+    let mut i = start;
+    while i < end {
+        match match_at(&chars, i, &terms) {
+            Some(len) => {
+                let stop = (i + len).min(end);
+                let word: String = chars[i..stop].iter().collect();
+                result.push_str("<mark>");
+                result.push_str(&escape_html(&word));
+                result.push_str("</mark>");
+                i = stop;
+            }
+            None => {
+                result.push_str(&escape_html(&chars[i].to_string()));
+                i += 1;
+            }
+        }
+    }
+    if end < chars.len() {
+        result.push('…');
+    }
+    result
+}
@@ -0,0 +1,874 @@
+// Lenguaje de consulta al estilo JQL para buscar tareas entre proyectos, p. ej.:
+//   project in (WEB, API) AND status != Done AND assignee = currentUser() ORDER BY priority DESC
+// El parser produce un AST y `compile` lo traduce a un filtro y un orden de MongoDB.
+// Los nombres (claves de proyecto, usuarios) se resuelven antes en el servicio y llegan en
+// `JqlContext`, así esta parte es pura y se puede probar sin base de datos.
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use mongodb::bson::{Bson, DateTime as BsonDateTime, Document, doc, oid::ObjectId};
+use std::{collections::HashMap, fmt};
+
+use crate::errors::AppError;
+
+// Error con la posición (columna, empezando en 1) donde se detectó
+#[derive(Debug, Clone, PartialEq)]
+pub struct JqlError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl JqlError {
+    fn new(position: usize, message: impl Into<String>) -> Self {
+        Self {
+            position,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for JqlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "posición {}: {}", self.position, self.message)
+    }
+}
+
+impl From<JqlError> for AppError {
+    fn from(err: JqlError) -> Self {
+        AppError::ValidationError(format!("Consulta JQL inválida en la {}", err))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Léxico
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Word(String),
+    Quoted(String),
+    Op(Operator),
+    LParen,
+    RParen,
+    Comma,
+    Eof,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    position: usize,
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '.' | '-' | '@' | ':' | '+')
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, JqlError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let position = i + 1;
+
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let (kind, len) = match c {
+            '(' => (TokenKind::LParen, 1),
+            ')' => (TokenKind::RParen, 1),
+            ',' => (TokenKind::Comma, 1),
+            '=' => (TokenKind::Op(Operator::Eq), 1),
+            '~' => (TokenKind::Op(Operator::Contains), 1),
+            '!' => match chars.get(i + 1) {
+                Some('=') => (TokenKind::Op(Operator::NotEq), 2),
+                Some('~') => (TokenKind::Op(Operator::NotContains), 2),
+                _ => return Err(JqlError::new(position, "Se esperaba '!=' o '!~'")),
+            },
+            '>' | '<' => {
+                let or_equal = chars.get(i + 1) == Some(&'=');
+                let op = match (c, or_equal) {
+                    ('>', false) => Operator::Gt,
+                    ('>', true) => Operator::Gte,
+                    ('<', false) => Operator::Lt,
+                    _ => Operator::Lte,
+                };
+                (TokenKind::Op(op), if or_equal { 2 } else { 1 })
+            }
+            '"' | '\'' => {
+                let mut value = String::new();
+                let mut j = i + 1;
+                loop {
+                    match chars.get(j) {
+                        None => {
+                            return Err(JqlError::new(position, "Texto entre comillas sin cerrar"));
+                        }
+                        Some('\\') if j + 1 < chars.len() => {
+                            value.push(chars[j + 1]);
+                            j += 2;
+                        }
+                        Some(&q) if q == c => break,
+                        Some(&other) => {
+                            value.push(other);
+                            j += 1;
+                        }
+                    }
+                }
+                (TokenKind::Quoted(value), j + 1 - i)
+            }
+            c if is_word_char(c) => {
+                let mut j = i;
+                while j < chars.len() && is_word_char(chars[j]) {
+                    j += 1;
+                }
+                (TokenKind::Word(chars[i..j].iter().collect()), j - i)
+            }
+            other => {
+                return Err(JqlError::new(
+                    position,
+                    format!("Carácter inesperado '{}'", other),
+                ));
+            }
+        };
+
+        tokens.push(Token { kind, position });
+        i += len;
+    }
+
+    tokens.push(Token {
+        kind: TokenKind::Eof,
+        position: chars.len() + 1,
+    });
+    Ok(tokens)
+}
+
+// ---------------------------------------------------------------------------
+// AST
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    Project,
+    Status,
+    Priority,
+    Assignee,
+    Reporter,
+    Summary,
+    Description,
+    Text,
+    Created,
+    Updated,
+    Sprint,
+    Epic,
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "project" => Some(Field::Project),
+            "status" => Some(Field::Status),
+            "priority" => Some(Field::Priority),
+            "assignee" => Some(Field::Assignee),
+            "reporter" => Some(Field::Reporter),
+            "summary" | "title" => Some(Field::Summary),
+            "description" => Some(Field::Description),
+            "text" => Some(Field::Text),
+            "created" | "createddate" => Some(Field::Created),
+            "updated" | "updateddate" => Some(Field::Updated),
+            "sprint" => Some(Field::Sprint),
+            "epic" => Some(Field::Epic),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Field::Project => "project",
+            Field::Status => "status",
+            Field::Priority => "priority",
+            Field::Assignee => "assignee",
+            Field::Reporter => "reporter",
+            Field::Summary => "summary",
+            Field::Description => "description",
+            Field::Text => "text",
+            Field::Created => "created",
+            Field::Updated => "updated",
+            Field::Sprint => "sprint",
+            Field::Epic => "epic",
+        }
+    }
+
+    fn is_text(self) -> bool {
+        matches!(self, Field::Summary | Field::Description | Field::Text)
+    }
+
+    fn is_date(self) -> bool {
+        matches!(self, Field::Created | Field::Updated)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operator {
+    Eq,
+    NotEq,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Contains,
+    NotContains,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueKind {
+    Literal(String),
+    Function(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Value {
+    pub kind: ValueKind,
+    pub position: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare {
+        field: Field,
+        op: Operator,
+        value: Value,
+    },
+    In {
+        field: Field,
+        values: Vec<Value>,
+        negated: bool,
+    },
+    Empty {
+        field: Field,
+        negated: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrderField {
+    Created,
+    Updated,
+    Priority,
+    Status,
+    Summary,
+    Rank,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderBy {
+    pub field: OrderField,
+    pub descending: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JqlQuery {
+    pub filter: Option<Expr>,
+    pub order_by: Vec<OrderBy>,
+}
+
+impl JqlQuery {
+    // Valores literales usados con un campo, para resolverlos antes de compilar
+    pub fn literals_for(&self, field: Field) -> Vec<String> {
+        fn walk(expr: &Expr, field: Field, out: &mut Vec<String>) {
+            let values: &[Value] = match expr {
+                Expr::And(a, b) | Expr::Or(a, b) => {
+                    walk(a, field, out);
+                    walk(b, field, out);
+                    return;
+                }
+                Expr::Not(inner) => return walk(inner, field, out),
+                Expr::Compare {
+                    field: f, value, ..
+                } if *f == field => std::slice::from_ref(value),
+                Expr::In {
+                    field: f, values, ..
+                } if *f == field => values,
+                _ => return,
+            };
+            for value in values {
+                if let ValueKind::Literal(text) = &value.kind {
+                    out.push(text.clone());
+                }
+            }
+        }
+
+        let mut out = Vec::new();
+        if let Some(filter) = &self.filter {
+            walk(filter, field, &mut out);
+        }
+        out
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Parser (descenso recursivo)
+// ---------------------------------------------------------------------------
+
+struct Parser {
+    tokens: Vec<Token>,
+    index: usize,
+}
+
+fn is_keyword(word: &str, keyword: &str) -> bool {
+    word.eq_ignore_ascii_case(keyword)
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.index]
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.index].clone();
+        if token.kind != TokenKind::Eof {
+            self.index += 1;
+        }
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(&self.peek().kind, TokenKind::Word(word) if is_keyword(word, keyword))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if self.peek_keyword(keyword) {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), JqlError> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(JqlError::new(
+                self.peek().position,
+                format!("Se esperaba {}", keyword),
+            ))
+        }
+    }
+
+    fn expect(&mut self, kind: TokenKind, description: &str) -> Result<(), JqlError> {
+        if self.peek().kind == kind {
+            self.index += 1;
+            Ok(())
+        } else {
+            Err(JqlError::new(
+                self.peek().position,
+                format!("Se esperaba {}", description),
+            ))
+        }
+    }
+
+    fn parse_query(&mut self) -> Result<JqlQuery, JqlError> {
+        let filter = if self.peek_keyword("ORDER") || self.peek().kind == TokenKind::Eof {
+            None
+        } else {
+            Some(self.parse_or()?)
+        };
+
+        let mut order_by = Vec::new();
+        if self.eat_keyword("ORDER") {
+            self.expect_keyword("BY")?;
+            loop {
+                order_by.push(self.parse_order_item()?);
+                if self.peek().kind != TokenKind::Comma {
+                    break;
+                }
+                self.index += 1;
+            }
+        }
+
+        let token = self.peek();
+        if token.kind != TokenKind::Eof {
+            return Err(JqlError::new(
+                token.position,
+                "Se esperaba AND, OR, ORDER BY o el final de la consulta",
+            ));
+        }
+
+        Ok(JqlQuery { filter, order_by })
+    }
+
+    fn parse_order_item(&mut self) -> Result<OrderBy, JqlError> {
+        let token = self.next();
+        let TokenKind::Word(name) = &token.kind else {
+            return Err(JqlError::new(
+                token.position,
+                "Se esperaba un campo de orden",
+            ));
+        };
+        let field = match name.to_lowercase().as_str() {
+            "created" | "createddate" => OrderField::Created,
+            "updated" | "updateddate" => OrderField::Updated,
+            "priority" => OrderField::Priority,
+            "status" => OrderField::Status,
+            "summary" | "title" => OrderField::Summary,
+            "rank" => OrderField::Rank,
+            _ => {
+                return Err(JqlError::new(
+                    token.position,
+                    format!("No se puede ordenar por '{}'", name),
+                ));
+            }
+        };
+
+        let descending = if self.eat_keyword("DESC") {
+            true
+        } else {
+            self.eat_keyword("ASC");
+            false
+        };
+        Ok(OrderBy { field, descending })
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, JqlError> {
+        let mut expr = self.parse_and()?;
+        while self.eat_keyword("OR") {
+            let right = self.parse_and()?;
+            expr = Expr::Or(Box::new(expr), Box::new(right));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, JqlError> {
+        let mut expr = self.parse_unary()?;
+        while self.eat_keyword("AND") {
+            let right = self.parse_unary()?;
+            expr = Expr::And(Box::new(expr), Box::new(right));
+        }
+        Ok(expr)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, JqlError> {
+        if self.eat_keyword("NOT") {
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+        if self.peek().kind == TokenKind::LParen {
+            self.index += 1;
+            let expr = self.parse_or()?;
+            self.expect(TokenKind::RParen, "')'")?;
+            return Ok(expr);
+        }
+        self.parse_clause()
+    }
+
+    fn parse_clause(&mut self) -> Result<Expr, JqlError> {
+        let token = self.next();
+        let TokenKind::Word(name) = &token.kind else {
+            return Err(JqlError::new(token.position, "Se esperaba un campo"));
+        };
+        let field = Field::parse(name).ok_or_else(|| {
+            JqlError::new(token.position, format!("Campo desconocido '{}'", name))
+        })?;
+
+        // field IS [NOT] EMPTY
+        if self.eat_keyword("IS") {
+            let negated = self.eat_keyword("NOT");
+            if !(self.eat_keyword("EMPTY") || self.eat_keyword("NULL")) {
+                return Err(JqlError::new(self.peek().position, "Se esperaba EMPTY"));
+            }
+            return Ok(Expr::Empty { field, negated });
+        }
+
+        // field [NOT] IN (a, b)
+        let negated = self.eat_keyword("NOT");
+        if self.eat_keyword("IN") {
+            let values = self.parse_list()?;
+            return Ok(Expr::In {
+                field,
+                values,
+                negated,
+            });
+        }
+        if negated {
+            return Err(JqlError::new(self.peek().position, "Se esperaba IN"));
+        }
+
+        let op_token = self.next();
+        let TokenKind::Op(op) = op_token.kind else {
+            return Err(JqlError::new(op_token.position, "Se esperaba un operador"));
+        };
+        Self::check_operator(field, op, op_token.position)?;
+
+        let value = self.parse_value()?;
+        Ok(Expr::Compare { field, op, value })
+    }
+
+    fn check_operator(field: Field, op: Operator, position: usize) -> Result<(), JqlError> {
+        let allowed = match op {
+            Operator::Eq | Operator::NotEq => !field.is_date(),
+            Operator::Contains | Operator::NotContains => field.is_text(),
+            Operator::Gt | Operator::Gte | Operator::Lt | Operator::Lte => field.is_date(),
+        };
+        if allowed {
+            Ok(())
+        } else {
+            Err(JqlError::new(
+                position,
+                format!("Operador no válido para el campo '{}'", field.name()),
+            ))
+        }
+    }
+
+    fn parse_list(&mut self) -> Result<Vec<Value>, JqlError> {
+        // Se admite también una función que devuelve una lista: sprint in openSprints()
+        if self.peek().kind != TokenKind::LParen {
+            return Ok(vec![self.parse_value()?]);
+        }
+        self.index += 1;
+
+        let mut values = vec![self.parse_value()?];
+        while self.peek().kind == TokenKind::Comma {
+            self.index += 1;
+            values.push(self.parse_value()?);
+        }
+        self.expect(TokenKind::RParen, "')' o ','")?;
+        Ok(values)
+    }
+
+    fn parse_value(&mut self) -> Result<Value, JqlError> {
+        let token = self.next();
+        let kind = match token.kind {
+            TokenKind::Quoted(text) => ValueKind::Literal(text),
+            TokenKind::Word(word) => {
+                if self.peek().kind == TokenKind::LParen {
+                    self.index += 1;
+                    self.expect(TokenKind::RParen, "')'")?;
+                    ValueKind::Function(word)
+                } else {
+                    ValueKind::Literal(word)
+                }
+            }
+            _ => return Err(JqlError::new(token.position, "Se esperaba un valor")),
+        };
+        Ok(Value {
+            kind,
+            position: token.position,
+        })
+    }
+}
+
+pub fn parse(input: &str) -> Result<JqlQuery, JqlError> {
+    let tokens = tokenize(input)?;
+    Parser { tokens, index: 0 }.parse_query()
+}
+
+// ---------------------------------------------------------------------------
+// Compilación a MongoDB
+// ---------------------------------------------------------------------------
+
+// Datos ya resueltos por el servicio. Las claves de los mapas van en minúsculas.
+pub struct JqlContext {
+    pub current_user: ObjectId,
+    pub now: DateTime<Utc>,
+    // Clave de proyecto o ID en hex -> ID (solo proyectos accesibles)
+    pub projects: HashMap<String, ObjectId>,
+    // Nombre de usuario o email -> ID
+    pub users: HashMap<String, ObjectId>,
+    pub open_sprints: Vec<ObjectId>,
+}
+
+pub struct CompiledQuery {
+    pub filter: Document,
+    // Campos para $addFields (claves calculadas) y el $sort final
+    pub sort_fields: Document,
+    pub sort: Document,
+}
+
+const PRIORITIES: [&str; 4] = ["Low", "Medium", "High", "Urgent"];
+const STATUSES: [&str; 4] = ["ToDo", "InProgress", "Done", "Cancelled"];
+
+fn storage_field(field: Field) -> &'static str {
+    match field {
+        Field::Project => "project_id",
+        Field::Status => "status",
+        Field::Priority => "priority",
+        Field::Assignee => "assignee_id",
+        Field::Reporter => "reporter_id",
+        Field::Summary => "title",
+        Field::Description => "description",
+        Field::Text => "title",
+        Field::Created => "created_at",
+        Field::Updated => "updated_at",
+        Field::Sprint => "sprint_id",
+        Field::Epic => "epic_id",
+    }
+}
+
+fn enum_value(options: &[&str], value: &Value, label: &str) -> Result<Bson, JqlError> {
+    let ValueKind::Literal(text) = &value.kind else {
+        return Err(JqlError::new(value.position, "Función no válida aquí"));
+    };
+    let normalized: String = text
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '_')
+        .collect::<String>()
+        .to_lowercase();
+    options
+        .iter()
+        .find(|option| option.to_lowercase() == normalized)
+        .map(|option| Bson::String(option.to_string()))
+        .ok_or_else(|| JqlError::new(value.position, format!("{} desconocido '{}'", label, text)))
+}
+
+fn parse_date(value: &Value, now: DateTime<Utc>) -> Result<DateTime<Utc>, JqlError> {
+    let invalid = |text: &str| {
+        JqlError::new(
+            value.position,
+            format!(
+                "Fecha inválida '{}' (usa AAAA-MM-DD, RFC 3339 o relativa como -7d)",
+                text
+            ),
+        )
+    };
+
+    let text = match &value.kind {
+        ValueKind::Function(name) if name.eq_ignore_ascii_case("now") => return Ok(now),
+        ValueKind::Function(name) => {
+            return Err(JqlError::new(
+                value.position,
+                format!("Función desconocida '{}()'", name),
+            ));
+        }
+        ValueKind::Literal(text) => text.as_str(),
+    };
+
+    // Fechas relativas: -7d, +2w, -3h, -30m
+    if let Some(sign) = text.chars().next().filter(|c| *c == '-' || *c == '+') {
+        if !text.is_ascii() || text.len() < 3 {
+            return Err(invalid(text));
+        }
+        let (amount, unit) = text[1..].split_at(text.len().saturating_sub(2));
+        let amount: i64 = amount.parse().map_err(|_| invalid(text))?;
+        let duration = match unit {
+            "m" => Duration::try_minutes(amount),
+            "h" => Duration::try_hours(amount),
+            "d" => Duration::try_days(amount),
+            "w" => Duration::try_weeks(amount),
+            _ => return Err(invalid(text)),
+        };
+        // //! Una cantidad enorme no cabe en una duración o saca la fecha de rango
+        let date = duration.and_then(|duration| {
+            if sign == '-' {
+                now.checked_sub_signed(duration)
+            } else {
+                now.checked_add_signed(duration)
+            }
+        });
+        return date.ok_or_else(|| {
+            JqlError::new(
+                value.position,
+                format!("La fecha relativa '{}' está fuera de rango", text),
+            )
+        });
+    }
+
+    if let Ok(date) = DateTime::parse_from_rfc3339(text) {
+        return Ok(date.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .map(|date| date.and_hms_opt(0, 0, 0).unwrap().and_utc())
+        .map_err(|_| invalid(text))
+}
+
+impl JqlContext {
+    fn object_id(&self, field: Field, value: &Value) -> Result<Vec<Bson>, JqlError> {
+        let text = match &value.kind {
+            ValueKind::Function(name) => {
+                return match (field, name.to_lowercase().as_str()) {
+                    (Field::Assignee | Field::Reporter, "currentuser") => {
+                        Ok(vec![Bson::ObjectId(self.current_user)])
+                    }
+                    (Field::Sprint, "opensprints") => Ok(self
+                        .open_sprints
+                        .iter()
+                        .copied()
+                        .map(Bson::ObjectId)
+                        .collect()),
+                    _ => Err(JqlError::new(
+                        value.position,
+                        format!("Función desconocida '{}()'", name),
+                    )),
+                };
+            }
+            ValueKind::Literal(text) => text,
+        };
+
+        let resolved = match field {
+            Field::Project => self.projects.get(&text.to_lowercase()).copied(),
+            Field::Assignee | Field::Reporter => ObjectId::parse_str(text)
+                .ok()
+                .or_else(|| self.users.get(&text.to_lowercase()).copied()),
+            _ => ObjectId::parse_str(text).ok(),
+        };
+
+        resolved.map(|id| vec![Bson::ObjectId(id)]).ok_or_else(|| {
+            let message = match field {
+                Field::Project => format!("Proyecto desconocido o sin acceso '{}'", text),
+                Field::Assignee | Field::Reporter => format!("Usuario desconocido '{}'", text),
+                _ => format!("ID inválido '{}'", text),
+            };
+            JqlError::new(value.position, message)
+        })
+    }
+
+    fn values(&self, field: Field, value: &Value) -> Result<Vec<Bson>, JqlError> {
+        match field {
+            Field::Status => Ok(vec![enum_value(&STATUSES, value, "Estado")?]),
+            Field::Priority => Ok(vec![enum_value(&PRIORITIES, value, "Prioridad")?]),
+            Field::Summary | Field::Description | Field::Text => match &value.kind {
+                ValueKind::Literal(text) => Ok(vec![Bson::String(text.clone())]),
+                ValueKind::Function(_) => {
+                    Err(JqlError::new(value.position, "Función no válida aquí"))
+                }
+            },
+            Field::Created | Field::Updated => Err(JqlError::new(
+                value.position,
+                "Las fechas solo admiten >, >=, < y <=",
+            )),
+            _ => self.object_id(field, value),
+        }
+    }
+
+    fn text_condition(field: Field, condition: Bson) -> Document {
+        if field == Field::Text {
+            doc! {"$or": [
+                {"title": condition.clone()},
+                {"description": condition},
+            ]}
+        } else {
+            doc! {storage_field(field): condition}
+        }
+    }
+
+    fn compile_expr(&self, expr: &Expr) -> Result<Document, JqlError> {
+        match expr {
+            Expr::And(a, b) => Ok(doc! {"$and": [self.compile_expr(a)?, self.compile_expr(b)?]}),
+            Expr::Or(a, b) => Ok(doc! {"$or": [self.compile_expr(a)?, self.compile_expr(b)?]}),
+            Expr::Not(inner) => Ok(doc! {"$nor": [self.compile_expr(inner)?]}),
+            Expr::Empty { field, negated } => {
+                let condition = if *negated {
+                    doc! {"$nin": [Bson::Null, ""]}
+                } else {
+                    doc! {"$in": [Bson::Null, ""]}
+                };
+                Ok(Self::text_condition(*field, Bson::Document(condition)))
+            }
+            Expr::In {
+                field,
+                values,
+                negated,
+            } => {
+                let mut resolved = Vec::new();
+                for value in values {
+                    resolved.extend(self.values(*field, value)?);
+                }
+                let op = if *negated { "$nin" } else { "$in" };
+                Ok(Self::text_condition(
+                    *field,
+                    Bson::Document(doc! {op: resolved}),
+                ))
+            }
+            Expr::Compare { field, op, value } => self.compile_compare(*field, *op, value),
+        }
+    }
+
+    fn compile_compare(
+        &self,
+        field: Field,
+        op: Operator,
+        value: &Value,
+    ) -> Result<Document, JqlError> {
+        let condition = match op {
+            Operator::Eq | Operator::NotEq => {
+                let mut values = self.values(field, value)?;
+                // Una función puede devolver varios valores (openSprints())
+                let single = values.len() == 1;
+                match (op, single) {
+                    (Operator::Eq, true) => values.remove(0),
+                    (Operator::Eq, false) => Bson::Document(doc! {"$in": values}),
+                    (_, true) => Bson::Document(doc! {"$ne": values.remove(0)}),
+                    (_, false) => Bson::Document(doc! {"$nin": values}),
+                }
+            }
+            Operator::Contains | Operator::NotContains => {
+                let ValueKind::Literal(text) = &value.kind else {
+                    return Err(JqlError::new(value.position, "Función no válida aquí"));
+                };
+                let regex = doc! {"$regex": regex::escape(text), "$options": "i"};
+                if op == Operator::Contains {
+                    Bson::Document(regex)
+                } else {
+                    Bson::Document(doc! {"$not": regex})
+                }
+            }
+            Operator::Gt | Operator::Gte | Operator::Lt | Operator::Lte => {
+                let date = BsonDateTime::from_chrono(parse_date(value, self.now)?);
+                let op = match op {
+                    Operator::Gt => "$gt",
+                    Operator::Gte => "$gte",
+                    Operator::Lt => "$lt",
+                    _ => "$lte",
+                };
+                Bson::Document(doc! {op: date})
+            }
+        };
+
+        // "text !~ x" debe excluir la tarea si aparece en cualquiera de los dos campos
+        if field == Field::Text && op == Operator::NotContains {
+            return Ok(doc! {"title": condition.clone(), "description": condition});
+        }
+        Ok(Self::text_condition(field, condition))
+    }
+}
+
+pub fn compile(query: &JqlQuery, context: &JqlContext) -> Result<CompiledQuery, JqlError> {
+    let filter = match &query.filter {
+        Some(expr) => context.compile_expr(expr)?,
+        None => doc! {},
+    };
+
+    let mut sort_fields = doc! {};
+    let mut sort = doc! {};
+    for (index, order) in query.order_by.iter().enumerate() {
+        let direction = if order.descending { -1 } else { 1 };
+        let key = format!("_sort_{}", index);
+        let expression = match order.field {
+            OrderField::Created => Bson::String("$created_at".to_string()),
+            OrderField::Updated => Bson::String("$updated_at".to_string()),
+            OrderField::Rank => Bson::String("$rank".to_string()),
+            OrderField::Summary => Bson::Document(doc! {"$toLower": "$title"}),
+            OrderField::Priority => {
+                Bson::Document(doc! {"$indexOfArray": [PRIORITIES.to_vec(), "$priority"]})
+            }
+            OrderField::Status => {
+                Bson::Document(doc! {"$indexOfArray": [STATUSES.to_vec(), "$status"]})
+            }
+        };
+        sort_fields.insert(key.clone(), expression);
+        sort.insert(key, direction);
+    }
+    if sort.is_empty() {
+        sort.insert("created_at", -1);
+    }
+    sort.insert("_id", 1);
+
+    Ok(CompiledQuery {
+        filter,
+        sort_fields,
+        sort,
+    })
+}
@@ -0,0 +1,101 @@
+// Ranking lexicográfico al estilo LexoRank: cada rango es una cadena en base 36
+// y el orden de las tareas es el orden lexicográfico de sus rangos. Insertar entre
+// dos vecinos solo requiere calcular una cadena intermedia, sin tocar al resto.
+use crate::errors::AppError;
+
+const ALPHABET: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+const BASE: u32 = 36;
+
+// Por encima de esta longitud conviene redistribuir los rangos del proyecto
+pub const MAX_RANK_LENGTH: usize = 12;
+
+fn digit_value(c: u8) -> Option<u32> {
+    match c {
+        b'0'..=b'9' => Some((c - b'0') as u32),
+        b'a'..=b'z' => Some((c - b'a') as u32 + 10),
+        _ => None,
+    }
+}
+
+fn digit_char(value: u32) -> char {
+    ALPHABET[value as usize] as char
+}
+
+pub fn is_valid_rank(rank: &str) -> bool {
+    !rank.is_empty() && !rank.ends_with('0') && rank.bytes().all(|c| digit_value(c).is_some())
+}
+
+pub fn needs_rebalance(rank: &str) -> bool {
+    rank.is_empty() || rank.len() > MAX_RANK_LENGTH
+}
+
+/// Calcula un rango estrictamente entre `prev` y `next`. `None` significa sin
+/// límite por ese lado (inicio o final de la lista).
+pub fn between(prev: Option<&str>, next: Option<&str>) -> Result<String, AppError> {
+    let prev = prev.unwrap_or("").as_bytes();
+    let next = next.map(str::as_bytes);
+
+    if let Some(next) = next
+        && prev >= next
+    {
+        return Err(AppError::ValidationError(
+            "El rango anterior debe ser menor que el siguiente".to_string(),
+        ));
+    }
+
+    let mut result = String::new();
+    // Mientras el límite superior siga vigente, se compara dígito a dígito con él
+    let mut upper = next;
+    let mut i = 0;
+
+    loop {
+        let low = prev.get(i).and_then(|c| digit_value(*c)).unwrap_or(0);
+        let high = match upper {
+            Some(bound) => match bound.get(i) {
+                Some(c) => digit_value(*c).ok_or_else(|| {
+                    AppError::ValidationError("Rango con caracteres inválidos".to_string())
+                })?,
+                None => BASE,
+            },
+            None => BASE,
+        };
+
+        if high > low + 1 {
+            result.push(digit_char((low + high) / 2));
+            return Ok(result);
+        }
+
+        // Sin hueco en esta posición: se copia el dígito bajo y a partir de aquí
+        // basta con superar a `prev`, ya que el resultado queda por debajo de `next`
+        result.push(digit_char(low));
+        if high != low {
+            upper = None;
+        }
+        i += 1;
+    }
+}
+
+/// Genera `count` rangos cortos y equiespaciados, para crear o redistribuir listas.
+pub fn evenly_spaced(count: usize) -> Vec<String> {
+    let mut length = 2;
+    while (BASE as u128).pow(length) < (count as u128 + 1) * 64 {
+        length += 1;
+    }
+
+    let space = (BASE as u128).pow(length);
+    let step = space / (count as u128 + 1);
+
+    (1..=count as u128)
+        .map(|i| {
+            let mut value = step * i;
+            let mut digits = vec!['0'; length as usize];
+            for slot in digits.iter_mut().rev() {
+                *slot = digit_char((value % BASE as u128) as u32);
+                value /= BASE as u128;
+            }
+            // Los ceros finales no aportan orden y dificultan insertar justo después
+            let rank: String = digits.into_iter().collect();
+            rank.trim_end_matches('0').to_string()
+        })
+        .collect()
+}
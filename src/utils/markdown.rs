@@ -0,0 +1,145 @@
+// Markdown (CommonMark, con tablas y tachado) de descripciones y comentarios.
+// Se guarda el texto original junto al HTML ya saneado, así todos los clientes muestran lo
+// mismo sin tener que confiar en su propio renderizado. Las claves de tarea (`WEB-12`) y las
+// menciones a miembros del proyecto se convierten en enlaces.
+
+use ammonia::Builder;
+use pulldown_cmark::{CowStr, Event, Options, Parser, Tag, TagEnd, TextMergeStream, html};
+use regex::Regex;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::LazyLock,
+};
+
+use crate::{models::user_model::UserData, utils::mentions::mention_spans};
+
+// Etiquetas y atributos permitidos en el HTML resultante; el resto se elimina
+const ALLOWED_TAGS: &[&str] = &[
+    "a",
+    "blockquote",
+    "br",
+    "code",
+    "del",
+    "em",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "img",
+    "li",
+    "ol",
+    "p",
+    "pre",
+    "strong",
+    "table",
+    "tbody",
+    "td",
+    "th",
+    "thead",
+    "tr",
+    "ul",
+];
+
+static SANITIZER: LazyLock<Builder<'static>> = LazyLock::new(|| {
+    let mut builder = Builder::default();
+    builder
+        .tags(ALLOWED_TAGS.iter().copied().collect())
+        .tag_attributes(HashMap::from([
+            ("a", HashSet::from(["href", "title"])),
+            ("img", HashSet::from(["src", "alt", "title"])),
+            ("ol", HashSet::from(["start"])),
+        ]))
+        .generic_attributes(HashSet::new())
+        .allowed_classes(HashMap::from([(
+            "a",
+            HashSet::from(["issue-key", "mention"]),
+        )]))
+        .url_schemes(HashSet::from(["http", "https", "mailto"]));
+    builder
+});
+
+// Claves con el formato de `PROYECTO-n`; se descartan las que no llevan ninguna letra
+// (`2024-10` es una fecha, no una tarea)
+static ISSUE_KEY_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b[A-Z0-9]{2,10}-[0-9]+\b").expect("Invalid regex pattern"));
+
+/// Convierte `source` a HTML saneado. `members` son los miembros del proyecto que pueden
+/// aparecer mencionados; las menciones a cualquier otro nombre se dejan como texto.
+pub fn render_markdown(source: &str, members: &[UserData]) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+
+    // //? Dentro de enlaces y bloques de código el texto se deja tal cual. El parser parte el
+    // //? texto en los posibles delimitadores (`_`, `*`...), así que antes se vuelve a unir.
+    let mut verbatim_depth = 0usize;
+    let events =
+        TextMergeStream::new(Parser::new_ext(source, options)).flat_map(|event| match event {
+            Event::Start(Tag::Link { .. } | Tag::CodeBlock(_)) => {
+                verbatim_depth += 1;
+                vec![event]
+            }
+            Event::End(TagEnd::Link | TagEnd::CodeBlock) => {
+                verbatim_depth = verbatim_depth.saturating_sub(1);
+                vec![event]
+            }
+            Event::Text(text) if verbatim_depth == 0 => autolink(&text, members),
+            event => vec![event],
+        });
+
+    let mut rendered = String::new();
+    html::push_html(&mut rendered, events);
+    SANITIZER.clean(&rendered).to_string()
+}
+
+// Trocea un texto en partes literales y enlaces a tareas y miembros mencionados
+fn autolink(text: &str, members: &[UserData]) -> Vec<Event<'static>> {
+    let mut links: Vec<(usize, usize, String)> = ISSUE_KEY_REGEX
+        .find_iter(text)
+        .filter(|key| key.as_str().contains(|c: char| c.is_ascii_uppercase()))
+        .map(|key| {
+            let html = format!(
+                r#"<a class="issue-key" href="/browse/{0}">{0}</a>"#,
+                key.as_str()
+            );
+            (key.start(), key.end(), html)
+        })
+        .collect();
+
+    for (start, username) in mention_spans(text) {
+        let Some(member) = members
+            .iter()
+            .find(|member| member.username.eq_ignore_ascii_case(username))
+        else {
+            continue;
+        };
+        // Los nombres solo llevan letras, números, `_`, `.` y `-`: no hace falta escaparlos
+        let html = format!(
+            r#"<a class="mention" href="/users/{}">@{}</a>"#,
+            member.id, username
+        );
+        links.push((start, start + 1 + username.len(), html));
+    }
+    links.sort_by_key(|(start, _, _)| *start);
+
+    let mut events = Vec::new();
+    let mut position = 0;
+    for (start, end, html) in links {
+        // Un enlace que se solapa con el anterior (`@ana-WEB-1`) se descarta
+        if start < position {
+            continue;
+        }
+        if start > position {
+            events.push(Event::Text(CowStr::from(text[position..start].to_string())));
+        }
+        events.push(Event::InlineHtml(CowStr::from(html)));
+        position = end;
+    }
+    if position < text.len() {
+        events.push(Event::Text(CowStr::from(text[position..].to_string())));
+    }
+    events
+}
@@ -0,0 +1,45 @@
+// Menciones `@usuario` dentro de un texto libre.
+// Una mención empieza por `@` al principio del texto o tras un carácter que no forma parte
+// de un nombre (así `correo@dominio` no cuenta) y sigue con letras, números, `_`, `.` o `-`.
+
+fn is_username_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '.' | '-')
+}
+
+// Menciones del texto con la posición de su `@`, en el orden en que aparecen
+pub fn mention_spans(text: &str) -> Vec<(usize, &str)> {
+    let mut spans = Vec::new();
+    let mut previous: Option<char> = None;
+
+    for (index, c) in text.char_indices() {
+        let starts_mention = c == '@' && !previous.is_some_and(is_username_char);
+        previous = Some(c);
+        if !starts_mention {
+            continue;
+        }
+
+        let rest = &text[index + 1..];
+        let end = rest
+            .char_indices()
+            .find(|(_, c)| !is_username_char(*c))
+            .map_or(rest.len(), |(end, _)| end);
+        // Un punto o guion final es puntuación de la frase, no parte del nombre
+        let username = rest[..end].trim_end_matches(['.', '-']);
+        if !username.is_empty() {
+            spans.push((index, username));
+        }
+    }
+
+    spans
+}
+
+// Nombres mencionados, sin repetir y en el orden en que aparecen
+pub fn extract_mentions(text: &str) -> Vec<String> {
+    let mut mentions: Vec<String> = Vec::new();
+    for (_, username) in mention_spans(text) {
+        if !mentions.iter().any(|m| m.eq_ignore_ascii_case(username)) {
+            mentions.push(username.to_string());
+        }
+    }
+    mentions
+}
@@ -3,3 +3,14 @@ use std::sync::LazyLock;
 
 pub static KEY_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^[A-Z0-9]+$").expect("Invalid regex pattern"));
+
+pub static CUSTOM_FIELD_KEY_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[a-z][a-z0-9_]{0,31}$").expect("Invalid regex pattern"));
+
+// Idiomas en los que se envían los correos (ver `SUPPORTED_LOCALES`)
+pub static LOCALE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(es|en)$").expect("Invalid regex pattern"));
+
+// Código corto de emoji; se usa como nombre de campo en MongoDB, así que sin '.' ni '$'
+pub static REACTION_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[a-z0-9_+\-]{1,32}$").expect("Invalid regex pattern"));